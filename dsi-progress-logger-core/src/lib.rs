@@ -0,0 +1,378 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![doc = include_str!("../README.md")]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter, Result};
+use core::time::Duration;
+
+/// A monotonic clock.
+///
+/// The clock is implemented by closures returning a [`Duration`], so it is
+/// usually sufficient to wrap a hardware timer into a closure.
+pub trait Clock {
+    /// Return the time elapsed since an arbitrary instant, which must be the
+    /// same for all calls.
+    fn now(&mut self) -> Duration;
+}
+
+impl<F: FnMut() -> Duration> Clock for F {
+    fn now(&mut self) -> Duration {
+        self()
+    }
+}
+
+/// A counter telling when it is time to log.
+///
+/// This is the counting core of `dsi-progress-logger`: it keeps track of the
+/// count and of the time, but logging is left to the caller, which receives a
+/// [`ProgressSnapshot`] from [`update`](Self::update),
+/// [`update_with_count`](Self::update_with_count), and
+/// [`light_update`](Self::light_update) when the [log
+/// interval](Self::log_interval) has passed, and from [`done`](Self::done).
+///
+/// Setters return `&mut Self`, so they can be chained as in
+/// `dsi-progress-logger`.
+#[derive(Debug, Clone)]
+pub struct ProgressCounter<C> {
+    /// The clock.
+    clock: C,
+    /// The name of an item.
+    item_name: String,
+    /// The log interval.
+    log_interval: Duration,
+    /// The expected number of updates.
+    expected_updates: Option<usize>,
+    /// The count.
+    count: usize,
+    /// The time at which the counter was started.
+    start_time: Option<Duration>,
+    /// The time at which the counter was stopped.
+    stop_time: Option<Duration>,
+    /// The time after which the next snapshot will be returned.
+    next_log_time: Duration,
+}
+
+impl<C: Clock> ProgressCounter<C> {
+    /// Calls to [`light_update`](Self::light_update) will read the clock only
+    /// if the current count is a multiple of this mask plus one.
+    ///
+    /// Note that this constant is significantly smaller than the one used by
+    /// `dsi-progress-logger`, as updates on embedded targets are usually
+    /// slower.
+    pub const LIGHT_UPDATE_MASK: usize = (1 << 10) - 1;
+
+    /// Create a new counter using the given clock, with item name `item` and
+    /// log interval of ten seconds.
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            item_name: String::from("item"),
+            log_interval: Duration::from_secs(10),
+            expected_updates: None,
+            count: 0,
+            start_time: None,
+            stop_time: None,
+            next_log_time: Duration::ZERO,
+        }
+    }
+
+    /// Set the name of an item.
+    pub fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.item_name = String::from(item_name.as_ref());
+        self
+    }
+
+    /// Set the log interval.
+    pub fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.log_interval = log_interval;
+        self
+    }
+
+    /// Set the expected number of updates.
+    ///
+    /// If set, snapshots will contain the percentage of completion and an
+    /// estimate of the time to completion.
+    pub fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.expected_updates = expected_updates;
+        self
+    }
+
+    /// Start the counter, resetting the count.
+    pub fn start(&mut self) {
+        let now = self.clock.now();
+        self.count = 0;
+        self.start_time = Some(now);
+        self.stop_time = None;
+        self.next_log_time = now + self.log_interval;
+    }
+
+    /// Increase the count and return a snapshot if it is time to log.
+    ///
+    /// This method reads the clock at each call.
+    pub fn update(&mut self) -> Option<ProgressSnapshot<'_>> {
+        self.update_with_count(1)
+    }
+
+    /// Increase the count by the given amount and return a snapshot if it is
+    /// time to log.
+    ///
+    /// This method reads the clock at each call.
+    pub fn update_with_count(&mut self, count: usize) -> Option<ProgressSnapshot<'_>> {
+        self.count = self.count.saturating_add(count);
+        self.check_log_time()
+    }
+
+    /// Increase the count and, if the new count is a multiple of
+    /// [`LIGHT_UPDATE_MASK`](Self::LIGHT_UPDATE_MASK) plus one, return a
+    /// snapshot if it is time to log.
+    ///
+    /// This method should be used when updates are too frequent to read the
+    /// clock at each call.
+    pub fn light_update(&mut self) -> Option<ProgressSnapshot<'_>> {
+        self.count = self.count.saturating_add(1);
+        if self.count & Self::LIGHT_UPDATE_MASK == 0 {
+            self.check_log_time()
+        } else {
+            None
+        }
+    }
+
+    /// Stop the counter and return the final snapshot.
+    pub fn done(&mut self) -> ProgressSnapshot<'_> {
+        let now = self.clock.now();
+        self.stop_time.get_or_insert(now);
+        self.snapshot_at(now)
+    }
+
+    /// Return the count.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Return a snapshot of the current state.
+    pub fn snapshot(&mut self) -> ProgressSnapshot<'_> {
+        let now = self.clock.now();
+        self.snapshot_at(now)
+    }
+
+    /// Return a snapshot if it is time to log, and set the next log time.
+    fn check_log_time(&mut self) -> Option<ProgressSnapshot<'_>> {
+        if self.start_time.is_none() || self.stop_time.is_some() {
+            return None;
+        }
+        let now = self.clock.now();
+        if now < self.next_log_time {
+            return None;
+        }
+        self.next_log_time = now + self.log_interval;
+        Some(self.snapshot_at(now))
+    }
+
+    /// Return a snapshot of the state at the given time.
+    fn snapshot_at(&self, now: Duration) -> ProgressSnapshot<'_> {
+        let end = self.stop_time.unwrap_or(now);
+        ProgressSnapshot {
+            item_name: &self.item_name,
+            count: self.count,
+            expected_updates: self.expected_updates,
+            elapsed: self
+                .start_time
+                .map_or(Duration::ZERO, |start| end.saturating_sub(start)),
+            stopped: self.stop_time.is_some(),
+        }
+    }
+}
+
+/// A snapshot of the state of a [`ProgressCounter`].
+///
+/// The [`Display`] implementation has a layout similar to that of the logs of
+/// `dsi-progress-logger`, except that numbers are not localized and that the
+/// item name is not pluralized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSnapshot<'a> {
+    /// The name of an item.
+    pub item_name: &'a str,
+    /// The count.
+    pub count: usize,
+    /// The expected number of updates, if set.
+    pub expected_updates: Option<usize>,
+    /// The elapsed time since the counter was started (up to the time it was
+    /// stopped, if it is stopped).
+    pub elapsed: Duration,
+    /// Whether the counter has been stopped.
+    pub stopped: bool,
+}
+
+impl ProgressSnapshot<'_> {
+    /// Return the number of items per second, or `None` if no time has
+    /// elapsed.
+    pub fn items_per_second(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (secs != 0.0).then(|| self.count as f64 / secs)
+    }
+
+    /// Return the percentage of completion, or `None` if the expected number
+    /// of updates is not set.
+    pub fn percent(&self) -> Option<f64> {
+        self.expected_updates
+            .filter(|&expected| expected != 0)
+            .map(|expected| 100.0 * self.count as f64 / expected as f64)
+    }
+
+    /// Return the estimated time to completion, or `None` if the expected
+    /// number of updates is not set or if no items have been counted.
+    pub fn eta(&self) -> Option<Duration> {
+        let expected = self.expected_updates?;
+        if self.count == 0 {
+            return None;
+        }
+        let remaining = expected.saturating_sub(self.count) as f64;
+        Some(Duration::from_secs_f64(
+            remaining * self.elapsed.as_secs_f64() / self.count as f64,
+        ))
+    }
+}
+
+impl Display for ProgressSnapshot<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.stopped {
+            write!(
+                f,
+                "Elapsed: {:.2}s [{} {}",
+                self.elapsed.as_secs_f64(),
+                self.count,
+                self.item_name
+            )?;
+        } else {
+            write!(
+                f,
+                "{} {}, {:.2}s",
+                self.count,
+                self.item_name,
+                self.elapsed.as_secs_f64()
+            )?;
+        }
+        if let Some(speed) = self.items_per_second() {
+            write!(f, ", {:.2} {}/s", speed, self.item_name)?;
+        }
+        if self.stopped {
+            return write!(f, "]");
+        }
+        if let (Some(percent), Some(eta)) = (self.percent(), self.eta()) {
+            write!(
+                f,
+                "; {:.2}% done, {:.2}s to end",
+                percent,
+                eta.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ProgressSnapshot<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        if self.stopped {
+            defmt::write!(
+                f,
+                "Elapsed: {=f64}s [{=usize} {=str}",
+                self.elapsed.as_secs_f64(),
+                self.count,
+                self.item_name
+            );
+        } else {
+            defmt::write!(
+                f,
+                "{=usize} {=str}, {=f64}s",
+                self.count,
+                self.item_name,
+                self.elapsed.as_secs_f64()
+            );
+        }
+        if let Some(speed) = self.items_per_second() {
+            defmt::write!(f, ", {=f64} {=str}/s", speed, self.item_name);
+        }
+        if self.stopped {
+            defmt::write!(f, "]");
+        } else if let (Some(percent), Some(eta)) = (self.percent(), self.eta()) {
+            defmt::write!(
+                f,
+                "; {=f64}% done, {=f64}s to end",
+                percent,
+                eta.as_secs_f64()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use core::cell::Cell;
+
+    /// Return a counter whose clock is advanced by one second at each read.
+    fn counter(time: &Cell<Duration>) -> ProgressCounter<impl Clock + '_> {
+        ProgressCounter::new(move || {
+            time.set(time.get() + Duration::from_secs(1));
+            time.get()
+        })
+    }
+
+    #[test]
+    fn test_log_interval() {
+        let time = Cell::new(Duration::ZERO);
+        let mut counter = counter(&time);
+        counter.log_interval(Duration::from_secs(3));
+        // Not started
+        assert!(counter.update().is_none());
+        counter.start();
+        assert!(counter.update().is_none());
+        assert!(counter.update().is_none());
+        let snapshot = counter.update().unwrap();
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.elapsed, Duration::from_secs(3));
+        assert!(counter.update().is_none());
+    }
+
+    #[test]
+    fn test_light_update() {
+        let time = Cell::new(Duration::ZERO);
+        let mut counter = counter(&time);
+        counter.log_interval(Duration::ZERO);
+        counter.start();
+        let mask = ProgressCounter::<fn() -> Duration>::LIGHT_UPDATE_MASK;
+        for _ in 0..mask {
+            assert!(counter.light_update().is_none());
+        }
+        assert_eq!(counter.light_update().unwrap().count, mask + 1);
+    }
+
+    #[test]
+    fn test_display() {
+        let time = Cell::new(Duration::ZERO);
+        let mut counter = counter(&time);
+        counter
+            .item_name("pages")
+            .log_interval(Duration::from_secs(1))
+            .expected_updates(Some(40));
+        counter.start();
+        assert_eq!(
+            counter.update_with_count(10).unwrap().to_string(),
+            "10 pages, 1.00s, 10.00 pages/s; 25.00% done, 3.00s to end"
+        );
+        counter.update_with_count(10);
+        let done = counter.done();
+        assert!(done.stopped);
+        assert_eq!(done.to_string(), "Elapsed: 3.00s [20 pages, 6.67 pages/s]");
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Procedural macros for
+//! [`dsi-progress-logger`](https://crates.io/crates/dsi-progress-logger).
+//!
+//! This crate should not be used directly: enable the `macros` feature of
+//! `dsi-progress-logger` instead.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, ItemFn};
+
+/// Instrument a function with a progress logger.
+///
+/// The body of the function is executed with a binding `pl` to a
+/// [`ProgressLogger`](https://docs.rs/dsi-progress-logger/latest/dsi_progress_logger/struct.ProgressLogger.html)
+/// created by the `progress_logger!` macro, with the arguments of the
+/// attribute (if any) as key-value pairs, and started with the name of the
+/// function as message. The logger is wrapped in a `DoneOnDrop` guard, so
+/// `done` is called when the function returns, even if it returns early
+/// (e.g., by the `?` operator).
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// #[logged_progress(item_name = "node")]
+/// fn visit(n: usize) -> usize {
+///     let mut sum = 0;
+///     for i in 0..n {
+///         sum += i;
+///         pl.light_update();
+///     }
+///     sum
+/// }
+///
+/// assert_eq!(visit(100), 4950);
+/// ```
+#[proc_macro_attribute]
+pub fn logged_progress(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(item as ItemFn);
+    let msg = format!("{}...", sig.ident);
+    quote! {
+        #(#attrs)*
+        #vis #sig {
+            #[allow(unused_mut, unused_variables)]
+            let mut pl = ::dsi_progress_logger::DoneOnDrop::new(
+                ::dsi_progress_logger::progress_logger![#args]
+            );
+            ::dsi_progress_logger::ProgressLog::start(&mut *pl, #msg);
+            #block
+        }
+    }
+    .into()
+}
@@ -0,0 +1,722 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{
+    clock, CiFormat, DisplayFields, ElapsedFormat, EtaSmoothing, Instant, OvershootPolicy,
+    ProgressLog, ProgressLogger, ProgressUpdate, Span, TimeUnit,
+};
+use std::fmt::{Arguments, Display, Formatter, Result};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A lock-free concurrent wrapper for a [`ProgressLog`] implementation.
+///
+/// This wrapper keeps the count of items not yet passed to the underlying
+/// logger in a single atomic integer, and the time of the next flush in
+/// another atomic integer. Each update increases the count and checks the
+/// time; the thread that observes that the time of the next flush has passed,
+/// and succeeds in moving it forward by the [flush
+/// interval](Self::flush_interval) by a compare-and-swap operation, passes
+/// the count to the underlying logger, which is protected by a mutex, and
+/// which will log if it is time to do so. All other threads never touch the
+/// mutex, so updates do not serialize on a lock as in the case of a
+/// [`ConcurrentWrapper`](crate::ConcurrentWrapper).
+///
+/// Since updates are applied to a shared atomic integer, it is not necessary to
+/// clone the wrapper: like [`SharedWrapper`](crate::SharedWrapper), the
+/// wrapper can be updated through a shared reference, and [`ProgressUpdate`]
+/// is implemented both for the wrapper and for shared references to the
+/// wrapper. The cost of [`update`](Self::update) is one atomic addition, which
+/// might be significant in case of high contention, an atomic load, and a read
+/// of the clock, which is usually the dominant cost; for very short
+/// activities, [`light_update`](Self::light_update) reads the clock only every
+/// [`LIGHT_UPDATE_MASK`](Self::LIGHT_UPDATE_MASK) + 1 items.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut apl = AtomicWrapper::new();
+/// apl.item_name("pumpkin");
+/// apl.start("Smashing pumpkins (using many threads)...");
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..100 {
+///         let apl = &apl;
+///         s.spawn(move || {
+///             for _ in 0..100000 {
+///                 apl.light_update();
+///             }
+///         });
+///     }
+/// });
+///
+/// apl.done();
+/// ```
+#[derive(Debug)]
+pub struct AtomicWrapper<P: ProgressLog = ProgressLogger> {
+    /// Underlying logger.
+    inner: Mutex<P>,
+    /// The number of items not yet passed to the underlying logger.
+    count: AtomicUsize,
+    /// The time of the next flush, in nanoseconds since `epoch`.
+    next_flush_time: AtomicU64,
+    /// The reference time for `next_flush_time`.
+    epoch: Instant,
+    /// The flush interval in nanoseconds.
+    flush_interval: u64,
+}
+
+impl Default for AtomicWrapper {
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtomicWrapper {
+    /// Create a new [`AtomicWrapper`] wrapping a default [`ProgressLogger`],
+    /// using the [default flush interval](Self::DEFAULT_FLUSH_INTERVAL).
+    #[track_caller]
+    pub fn new() -> Self {
+        Self::wrap(ProgressLogger::default())
+    }
+}
+
+impl<P: ProgressLog> AtomicWrapper<P> {
+    /// The default flush interval.
+    pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Calls to [`light_update`](Self::light_update) will check the time only
+    /// if the count crosses a multiple of this mask plus one.
+    pub const LIGHT_UPDATE_MASK: usize = (1 << 10) - 1;
+
+    /// Wrap a given [`ProgressLog`] in an [`AtomicWrapper`] using the
+    /// [default flush interval](Self::DEFAULT_FLUSH_INTERVAL).
+    pub fn wrap(inner: P) -> Self {
+        Self {
+            inner: Mutex::new(inner),
+            count: AtomicUsize::new(0),
+            next_flush_time: AtomicU64::new(0),
+            epoch: clock::now(),
+            flush_interval: Self::DEFAULT_FLUSH_INTERVAL.as_nanos() as u64,
+        }
+    }
+
+    /// Set the flush interval, that is, the minimum time between two
+    /// updates of the underlying logger.
+    ///
+    /// The interval should be significantly smaller than the log interval of
+    /// the underlying logger, as logs can be delayed by up to this amount.
+    pub fn flush_interval(&mut self, flush_interval: Duration) -> &mut Self {
+        self.flush_interval = flush_interval.as_nanos() as u64;
+        self
+    }
+
+    /// Check whether `now` is past the time of the next flush and, if so,
+    /// try to move the time of the next flush forward.
+    ///
+    /// Returns true if the current thread is the one that should flush.
+    #[inline]
+    fn claim_flush(&self, now: Instant) -> bool {
+        let now = now.saturating_duration_since(self.epoch).as_nanos() as u64;
+        let next_flush_time = self.next_flush_time.load(Ordering::Relaxed);
+        now >= next_flush_time
+            && self
+                .next_flush_time
+                .compare_exchange(
+                    next_flush_time,
+                    now + self.flush_interval,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+    }
+
+    /// Increase the count by one and check whether it is time to pass the
+    /// count to the underlying logger.
+    ///
+    /// This method reads the clock at each call.
+    #[inline]
+    pub fn update(&self) {
+        self.update_with_count(1);
+    }
+
+    /// Increase the count by the given amount and check whether it is time to
+    /// pass the count to the underlying logger.
+    ///
+    /// This method reads the clock at each call.
+    #[inline]
+    pub fn update_with_count(&self, count: usize) {
+        self.update_with_count_and_time(count, clock::now());
+    }
+
+    /// Increase the count by the given amount and check whether it is time to
+    /// pass the count to the underlying logger, using `now` as the current
+    /// time.
+    pub fn update_with_count_and_time(&self, count: usize, now: Instant) {
+        self.count.fetch_add(count, Ordering::Relaxed);
+        if self.claim_flush(now) {
+            let count = self.count.swap(0, Ordering::Relaxed);
+            self.inner
+                .lock()
+                .unwrap()
+                .update_with_count_and_time(count, now);
+        }
+    }
+
+    /// Increase the count by one, checking whether it is time to pass the
+    /// count to the underlying logger only if the count crosses a multiple of
+    /// [`LIGHT_UPDATE_MASK`](Self::LIGHT_UPDATE_MASK) + 1.
+    #[inline]
+    pub fn light_update(&self) {
+        if self.count.fetch_add(1, Ordering::Relaxed) & Self::LIGHT_UPDATE_MASK
+            == Self::LIGHT_UPDATE_MASK
+        {
+            self.update_with_count(0);
+        }
+    }
+
+    /// Pass the current count to the underlying logger.
+    pub fn flush(&self) {
+        let count = self.count.swap(0, Ordering::Relaxed);
+        if count != 0 {
+            self.inner.lock().unwrap().update_with_count(count);
+        }
+    }
+
+    /// Discard the current count.
+    fn clear(&self) {
+        self.count.store(0, Ordering::Relaxed);
+    }
+
+    /// Flush the current count and return the underlying logger.
+    pub fn into_inner(self) -> P {
+        self.flush();
+        self.inner.into_inner().unwrap()
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for AtomicWrapper<P> {
+    #[inline]
+    fn update(&mut self) {
+        Self::update_with_count(self, 1);
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        Self::update_with_count(self, count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        Self::update_with_count_and_time(self, 1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        Self::update_with_count_and_time(self, count, now);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        Self::update_with_count(self, counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        Self::light_update(self);
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for &AtomicWrapper<P> {
+    #[inline]
+    fn update(&mut self) {
+        AtomicWrapper::update_with_count(self, 1);
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        AtomicWrapper::update_with_count(self, count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        AtomicWrapper::update_with_count_and_time(self, 1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        AtomicWrapper::update_with_count_and_time(self, count, now);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        AtomicWrapper::update_with_count(self, counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        AtomicWrapper::light_update(self);
+    }
+}
+
+impl<P: ProgressLog> ProgressLog for AtomicWrapper<P> {
+    fn log(&mut self, now: Instant) {
+        self.flush();
+        self.inner.get_mut().unwrap().log(now);
+    }
+
+    fn log_if(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().log_if();
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.inner.get_mut().unwrap().item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.inner.get_mut().unwrap().log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.inner.get_mut().unwrap().log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .expected_updates(expected_updates);
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.inner.get_mut().unwrap().time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.inner.get_mut().unwrap().min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.inner.get_mut().unwrap().max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.inner.get_mut().unwrap().min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.inner.get_mut().unwrap().target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.inner.get_mut().unwrap().eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.inner.get_mut().unwrap().time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.inner.get_mut().unwrap().cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.inner.get_mut().unwrap().ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.inner.get_mut().unwrap().log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.inner.get_mut().unwrap().elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.inner.get_mut().unwrap().display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.clear();
+        self.inner.get_mut().unwrap().start(msg);
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.clear();
+        self.inner
+            .get_mut()
+            .unwrap()
+            .start_with_expected(msg, expected_updates);
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.clear();
+        self.inner
+            .get_mut()
+            .unwrap()
+            .start_resumed(msg, already_done, expected_updates);
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+        self.inner.get_mut().unwrap().restart();
+    }
+
+    fn update_failed(&mut self) {
+        self.inner.get_mut().unwrap().update_failed();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        self.inner.get_mut().unwrap().update_metric(name, value);
+    }
+
+    fn update_and_display(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().update_and_display();
+    }
+
+    fn stop(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().stop();
+    }
+
+    fn done(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().done();
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        self.flush();
+        self.inner.get_mut().unwrap().done_with_count(count);
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.inner.lock().unwrap().time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.lock().unwrap().is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        self.inner.lock().unwrap().is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.lock().unwrap().is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().eta()
+    }
+
+    fn count(&self) -> usize {
+        self.inner.lock().unwrap().count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.inner.lock().unwrap().get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.inner.lock().unwrap().get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.inner.lock().unwrap().get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.inner.lock().unwrap().get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.inner.lock().unwrap().get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.inner.lock().unwrap().span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.inner.get_mut().unwrap().add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().refresh();
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        self.inner.lock().unwrap().info(args)
+    }
+}
+
+/// This implementation flushes the current count before displaying the
+/// underlying logger.
+impl Display for AtomicWrapper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.flush();
+        self.inner.lock().unwrap().fmt(f)
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::ProgressLog;
+#[cfg(feature = "crossbeam-channel")]
+use crate::{clock, Deadline};
+use std::sync::mpsc;
+#[cfg(feature = "crossbeam-channel")]
+use std::time::Duration;
+
+/// A wrapper around a channel receiver that updates a [`ProgressLog`] each
+/// time an item is received.
+///
+/// The wrapper implements methods analogous to those of the underlying
+/// receiver, and [`Iterator`]. It is available for [`std::sync::mpsc::Receiver`]
+/// and, if the `crossbeam-channel` feature is enabled, for
+/// [`crossbeam_channel::Receiver`]. In the latter case, using
+/// [`report_queue_depth`](ProgressReceiver::report_queue_depth) you can also
+/// periodically log the number of items waiting in the channel.
+///
+/// The logger is neither started nor stopped by the wrapper.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+/// use std::sync::mpsc::channel;
+///
+/// let (tx, rx) = channel();
+/// std::thread::spawn(move || {
+///     for i in 0..1000 {
+///         tx.send(i).unwrap();
+///     }
+/// });
+///
+/// let mut pl = progress_logger![item_name = "message"];
+/// pl.start("Receiving messages...");
+/// let sum: usize = ProgressReceiver::new(rx, &mut pl).sum();
+/// pl.done();
+/// assert_eq!(sum, 499500);
+/// ```
+#[derive(Debug)]
+pub struct ProgressReceiver<R, P: ProgressLog> {
+    receiver: R,
+    pl: P,
+    /// The interval between queue-depth reports, and the next report time.
+    #[cfg(feature = "crossbeam-channel")]
+    depth_report: Option<(Duration, Deadline)>,
+}
+
+impl<R, P: ProgressLog> ProgressReceiver<R, P> {
+    /// Wrap the given receiver, updating the given logger.
+    pub fn new(receiver: R, pl: P) -> Self {
+        Self {
+            receiver,
+            pl,
+            #[cfg(feature = "crossbeam-channel")]
+            depth_report: None,
+        }
+    }
+
+    /// Return a mutable reference to the underlying logger.
+    pub fn pl(&mut self) -> &mut P {
+        &mut self.pl
+    }
+
+    /// Return the underlying receiver and logger.
+    pub fn into_inner(self) -> (R, P) {
+        (self.receiver, self.pl)
+    }
+}
+
+impl<T, P: ProgressLog> ProgressReceiver<mpsc::Receiver<T>, P> {
+    /// Receive an item as [`std::sync::mpsc::Receiver::recv`] does, updating
+    /// the logger.
+    pub fn recv(&mut self) -> Result<T, mpsc::RecvError> {
+        let item = self.receiver.recv()?;
+        self.pl.update();
+        Ok(item)
+    }
+
+    /// Try to receive an item as [`std::sync::mpsc::Receiver::try_recv`]
+    /// does, updating the logger if an item is received.
+    pub fn try_recv(&mut self) -> Result<T, mpsc::TryRecvError> {
+        let item = self.receiver.try_recv()?;
+        self.pl.update();
+        Ok(item)
+    }
+}
+
+impl<T, P: ProgressLog> Iterator for ProgressReceiver<mpsc::Receiver<T>, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv().ok()
+    }
+}
+
+#[cfg(feature = "crossbeam-channel")]
+impl<T, P: ProgressLog> ProgressReceiver<crossbeam_channel::Receiver<T>, P> {
+    /// Set the interval between reports of the number of items waiting in
+    /// the channel.
+    ///
+    /// If not [`None`], the number of items in the channel will be logged
+    /// using [`info`](ProgressLog::info) at most once every `interval`.
+    pub fn report_queue_depth(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.depth_report = interval.map(|interval| {
+            let now = clock::now();
+            let mut deadline = Deadline::new(now);
+            deadline.set(now, interval);
+            (interval, deadline)
+        });
+        self
+    }
+
+    /// Receive an item as [`crossbeam_channel::Receiver::recv`] does,
+    /// updating the logger.
+    pub fn recv(&mut self) -> Result<T, crossbeam_channel::RecvError> {
+        let item = self.receiver.recv()?;
+        self.update();
+        Ok(item)
+    }
+
+    /// Try to receive an item as [`crossbeam_channel::Receiver::try_recv`]
+    /// does, updating the logger if an item is received.
+    pub fn try_recv(&mut self) -> Result<T, crossbeam_channel::TryRecvError> {
+        let item = self.receiver.try_recv()?;
+        self.update();
+        Ok(item)
+    }
+
+    fn update(&mut self) {
+        self.pl.update();
+        if let Some((interval, deadline)) = &mut self.depth_report {
+            if deadline.passed() {
+                self.pl
+                    .info(format_args!("Queue depth: {}", self.receiver.len()));
+                deadline.set(clock::now(), *interval);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "crossbeam-channel")]
+impl<T, P: ProgressLog> Iterator for ProgressReceiver<crossbeam_channel::Receiver<T>, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv().ok()
+    }
+}
@@ -0,0 +1,173 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::ProgressLog;
+#[cfg(feature = "rayon")]
+use crate::{ConcurrentWrapper, ProgressUpdate};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Process a slice in chunks, updating a [`ProgressLog`] once per chunk.
+///
+/// The expected number of updates of the logger is set to the length of the
+/// slice; then, `f` is called on each chunk of length `chunk_size` (the last
+/// chunk might be shorter), and after each call the logger is updated with the
+/// length of the chunk.
+///
+/// The logger is neither started nor stopped.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let data = vec![0_u64; 1_000_000];
+/// let mut sum = 0;
+/// let mut pl = progress_logger![item_name = "element"];
+/// pl.start("Summing elements...");
+/// for_each_chunk(&mut pl, &data, 1 << 16, |chunk| {
+///     sum += chunk.iter().sum::<u64>();
+/// });
+/// pl.done();
+/// ```
+pub fn for_each_chunk<T, P: ProgressLog>(
+    pl: &mut P,
+    data: &[T],
+    chunk_size: usize,
+    mut f: impl FnMut(&[T]),
+) {
+    pl.expected_updates(Some(data.len()));
+    for chunk in data.chunks(chunk_size) {
+        f(chunk);
+        pl.update_with_count(chunk.len());
+    }
+}
+
+/// Process a mutable slice in chunks, updating a [`ProgressLog`] once per
+/// chunk.
+///
+/// This function is the mutable analogue of [`for_each_chunk`].
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut data = vec![0_u64; 1_000_000];
+/// let mut pl = progress_logger![item_name = "element"];
+/// pl.start("Sorting chunks...");
+/// for_each_chunk_mut(&mut pl, &mut data, 1 << 16, |chunk| {
+///     chunk.sort_unstable();
+/// });
+/// pl.done();
+/// ```
+pub fn for_each_chunk_mut<T, P: ProgressLog>(
+    pl: &mut P,
+    data: &mut [T],
+    chunk_size: usize,
+    mut f: impl FnMut(&mut [T]),
+) {
+    pl.expected_updates(Some(data.len()));
+    for chunk in data.chunks_mut(chunk_size) {
+        let len = chunk.len();
+        f(chunk);
+        pl.update_with_count(len);
+    }
+}
+
+/// Process a slice in chunks in parallel, updating a [`ConcurrentWrapper`] once
+/// per chunk.
+///
+/// This function is the parallel analogue of [`for_each_chunk`]: chunks are
+/// processed using [rayon](https://docs.rs/rayon/), and each rayon job uses its
+/// own clone of `cpl`, which is updated with the length of the chunk after each
+/// call to `f`. Clones are dropped, and thus flushed, before this function
+/// returns, so the count of `cpl` is exact when the function returns.
+///
+/// The logger is neither started nor stopped. This function is available only
+/// if the `rayon` feature is enabled.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// let data = vec![1_u64; 1_000_000];
+/// let sum = AtomicU64::new(0);
+/// let mut cpl = concurrent_progress_logger![item_name = "element"];
+/// cpl.start("Summing elements in parallel...");
+/// par_for_each_chunk(&mut cpl, &data, 1 << 16, |chunk| {
+///     sum.fetch_add(chunk.iter().sum::<u64>(), Ordering::Relaxed);
+/// });
+/// cpl.done();
+/// assert_eq!(sum.into_inner(), 1_000_000);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_for_each_chunk<T: Sync, P: ProgressLog + Clone + Send>(
+    cpl: &mut ConcurrentWrapper<P>,
+    data: &[T],
+    chunk_size: usize,
+    f: impl Fn(&[T]) + Sync + Send,
+) {
+    cpl.expected_updates(Some(data.len()));
+    data.par_chunks(chunk_size)
+        .for_each_with(cpl.clone(), |pl, chunk| {
+            f(chunk);
+            pl.update_with_count(chunk.len());
+        });
+}
+
+/// Process a mutable slice in chunks in parallel, updating a
+/// [`ConcurrentWrapper`] once per chunk.
+///
+/// This function is the mutable analogue of [`par_for_each_chunk`], and it is
+/// available only if the `rayon` feature is enabled.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut data = vec![0_u64; 1_000_000];
+/// let mut cpl = concurrent_progress_logger![item_name = "element"];
+/// cpl.start("Sorting chunks in parallel...");
+/// par_for_each_chunk_mut(&mut cpl, &mut data, 1 << 16, |chunk| {
+///     chunk.sort_unstable();
+/// });
+/// cpl.done();
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_for_each_chunk_mut<T: Send, P: ProgressLog + Clone + Send>(
+    cpl: &mut ConcurrentWrapper<P>,
+    data: &mut [T],
+    chunk_size: usize,
+    f: impl Fn(&mut [T]) + Sync + Send,
+) {
+    cpl.expected_updates(Some(data.len()));
+    data.par_chunks_mut(chunk_size)
+        .for_each_with(cpl.clone(), |pl, chunk| {
+            let len = chunk.len();
+            f(chunk);
+            pl.update_with_count(len);
+        });
+}
@@ -0,0 +1,135 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::Instant;
+use std::time::Duration;
+
+/// Return the current time.
+///
+/// With the `test-utils` feature, if a [`MockClock`](crate::MockClock) is
+/// installed on the current thread, its time is returned instead.
+#[inline(always)]
+pub(crate) fn now() -> Instant {
+    #[cfg(any(test, feature = "test-utils"))]
+    if let Some(now) = crate::test_utils::mock_now() {
+        return now;
+    }
+    Instant::now()
+}
+
+/// A TSC-based clock.
+#[cfg(feature = "quanta")]
+#[derive(Debug, Clone)]
+struct FastClock(quanta::Clock);
+
+#[cfg(feature = "quanta")]
+impl FastClock {
+    fn new() -> Self {
+        Self(quanta::Clock::new())
+    }
+
+    #[inline(always)]
+    fn now(&self) -> quanta::Instant {
+        self.0.now()
+    }
+}
+
+/// A coarse monotonic clock (`CLOCK_MONOTONIC_COARSE`), with a resolution of a
+/// few milliseconds.
+///
+/// Time is expressed as the duration since an unspecified starting point.
+#[cfg(all(feature = "coarse-clock", target_os = "linux", not(feature = "quanta")))]
+#[derive(Debug, Clone)]
+struct FastClock;
+
+#[cfg(all(feature = "coarse-clock", target_os = "linux", not(feature = "quanta")))]
+impl FastClock {
+    fn new() -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    fn now(&self) -> Duration {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: ts is a valid timespec, and CLOCK_MONOTONIC_COARSE is
+        // always available on Linux.
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC_COARSE, &mut ts) };
+        Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+    }
+}
+
+/// The next time at which a logger should log.
+///
+/// Besides the deadline expressed as an [`Instant`], this structure can keep a
+/// copy of the deadline expressed using a cheaper clock, which is used by
+/// [`passed`](Deadline::passed) to check whether it is time to log:
+///
+/// - with the `quanta` feature, a TSC-based [`quanta::Clock`];
+/// - with the `coarse-clock` feature, on Linux, `CLOCK_MONOTONIC_COARSE`.
+///
+/// If both features are enabled, `quanta` takes precedence. Otherwise,
+/// [`passed`](Deadline::passed) uses [`Instant::now`].
+#[derive(Debug, Clone)]
+pub(crate) struct Deadline {
+    /// The deadline.
+    time: Instant,
+    /// The clock used for fast checks.
+    #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+    clock: FastClock,
+    /// The deadline expressed using [`Self::clock`].
+    #[cfg(feature = "quanta")]
+    fast_time: quanta::Instant,
+    /// The deadline expressed using [`Self::clock`].
+    #[cfg(all(feature = "coarse-clock", target_os = "linux", not(feature = "quanta")))]
+    fast_time: Duration,
+}
+
+impl Deadline {
+    /// Create a deadline expiring at `now`.
+    pub fn new(now: Instant) -> Self {
+        #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+        let clock = FastClock::new();
+        Self {
+            time: now,
+            #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+            fast_time: clock.now(),
+            #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+            clock,
+        }
+    }
+
+    /// Set the deadline to `interval` after `now`.
+    pub fn set(&mut self, now: Instant, interval: Duration) {
+        self.time = now + interval;
+        #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+        {
+            self.fast_time = self.clock.now() + interval;
+        }
+    }
+
+    /// Return whether the deadline has passed, using the fastest available
+    /// clock.
+    #[inline(always)]
+    pub fn passed(&self) -> bool {
+        #[cfg(any(test, feature = "test-utils"))]
+        if let Some(now) = crate::test_utils::mock_now() {
+            return self.time <= now;
+        }
+        #[cfg(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux")))]
+        return self.fast_time <= self.clock.now();
+        #[cfg(not(any(feature = "quanta", all(feature = "coarse-clock", target_os = "linux"))))]
+        return self.time <= now();
+    }
+
+    /// Return whether the deadline has passed at `now`.
+    #[inline(always)]
+    pub fn passed_at(&self, now: Instant) -> bool {
+        self.time <= now
+    }
+}
@@ -0,0 +1,180 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{clock, Deadline, Instant, ProgressLog, ProgressLogger, TimeUnit};
+use log::info;
+use num_format::{Locale, ToFormattedString};
+use std::fmt::{Display, Formatter, Result};
+use std::time::Duration;
+
+/// A logger for activities organized in epochs containing a fixed number of
+/// steps, such as training loops.
+///
+/// Each log line displays the current epoch and step (e.g., `epoch 3/10, step
+/// 4,500/10,000`), followed by the percentage of completion and an estimate of
+/// the time to completion for the current epoch and for the whole activity. At
+/// the end of each epoch, a summary of the epoch is logged.
+///
+/// The logger is based on two [`ProgressLogger`] instances, one for the
+/// current epoch and one for the whole activity.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut el = EpochLogger::new(3, 1000);
+/// el.log_target(std::module_path!());
+/// el.start("Training...");
+/// for _ in 0..3 {
+///     el.start_epoch();
+///     for _ in 0..1000 {
+///         // do something on each step
+///         el.update();
+///     }
+///     el.end_epoch();
+/// }
+/// el.done();
+/// ```
+#[derive(Debug)]
+pub struct EpochLogger {
+    /// The logger for the current epoch.
+    epoch_pl: ProgressLogger,
+    /// The logger for the whole activity.
+    overall_pl: ProgressLogger,
+    /// The number of epochs.
+    num_epochs: usize,
+    /// The number of steps in an epoch.
+    steps_per_epoch: usize,
+    /// The current epoch (one-based; zero if no epoch has been started).
+    epoch: usize,
+    /// The log interval.
+    log_interval: Duration,
+    /// The next time we will log the activity.
+    next_log_time: Deadline,
+}
+
+impl EpochLogger {
+    /// Create a new epoch logger for the given number of epochs, each
+    /// containing the given number of steps.
+    #[track_caller]
+    pub fn new(num_epochs: usize, steps_per_epoch: usize) -> Self {
+        let mut epoch_pl = ProgressLogger::default();
+        epoch_pl.item_name("step");
+        let overall_pl = epoch_pl.clone();
+        let log_interval = Duration::from_secs(10);
+        Self {
+            epoch_pl,
+            overall_pl,
+            num_epochs,
+            steps_per_epoch,
+            epoch: 0,
+            log_interval,
+            next_log_time: Deadline::new(clock::now()),
+        }
+    }
+
+    /// Set the name of a step.
+    pub fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.epoch_pl.item_name(&item_name);
+        self.overall_pl.item_name(item_name);
+        self
+    }
+
+    /// Set the log interval.
+    pub fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.log_interval = log_interval;
+        self
+    }
+
+    /// Set the [`log`] target.
+    pub fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.epoch_pl.log_target(&target);
+        self.overall_pl.log_target(target);
+        self
+    }
+
+    /// Start the logger, displaying the given message.
+    ///
+    /// You can pass the empty string to display nothing.
+    pub fn start(&mut self, msg: impl AsRef<str>) {
+        self.overall_pl
+            .start_with_expected(msg, self.num_epochs * self.steps_per_epoch);
+        self.epoch = 0;
+        self.next_log_time.set(clock::now(), self.log_interval);
+    }
+
+    /// Start a new epoch.
+    pub fn start_epoch(&mut self) {
+        self.epoch += 1;
+        self.epoch_pl.start_with_expected("", self.steps_per_epoch);
+    }
+
+    /// Increase the count and check whether it is time to log.
+    pub fn update(&mut self) {
+        self.update_with_count(1);
+    }
+
+    /// Increase the count by the given amount and check whether it is time to
+    /// log.
+    pub fn update_with_count(&mut self, count: usize) {
+        // The inner loggers are used only for counting
+        self.epoch_pl.count += count;
+        self.overall_pl.count += count;
+        if self.next_log_time.passed() {
+            let now = clock::now();
+            info!(target: &self.overall_pl.log_target, "{}", self);
+            self.next_log_time.set(now, self.log_interval);
+        }
+    }
+
+    /// End the current epoch, logging a summary.
+    pub fn end_epoch(&mut self) {
+        self.epoch_pl.stop();
+        info!(
+            target: &self.epoch_pl.log_target,
+            "Epoch {}/{} completed. {}", self.epoch, self.num_epochs, self.epoch_pl
+        );
+    }
+
+    /// Stop the logger, print `Completed.`, and display the final stats.
+    pub fn done(&mut self) {
+        self.overall_pl.done();
+    }
+
+    /// Write the percentage of completion and the time to completion of a
+    /// logger.
+    fn fmt_progress(f: &mut Formatter<'_>, pl: &ProgressLogger, now: Instant) -> Result {
+        if let (Some(start_time), Some(expected_updates)) = (pl.start_time, pl.expected_updates) {
+            let millis_to_end: u128 = (expected_updates.saturating_sub(pl.count) as u128
+                * (now - start_time).as_millis())
+                / (pl.count as u128 + 1);
+            f.write_fmt(format_args!(
+                "{:.2}% done, {} to end",
+                100.0 * pl.count as f64 / expected_updates as f64,
+                TimeUnit::pretty_print(millis_to_end)
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for EpochLogger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let now = clock::now();
+        f.write_fmt(format_args!(
+            "epoch {}/{}, {} {}/{}; epoch: ",
+            self.epoch,
+            self.num_epochs,
+            self.epoch_pl.item_name,
+            self.epoch_pl.count.to_formatted_string(&Locale::en),
+            self.steps_per_epoch.to_formatted_string(&Locale::en),
+        ))?;
+        Self::fmt_progress(f, &self.epoch_pl, now)?;
+        f.write_fmt(format_args!("; overall: "))?;
+        Self::fmt_progress(f, &self.overall_pl, now)
+    }
+}
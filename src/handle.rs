@@ -0,0 +1,187 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{clock, Instant};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The state of a [`ProgressLogger`](crate::ProgressLogger) published for its
+/// [handles](ProgressHandle).
+#[derive(Debug)]
+pub(crate) struct HandleState {
+    /// The reference time for `start_time` and `stop_time`.
+    epoch: Instant,
+    /// The count.
+    count: AtomicUsize,
+    /// The number of items already done when the activity was resumed.
+    count_offset: AtomicUsize,
+    /// The expected number of updates, or `usize::MAX` if not set.
+    expected_updates: AtomicUsize,
+    /// The start time, in nanoseconds since `epoch` plus one, or zero if not
+    /// started.
+    start_time: AtomicU64,
+    /// The stop time, in nanoseconds since `epoch` plus one, or zero if not
+    /// stopped.
+    stop_time: AtomicU64,
+}
+
+impl HandleState {
+    pub(crate) fn new(epoch: Instant) -> Self {
+        Self {
+            epoch,
+            count: AtomicUsize::new(0),
+            count_offset: AtomicUsize::new(0),
+            expected_updates: AtomicUsize::new(usize::MAX),
+            start_time: AtomicU64::new(0),
+            stop_time: AtomicU64::new(0),
+        }
+    }
+
+    /// Convert an optional time to its representation in this state.
+    fn encode(&self, time: Option<Instant>) -> u64 {
+        time.map_or(0, |time| {
+            time.saturating_duration_since(self.epoch).as_nanos() as u64 + 1
+        })
+    }
+
+    /// Convert the representation of a time in this state to a time.
+    fn decode(&self, time: u64) -> Option<Instant> {
+        time.checked_sub(1)
+            .map(|nanos| self.epoch + Duration::from_nanos(nanos))
+    }
+
+    /// Publish the count.
+    #[inline(always)]
+    pub(crate) fn publish_count(&self, count: usize) {
+        self.count.store(count, Ordering::Relaxed);
+    }
+
+    /// Publish the whole state.
+    pub(crate) fn publish(
+        &self,
+        count: usize,
+        count_offset: usize,
+        expected_updates: Option<usize>,
+        start_time: Option<Instant>,
+        stop_time: Option<Instant>,
+    ) {
+        self.count.store(count, Ordering::Relaxed);
+        self.count_offset.store(count_offset, Ordering::Relaxed);
+        self.expected_updates
+            .store(expected_updates.unwrap_or(usize::MAX), Ordering::Relaxed);
+        self.start_time
+            .store(self.encode(start_time), Ordering::Relaxed);
+        self.stop_time
+            .store(self.encode(stop_time), Ordering::Relaxed);
+    }
+}
+
+/// A cheap, cloneable handle that can be used to poll the state of a
+/// [`ProgressLogger`](crate::ProgressLogger) from other threads.
+///
+/// A handle is returned by
+/// [`ProgressLogger::handle`](crate::ProgressLogger::handle). The state of
+/// the logger is published into atomic integers, so taking a
+/// [snapshot](Self::snapshot) never contends with the threads updating the
+/// logger, even if the logger is protected by a lock (e.g., by a
+/// [`ConcurrentWrapper`](crate::ConcurrentWrapper)). This is useful, for
+/// example, for GUIs or watchdog threads.
+///
+/// The count is published at each update, except for
+/// [`light_update`](crate::ProgressUpdate::light_update), which publishes it
+/// only when it checks the time. Note that wrappers buffering updates pass
+/// them to the underlying logger only from time to time.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut pl = progress_logger![];
+/// let handle = pl.handle();
+/// pl.start_with_expected("Processing...", 1000);
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         for _ in 0..1000 {
+///             pl.update();
+///         }
+///     });
+///     // Poll the logger without touching it
+///     let snapshot = handle.snapshot();
+///     assert!(snapshot.count <= 1000);
+/// });
+/// assert_eq!(handle.snapshot().count, 1000);
+/// pl.done();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProgressHandle(pub(crate) Arc<HandleState>);
+
+impl ProgressHandle {
+    /// Return a snapshot of the state of the logger.
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        let state = &self.0;
+        let start_time = state.decode(state.start_time.load(Ordering::Relaxed));
+        let stop_time = state.decode(state.stop_time.load(Ordering::Relaxed));
+        let expected_updates = state.expected_updates.load(Ordering::Relaxed);
+        ProgressSnapshot {
+            count: state.count.load(Ordering::Relaxed),
+            count_offset: state.count_offset.load(Ordering::Relaxed),
+            expected_updates: (expected_updates != usize::MAX).then_some(expected_updates),
+            elapsed: start_time.map(|start_time| stop_time.unwrap_or_else(clock::now) - start_time),
+            running: start_time.is_some() && stop_time.is_none(),
+        }
+    }
+}
+
+/// A snapshot of the state of a [`ProgressLogger`](crate::ProgressLogger),
+/// returned by [`ProgressHandle::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSnapshot {
+    /// The count.
+    pub count: usize,
+    /// The number of items already done when the activity was
+    /// [resumed](crate::ProgressLog::start_resumed).
+    pub count_offset: usize,
+    /// The expected number of updates, if set.
+    pub expected_updates: Option<usize>,
+    /// The elapsed time since the logger was started (up to the time it was
+    /// stopped, if it is stopped), or `None` if the logger has not been
+    /// started.
+    pub elapsed: Option<Duration>,
+    /// Whether the logger is started but not stopped.
+    pub running: bool,
+}
+
+impl ProgressSnapshot {
+    /// Return the fraction of the expected number of updates done so far,
+    /// including the items already done when the activity was resumed, or
+    /// `None` if the expected number of updates is not set.
+    pub fn fraction(&self) -> Option<f64> {
+        self.expected_updates.map(|expected_updates| {
+            (self.count + self.count_offset) as f64 / expected_updates as f64
+        })
+    }
+
+    /// Return the average number of items per second, or `None` if the logger
+    /// has not been started or no time has elapsed.
+    pub fn items_per_second(&self) -> Option<f64> {
+        self.elapsed
+            .filter(|elapsed| !elapsed.is_zero())
+            .map(|elapsed| self.count as f64 / elapsed.as_secs_f64())
+    }
+
+    /// Return an estimate of the time to completion, based on the average
+    /// speed, or `None` if the expected number of updates is not set or no
+    /// item has been processed.
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self
+            .expected_updates?
+            .saturating_sub(self.count + self.count_offset);
+        let items_per_second = self.items_per_second().filter(|&speed| speed > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / items_per_second))
+    }
+}
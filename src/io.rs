@@ -0,0 +1,97 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::ProgressLog;
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+/// A wrapper around a [`Read`] that updates a [`ProgressLog`] with the number
+/// of bytes read.
+///
+/// The logger is neither started nor stopped by the wrapper.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+/// use std::io::Read;
+///
+/// let data = vec![0_u8; 1 << 20];
+/// let mut pl = progress_logger![item_name = "byte"];
+/// pl.start("Reading data...");
+/// let mut buffer = vec![];
+/// ProgressReader::new(data.as_slice(), &mut pl).read_to_end(&mut buffer)?;
+/// pl.done();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ProgressReader<R, P: ProgressLog> {
+    reader: R,
+    pl: P,
+}
+
+impl<R, P: ProgressLog> ProgressReader<R, P> {
+    /// Wrap the given reader, updating the given logger.
+    pub fn new(reader: R, pl: P) -> Self {
+        Self { reader, pl }
+    }
+
+    /// Return a mutable reference to the underlying logger.
+    pub fn pl(&mut self) -> &mut P {
+        &mut self.pl
+    }
+
+    /// Return the underlying reader and logger.
+    pub fn into_inner(self) -> (R, P) {
+        (self.reader, self.pl)
+    }
+}
+
+impl<R: Read, P: ProgressLog> Read for ProgressReader<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.pl.update_with_count(n);
+        Ok(n)
+    }
+}
+
+/// Copy the content of a reader into a writer as [`std::io::copy`] does,
+/// updating a [`ProgressLog`] with the number of bytes copied.
+///
+/// The logger is neither started nor stopped.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let data = vec![0_u8; 1 << 20];
+/// let mut copy = vec![];
+/// let mut pl = progress_logger![item_name = "byte"];
+/// pl.start("Copying data...");
+/// copy_with_progress(&mut data.as_slice(), &mut copy, &mut pl)?;
+/// pl.done();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn copy_with_progress<R: Read + ?Sized, W: Write + ?Sized, P: ProgressLog>(
+    reader: &mut R,
+    writer: &mut W,
+    pl: &mut P,
+) -> io::Result<u64> {
+    io::copy(&mut ProgressReader::new(reader, pl), writer)
+}
+
+/// Copy the content of a file into a writer as [`copy_with_progress`] does,
+/// setting the expected number of updates of the logger to the length of the
+/// file.
+pub fn copy_file_with_progress<W: Write + ?Sized, P: ProgressLog>(
+    file: &mut File,
+    writer: &mut W,
+    pl: &mut P,
+) -> io::Result<u64> {
+    pl.expected_updates(Some(file.metadata()?.len() as usize));
+    copy_with_progress(file, writer, pl)
+}
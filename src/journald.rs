@@ -0,0 +1,267 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::utils::executable_name;
+use log::kv::{self, Key, Value, VisitSource, VisitValue};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+
+/// The socket of the native protocol of the systemd journal.
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// A [`log`] backend sending records to the systemd journal.
+///
+/// Records are sent using the native protocol of the journal, with a syslog
+/// priority derived from their level and from the `event` key-value attached
+/// by [`ProgressLogger`](crate::ProgressLogger):
+///
+/// - final stats (`event` equal to `done`) have priority `NOTICE` (5);
+/// - periodic logs and other information have priority `INFO` (6);
+/// - warnings (e.g., slow intervals, overshoots, exceeded time budgets, and
+///   high failure rates) have priority `WARNING` (4);
+/// - errors (e.g., failure rates above the [error
+///   threshold](crate::ProgressLog::failure_rate_thresholds)) have priority
+///   `ERR` (3);
+/// - debug and trace records have priority `DEBUG` (7).
+///
+/// Key-values, such as the count and the speed, are stored as journal fields
+/// with uppercase names (e.g., `COUNT` and `ITEMS_PER_SECOND`), so they can be
+/// used to filter and analyze progress with `journalctl` (e.g., `journalctl
+/// EVENT=done`); missing values (e.g., the percentage of completion if the
+/// expected number of updates is not set) are omitted. Moreover, records carry
+/// the fields `SYSLOG_IDENTIFIER` (the name of the executable), `TARGET`,
+/// `CODE_FILE`, and `CODE_LINE`.
+///
+/// Records that cannot be sent (e.g., because the journal is not running, or
+/// because they do not fit a datagram) are dropped. This structure is
+/// available only if the `journald` feature is enabled, on Unix.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dsi_progress_logger::prelude::*;
+///
+/// JournaldLogger::new()?.install(log::LevelFilter::Info)?;
+/// let mut pl = progress_logger![];
+/// pl.start("Processing...");
+/// for _ in 0..1000 {
+///     pl.update();
+/// }
+/// // Logged with priority NOTICE and the field EVENT=done
+/// pl.done();
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JournaldLogger {
+    /// The socket connected to the journal.
+    socket: UnixDatagram,
+    /// The value of the `SYSLOG_IDENTIFIER` field.
+    identifier: String,
+}
+
+impl JournaldLogger {
+    /// Create a new [`JournaldLogger`] connected to the journal.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the socket of the journal cannot be connected
+    /// (e.g., because the system does not use systemd).
+    pub fn new() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(JOURNAL_SOCKET)?;
+        Ok(Self {
+            socket,
+            identifier: executable_name(),
+        })
+    }
+
+    /// Install this logger as the global logger with the given maximum level.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if a global logger has already been installed.
+    pub fn install(self, max_level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl Log for JournaldLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        // There is nobody to report errors to
+        let _ = self.socket.send(&entry(&self.identifier, record));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Return the syslog priority of a record.
+fn priority(record: &Record) -> u8 {
+    match record.level() {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info
+            if record
+                .key_values()
+                .get(Key::from_str("event"))
+                .is_some_and(|event| event.to_borrowed_str() == Some("done")) =>
+        {
+            5
+        }
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Return the entry of the journal corresponding to a record, in the format
+/// of the native protocol.
+fn entry(identifier: &str, record: &Record) -> Vec<u8> {
+    let mut entry = vec![];
+    append_field(
+        &mut entry,
+        "PRIORITY",
+        priority(record).to_string().as_bytes(),
+    );
+    append_field(&mut entry, "MESSAGE", record.args().to_string().as_bytes());
+    append_field(&mut entry, "SYSLOG_IDENTIFIER", identifier.as_bytes());
+    append_field(&mut entry, "TARGET", record.target().as_bytes());
+    if let Some(file) = record.file() {
+        append_field(&mut entry, "CODE_FILE", file.as_bytes());
+    }
+    if let Some(line) = record.line() {
+        append_field(&mut entry, "CODE_LINE", line.to_string().as_bytes());
+    }
+    // Visiting fields cannot fail
+    let _ = record.key_values().visit(&mut Fields(&mut entry));
+    entry
+}
+
+/// Append a field to an entry of the journal.
+///
+/// Values containing a newline are written in the binary format, in which the
+/// name is followed by a newline and by the length of the value as a 64-bit
+/// little-endian integer.
+fn append_field(entry: &mut Vec<u8>, name: &str, value: &[u8]) {
+    entry.extend_from_slice(name.as_bytes());
+    if value.contains(&b'\n') {
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value);
+    entry.push(b'\n');
+}
+
+/// Return the name of the journal field corresponding to a key, or `None` if
+/// the key does not yield a valid name.
+///
+/// Names are made of uppercase letters, digits, and underscores, and they
+/// cannot start with a digit or an underscore.
+fn field_name(key: &str) -> Option<String> {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        .then_some(name)
+}
+
+/// A visitor appending key-values to an entry of the journal.
+struct Fields<'a>(&'a mut Vec<u8>);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        if let Some(name) = field_name(key.as_str()) {
+            value.visit(Field {
+                entry: self.0,
+                name: &name,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A visitor appending a value, if not null, to an entry of the journal.
+struct Field<'a> {
+    entry: &'a mut Vec<u8>,
+    name: &'a str,
+}
+
+impl<'v> VisitValue<'v> for Field<'_> {
+    fn visit_any(&mut self, value: Value) -> Result<(), kv::Error> {
+        append_field(self.entry, self.name, value.to_string().as_bytes());
+        Ok(())
+    }
+
+    fn visit_null(&mut self) -> Result<(), kv::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry() {
+        let kvs: &[(&str, Option<f64>)] = &[("percent", None), ("items_per_second", Some(1.5))];
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("app::sort")
+            .args(format_args!("1,000 items\nmore"))
+            .key_values(&kvs)
+            .build();
+        let mut expected = b"PRIORITY=6\nMESSAGE\n".to_vec();
+        expected.extend_from_slice(&16_u64.to_le_bytes());
+        expected.extend_from_slice(
+            b"1,000 items\nmore\nSYSLOG_IDENTIFIER=app\nTARGET=app::sort\nITEMS_PER_SECOND=1.5\n",
+        );
+        assert_eq!(entry("app", &record), expected);
+    }
+
+    #[test]
+    fn test_priority() {
+        let done: &[(&str, &str)] = &[("event", "done")];
+        let progress: &[(&str, &str)] = &[("event", "progress")];
+        for (level, kvs, expected) in [
+            (Level::Info, done, 5),
+            (Level::Info, progress, 6),
+            (Level::Warn, done, 4),
+            (Level::Error, progress, 3),
+            (Level::Debug, progress, 7),
+        ] {
+            let record = Record::builder()
+                .level(level)
+                .args(format_args!(""))
+                .key_values(&kvs)
+                .build();
+            assert_eq!(priority(&record), expected);
+        }
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("res_mem"), Some("RES_MEM".to_string()));
+        assert_eq!(field_name("items/s"), Some("ITEMS_S".to_string()));
+        assert_eq!(field_name("_count"), None);
+        assert_eq!(field_name("9lives"), None);
+    }
+}
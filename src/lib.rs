@@ -7,24 +7,154 @@
  */
 
 #![doc = include_str!("../README.md")]
-
-use log::info;
-use num_format::{Locale, ToFormattedString};
-use pluralizer::pluralize;
-use std::fmt::{Arguments, Display, Formatter, Result};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
-use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, RefreshKind, System, SystemExt};
+// ratatui depends on both syn 2 (through strum_macros) and syn 3 (through
+// instability), so duplicate versions cannot be avoided by aligning the
+// dependencies of this workspace. The lint is allowed here rather than in
+// Cargo.toml, as the latter is overridden by command-line lint flags.
+#![allow(clippy::multiple_crate_versions)]
+
+use log::{info, log, warn, Level};
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::fmt::{Arguments, Debug, Display, Formatter, Result, Write as _};
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write as _;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{atomic::AtomicUsize, mpsc, Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{Instant, SystemTime};
+use sysinfo::{
+    get_current_pid, DiskExt, Pid, ProcessExt, ProcessRefreshKind, RefreshKind, System, SystemExt,
+};
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::{Instant, SystemTime};
+mod atomic;
+pub use atomic::*;
+mod channel;
+pub use channel::*;
+mod chunks;
+pub use chunks::*;
+mod clock;
+use clock::Deadline;
+mod epoch;
+pub use epoch::*;
+mod handle;
+pub use handle::*;
+mod io;
+pub use io::*;
+#[cfg(all(feature = "journald", unix))]
+mod journald;
+#[cfg(all(feature = "journald", unix))]
+pub use journald::*;
+mod no_logging;
+pub use no_logging::*;
+mod reduce;
+pub use reduce::*;
+mod registry;
+pub use registry::*;
+mod report;
+mod rollup;
+pub use rollup::*;
+mod shared;
+pub use shared::*;
+#[cfg(all(feature = "shared-memory", unix))]
+mod shared_memory;
+#[cfg(all(feature = "shared-memory", unix))]
+pub use shared_memory::*;
+mod span;
+pub use span::*;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub use test_utils::*;
+mod trace;
+#[cfg(feature = "ratatui")]
+mod tui;
+#[cfg(feature = "ratatui")]
+pub use tui::*;
 mod utils;
 pub use utils::*;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(feature = "macros")]
+pub use dsi_progress_logger_macros::logged_progress;
+
+/// The hot-path methods of a [`ProgressLog`].
+///
+/// This trait contains just the methods that mark progress, and it is a
+/// supertrait of [`ProgressLog`]. Code that only updates a logger set up
+/// elsewhere (e.g., a library function receiving a logger as argument) can
+/// require just this trait.
+pub trait ProgressUpdate {
+    /// Increase the count and check whether it is time to log.
+    ///
+    /// Implementations might not read the clock at each call: for example,
+    /// [`ProgressLogger`] predicts from the speed of the last log interval the
+    /// count at which the time should be checked again, so a log might be
+    /// delayed by a sudden slowdown (see
+    /// [`MAX_CHECK_INTERVAL`](ProgressLogger::MAX_CHECK_INTERVAL)). Use
+    /// [`update_with_time`](ProgressUpdate::update_with_time) to force a check.
+    fn update(&mut self);
+
+    /// Set the count and check whether it is time to log.
+    fn update_with_count(&mut self, count: usize);
+
+    /// Increase the count and check whether it is time to log, using `now` as
+    /// the current time.
+    ///
+    /// This method is equivalent to
+    /// [`update_with_count_and_time`](ProgressUpdate::update_with_count_and_time)
+    /// with a count of one.
+    fn update_with_time(&mut self, now: Instant) {
+        self.update_with_count_and_time(1, now);
+    }
+
+    /// Increase the count by the given amount and check whether it is time to
+    /// log, using `now` as the current time.
+    ///
+    /// This method is useful in wrappers that already know the current time, as
+    /// it avoids a second read of the clock. Note that the check always uses
+    /// `now`, even when faster clocks are available (see the `quanta` and
+    /// `coarse-clock` features).
+    ///
+    /// The default implementation ignores `now` and calls
+    /// [`update_with_count`](ProgressUpdate::update_with_count).
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        let _ = now;
+        self.update_with_count(count);
+    }
+
+    /// Increase the count by the sum of the given counts and check once
+    /// whether it is time to log.
+    ///
+    /// This method is useful when progress is reported in batches (e.g., a
+    /// vector of chunk sizes), as it performs a single time check for the
+    /// whole batch.
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    /// Increase the count but checks whether it is time to log only after an
+    /// implementation-defined number of calls.
+    ///
+    /// Useful for very short activities with respect to which  checking the
+    /// time is expensive.
+    fn light_update(&mut self);
+}
 
 /// Logging trait.
 ///
 /// To log the progress of an activity, you call [`start`](ProgressLog::start).
 /// Then, each time you want to mark progress, you call
-/// [`update`](ProgressLog::update), which increases the item counter, and will
+/// [`update`](ProgressUpdate::update), which increases the item counter, and will
 /// log progress information if enough time has passed since the last log.
-/// [`light_update`](ProgressLog::light_update) will perform a time check only
+/// [`light_update`](ProgressUpdate::light_update) will perform a time check only
 ///  on updates multiples of
 /// [`LIGHT_UPDATE_MASK`](ProgressLogger::LIGHT_UPDATE_MASK) + 1; it  should be
 /// used when the activity has an extremely low cost that is comparable to that
@@ -45,10 +175,8 @@ pub use utils::*;
 /// system data by the [`sysinfo`] crate, and will be updated at each log
 /// interval (note that this will slightly slow down the logging process).
 ///
-/// At any time, displaying the progress logger will give you time information
-/// up to the present. However,  since it is impossible to update the memory
-/// information from the [`Display::fmt`] implementation, you should call
-/// [`refresh`](ProgressLog::refresh) before displaying the logger on your own.
+/// At any time, displaying the progress logger will give you time and memory
+/// information up to the present.
 ///
 /// When the activity is over, you call [`stop`](ProgressLog::stop), which fixes
 /// the final time, and possibly display again the logger.
@@ -58,10 +186,19 @@ pub use utils::*;
 /// After you finish a run of the progress logger, can call
 /// [`start`](ProgressLog::start) again measure another activity.
 ///
+/// The setters of optional features (e.g.,
+/// [`max_logs`](ProgressLog::max_logs)) have a default implementation that
+/// ignores the setting, so implementations need to override only the setters
+/// of the features they support. Analogously, the queries of optional
+/// features (e.g., [`eta`](ProgressLog::eta)) return by default false, zero,
+/// an empty string, or [`None`], as in the case of `Option::<P>::None`, and
+/// the other methods of optional features are implemented by default using
+/// the basic ones.
+///
 /// # Examples
 ///
 /// See the [`ProgressLogger`] documentation.
-pub trait ProgressLog {
+pub trait ProgressLog: ProgressUpdate {
     /// Force a log of `self` assuming `now` is the current time.
     ///
     /// This is a low-level method that should not be called directly.
@@ -81,15 +218,125 @@ pub trait ProgressLog {
     ///   process that created the logger;
     /// - the [available memory](sysinfo::System::available_memory);
     /// - the [free memory](`sysinfo::System::free_memory);
-    /// - the [total amount](sysinfo::System::total_memory) of memory.
+    /// - the [total amount](sysinfo::System::total_memory) of memory;
+    /// - the [used swap](sysinfo::System::used_swap);
+    /// - the [total amount](sysinfo::System::total_swap) of swap.
+    ///
+    /// On Windows, the resident-set size and the virtual-memory size are
+    /// replaced by the working set and the private bytes of the process, and
+    /// the used and total swap by the commit charge and the commit limit of
+    /// the system, as displayed by the Task Manager.
+    ///
+    /// The resident-set size is also recorded at each log, and
+    /// [`done`](ProgressLog::done) displays a sparkline of its values over the
+    /// activity, making leaks and spikes apparent at a glance.
+    ///
+    /// On platforms on which [`sysinfo`] is not supported (e.g.,
+    /// `wasm32-unknown-unknown`) this setting is ignored.
     fn display_memory(&mut self, display_memory: bool) -> &mut Self;
 
+    /// Set whether to display additionally the change in resident-set size
+    /// since the last log (e.g., `+1.20GB`).
+    ///
+    /// This setting has effect only if memory is displayed (see
+    /// [`display_memory`](ProgressLog::display_memory)). The growth rate of the
+    /// resident-set size during a long activity is often what reveals a memory
+    /// leak.
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        let _ = display_memory_delta;
+        self
+    }
+
+    /// Set whether to display additionally the 1-, 5-, and 15-minute [load
+    /// averages](sysinfo::SystemExt::load_average) of the system.
+    ///
+    /// On shared servers, the load average often explains drops in speed that
+    /// have nothing to do with the activity being logged. On platforms on which
+    /// [`sysinfo`] is not supported (e.g., `wasm32-unknown-unknown`) this
+    /// setting is ignored.
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        let _ = display_load_average;
+        self
+    }
+
+    /// Set a path whose available disk space will be displayed at each log, and
+    /// the minimum available space, in bytes, below which a warning will be
+    /// logged.
+    ///
+    /// The warning is logged when the available space falls below the minimum,
+    /// and then again only after it has risen above the minimum.
+    ///
+    /// Passing [`None`] disables monitoring. The disk containing the path is
+    /// the one with the longest mount point that is a prefix of the
+    /// canonicalized path. On platforms on which [`sysinfo`] is not supported
+    /// (e.g., `wasm32-unknown-unknown`) this setting is ignored.
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        let _ = (path, min_free_space);
+        self
+    }
+
+    /// Set the fraction of the memory limit above which the resident-set size
+    /// of the process triggers a warning.
+    ///
+    /// If not [`None`] and memory is displayed (see
+    /// [`display_memory`](ProgressLog::display_memory)), a warning will be
+    /// logged the first time the resident-set size of the process exceeds the
+    /// given fraction of the memory limit, which is the total amount of memory
+    /// or, on Linux, the memory limit of the control group of the process, if
+    /// smaller. Note that in the absence of a control-group limit the memory
+    /// limit is the total amount of memory, not the available memory, so
+    /// memory used by other processes is not taken into account. The warning
+    /// will be logged again only after a new call to
+    /// [`start`](ProgressLog::start).
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        let _ = memory_warning_threshold;
+        self
+    }
+
+    /// Set the minimum interval between two refreshes of memory information.
+    ///
+    /// Refreshing memory information is comparatively expensive, so with short
+    /// log intervals you might want to refresh memory information less
+    /// frequently than progress is logged. By default, memory information is
+    /// refreshed each time the logger is displayed. Explicit calls to
+    /// [`refresh`](ProgressLog::refresh) always refresh memory information.
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        let _ = memory_refresh_interval;
+        self
+    }
+
     /// Set the name of an item.
     fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self;
 
+    /// Set the plural form of the name of an item.
+    ///
+    /// By default, the plural form is computed from the [name of an
+    /// item](ProgressLog::item_name) using simple English rules (e.g., `node` →
+    /// `nodes`, `entry` → `entries`, `index` → `indexes`); this setter makes it
+    /// possible to override it (e.g., for `vertex` → `vertices`). Since setting
+    /// the name of an item resets the plural form, this setter must be called
+    /// after [`item_name`](ProgressLog::item_name).
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        let _ = item_name_plural;
+        self
+    }
+
     /// Set the log interval.
     fn log_interval(&mut self, log_interval: Duration) -> &mut Self;
 
+    /// Set the maximum relative random variation of the log interval.
+    ///
+    /// If positive, each log interval will be chosen uniformly at random
+    /// between `log_interval` · (1 − `jitter`) and `log_interval` · (1 +
+    /// `jitter`), so that many processes started at the same time do not log
+    /// all in the same instant. For example, a jitter of 0.1 randomizes each
+    /// interval by ±10%. The value will be clamped between zero and one, and
+    /// NaN will be treated as zero; the default value is zero.
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        let _ = jitter;
+        self
+    }
+
     /// Set the expected number of updates.
     ///
     /// If not [`None`], the logger will display the percentage of completion
@@ -105,1083 +352,6785 @@ pub trait ProgressLog {
     fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self;
 
     /// Set whether to display additionally the speed achieved during the last
-    /// log interval.
+    /// log interval (or the last few, see
+    /// [`local_speed_window`](ProgressLog::local_speed_window)).
+    ///
+    /// Moreover, [`done`](ProgressLog::done) will report the speed of the last,
+    /// the slowest, and the fastest log interval.
     fn local_speed(&mut self, local_speed: bool) -> &mut Self;
 
-    /// Set the [`log`] target.
+    /// Set the number of log intervals over which the local speed is computed.
     ///
-    /// This should often be the path of the module logging progress, which is
-    /// obtained with [`std::module_path!`].
+    /// By default, the [local speed](ProgressLog::local_speed) is computed over
+    /// the last log interval only; with a larger window, the local speed is
+    /// computed over the last `local_speed_window` log intervals, making it
+    /// less sensitive to short hiccups. A window of zero is treated as a window
+    /// of one.
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        let _ = local_speed_window;
+        self
+    }
+
+    /// Set the speed, in items per second, below which logs are escalated to
+    /// the `warn` level.
     ///
-    /// Note that the macro [`progress_logger!`] sets this field automatically
-    /// to [`std::module_path!`].
+    /// If not [`None`], whenever the speed achieved during the last log
+    /// interval is below the given threshold, the log will be emitted at the
+    /// `warn` level instead of the `info` level, so that silent slowdowns
+    /// (e.g., thermal throttling, or network-filesystem hiccups) are surfaced.
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        let _ = min_speed;
+        self
+    }
+
+    /// Set whether to skip logs when no update happened during the log
+    /// interval.
+    ///
+    /// If true, the progress line is not logged if the count has not changed
+    /// since the previous log, so that loggers tracking intermittently active
+    /// activities (e.g., queues) do not fill the log with identical lines. When
+    /// activity resumes, a single note reporting the number of skipped log
+    /// intervals is logged before the next log. Everything else happening at a
+    /// log (e.g., checks, or updates of the [progress
+    /// file](ProgressLog::progress_file)) happens anyway.
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        let _ = skip_idle;
+        self
+    }
+
+    /// Set the maximum number of periodic logs of an activity.
+    ///
+    /// If not [`None`], at most the given number of progress lines are logged
+    /// for each activity, whereas the start message and the final stats are
+    /// always logged; in particular, zero suppresses progress lines entirely.
+    /// If the [expected duration](ProgressLog::expected_duration) or the
+    /// [expected number of updates](ProgressLog::expected_updates) is set, the
+    /// remaining lines are spaced evenly across the estimated remaining time.
+    /// The logger keeps logging at each [log
+    /// interval](ProgressLog::log_interval), so checks and other outputs
+    /// (e.g., the [progress file](ProgressLog::progress_file)) are not
+    /// delayed: only progress lines are skipped until the next evenly spaced
+    /// one is due. This is useful to keep archived logs of many runs readable.
+    ///
+    /// Lines forced by [`update_and_display`](ProgressLog::update_and_display)
+    /// are always logged, but they count toward the maximum.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// use dsi_progress_logger::prelude::*;
     ///
-    ///    env_logger::builder()
-    ///        .filter_level(log::LevelFilter::Info)
-    ///        .try_init()?;
-    ///
-    /// let mut pl = ProgressLogger::default();
-    /// pl.item_name("pumpkin");
-    /// pl.log_target(std::module_path!());
-    /// pl.start("Smashing pumpkins from a module...");
-    /// for _ in 0..100 {
-    ///    // do something on each pumpkin
-    ///    pl.update();
+    /// let mut pl = progress_logger![max_logs = Some(20)];
+    /// pl.start_with_expected("Processing...", 1000);
+    /// for _ in 0..1000 {
+    ///     pl.update();
     /// }
     /// pl.done();
-    /// #     Ok(())
-    /// # }
     /// ```
-    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self;
-
-    /// Start the logger, displaying the given message.
-    ///
-    /// You can pass the empty string to display nothing.
-    fn start(&mut self, msg: impl AsRef<str>);
-
-    /// Increase the count and check whether it is time to log.
-    fn update(&mut self);
-
-    /// Set the count and check whether it is time to log.
-    fn update_with_count(&mut self, count: usize);
-
-    /// Increase the count but checks whether it is time to log only after an
-    /// implementation-defined number of calls.
-    ///
-    /// Useful for very short activities with respect to which  checking the
-    /// time is expensive.
-    fn light_update(&mut self);
-
-    /// Increase the count and forces a log.
-    fn update_and_display(&mut self);
-
-    /// Stop the logger, fixing the final time.
-    fn stop(&mut self);
-
-    /// Stop the logger, print `Completed.`, and display the final stats. The
-    /// number of expected updates will be cleared.
-    fn done(&mut self);
-
-    /// Stop the logger, sets the count, prints `Completed.`, and displays the
-    /// final stats. The number of expected updates will be cleared.
-    ///
-    /// This method is particularly useful in two circumstances:
-    /// * you have updated the logger with some approximate values (e.g., in a
-    ///   multicore computation) but before printing the final stats you want
-    ///   the internal counter to contain an exact value;
-    /// * you have used the logger as a handy timer, calling just
-    ///   [`start`](#fields.start) and this method.
-    fn done_with_count(&mut self, count: usize);
-
-    /// Return the elapsed time since the logger was started, or `None` if the
-    /// logger has not been started.
-    fn elapsed(&self) -> Option<Duration>;
-
-    /// Refreshe memory information, if previously requested with
-    /// [`display_memory`](#method.display_memory). You do not need to call this
-    /// method unless you display the logger manually.
-    fn refresh(&mut self);
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        let _ = max_logs;
+        self
+    }
 
-    /// Output the given message.
+    /// Set the minimum duration of an activity before any output.
     ///
-    /// For maximum flexibility, this method takes as argument the result of a
-    /// [`std::format_args!`] macro. Note that there will be no output if the
-    /// logger is the [`None`] variant.
+    /// If not [`None`], nothing is logged for an activity, not even the start
+    /// message, until the given duration has elapsed since the start; at that
+    /// point, the start message is logged followed by the first progress line.
+    /// Activities completing earlier emit no output at all, so libraries can
+    /// log progress for every helper call without cluttering fast interactive
+    /// sessions. [`update_and_display`](ProgressLog::update_and_display) forces
+    /// output regardless of the elapsed time. Checks (e.g., of the [time
+    /// budget](ProgressLog::time_budget) or of the [memory
+    /// threshold](ProgressLog::memory_warning_threshold)) are performed in any
+    /// case, and warnings are always logged.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// use dsi_progress_logger::*;
-    ///
-    ///    env_logger::builder()
-    ///        .filter_level(log::LevelFilter::Info)
-    ///        .try_init()?;
+    /// use dsi_progress_logger::prelude::*;
+    /// use std::time::Duration;
     ///
-    /// let logger_name = "my_logger";
-    /// let mut pl = progress_logger![];
-    /// pl.info(format_args!("My logger named {}", logger_name));
-    /// #     Ok(())
-    /// # }
+    /// let mut pl = progress_logger![min_duration = Some(Duration::from_secs(2))];
+    /// // Nothing is logged
+    /// pl.start("Sorting...");
+    /// pl.update_with_count(10);
+    /// pl.done();
     /// ```
-    fn info(&self, args: Arguments<'_>);
-}
-
-impl<P: ProgressLog> ProgressLog for &mut P {
-    fn log(&mut self, now: Instant) {
-        (**self).log(now);
-    }
-
-    fn log_if(&mut self) {
-        (**self).log_if();
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        let _ = min_duration;
+        self
     }
 
-    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
-        (**self).display_memory(display_memory);
+    /// Set a target speed, in items per second.
+    ///
+    /// If not [`None`], each log displays the current speed as a percentage of
+    /// the target speed, and [`done`](ProgressLog::done) reports whether the
+    /// target speed was met, logging at the `warn` level if it was not. This is
+    /// useful, for example, to spot regressions in performance jobs.
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        let _ = target_speed;
         self
     }
 
-    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
-        (**self).item_name(item_name);
+    /// Set the failure rates above which logs are escalated to the `warn` and
+    /// `error` levels.
+    ///
+    /// The failure rate is the ratio between the number of [failed
+    /// items](ProgressLog::update_failed) and the number of items. If not
+    /// [`None`], whenever the failure rate is at least `warn_rate`
+    /// (respectively, `error_rate`), logs, including the final one of
+    /// [`done`](ProgressLog::done), will be emitted at the `warn`
+    /// (respectively, `error`) level, so that partial failures are not silent.
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        let _ = (warn_rate, error_rate);
         self
     }
 
-    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
-        (**self).log_interval(log_interval);
+    /// Set how the estimated time to end is smoothed between logs.
+    ///
+    /// On skewed workloads, the raw estimate, which is based on the average
+    /// speed since the start, can oscillate wildly between logs. With
+    /// [`EtaSmoothing::Monotone`] or [`EtaSmoothing::Hysteresis`] the estimated
+    /// completion time is revised only at logs, and only under the conditions
+    /// described by the policy. The default is [`EtaSmoothing::Raw`].
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        let _ = eta_smoothing;
         self
     }
 
-    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
-        (**self).expected_updates(expected_updates);
+    /// Set the coefficient of variation of the speed of recent log intervals
+    /// above which the time to end is displayed as a range.
+    ///
+    /// If not [`None`], when the speed of the last few log intervals varies by
+    /// more than the given fraction of its mean (e.g., 0.5 for 50%), the time
+    /// to end is displayed as a range (e.g., `2h 0m 0s–3h 0m 0s to end`)
+    /// computed using the mean speed plus or minus its standard deviation, as a
+    /// point estimate would communicate false confidence.
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        let _ = eta_range_threshold;
         self
     }
 
-    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
-        (**self).time_unit(time_unit);
+    /// Set the number of log intervals whose speed is displayed as a sparkline.
+    ///
+    /// If not [`None`], each progress line is followed by a sparkline of the
+    /// speed of the last given number of log intervals (e.g., `▃▄▆█▇▅▂▁`),
+    /// scaled between the minimum and the maximum speed, which shows at a
+    /// glance whether the activity is speeding up or slowing down. The
+    /// sparkline is displayed only if speed or timing
+    /// [fields](ProgressLog::display_fields) are displayed. The default is
+    /// [`None`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![speed_sparkline = Some(20)];
+    /// pl.start("Processing...");
+    /// for _ in 0..1000 {
+    ///     pl.update();
+    /// }
+    /// pl.done();
+    /// ```
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        let _ = speed_sparkline;
         self
     }
 
-    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
-        (**self).local_speed(local_speed);
+    /// Set the policy to apply when the count exceeds the expected number of
+    /// updates.
+    ///
+    /// Overshooting the expected number of updates usually indicates a counting
+    /// bug. Independently of the policy, the percentage of completion and the
+    /// time to end are replaced by the number of excess updates. The default
+    /// policy is [`OvershootPolicy::Warn`].
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        let _ = overshoot_policy;
         self
     }
 
-    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
-        (**self).log_target(target);
+    /// Set the expected duration of the activity.
+    ///
+    /// If not [`None`], the percentage of the expected duration that has
+    /// elapsed is displayed alongside the count. This is the natural measure of
+    /// progress for activities of fixed length (e.g., a benchmark running for
+    /// 30 minutes), and it can be used with or without an [expected number of
+    /// updates](ProgressLog::expected_updates). A zero duration is treated as
+    /// [`None`].
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        let _ = expected_duration;
         self
     }
 
-    fn start(&mut self, msg: impl AsRef<str>) {
-        (**self).start(msg);
+    /// Set a time budget for the activity.
+    ///
+    /// If not [`None`], a warning will be logged the first time the projected
+    /// completion time (which requires the [expected number of
+    /// updates](ProgressLog::expected_updates)) exceeds the budget, and the
+    /// first time the elapsed time exceeds the budget. Warnings are logged
+    /// again only after a new call to [`start`](ProgressLog::start).
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        let _ = time_budget;
+        self
     }
 
-    fn update(&mut self) {
-        (**self).update();
+    /// Set a callback invoked when the elapsed time exceeds the [time
+    /// budget](ProgressLog::time_budget).
+    ///
+    /// The callback is invoked at most once between two calls to
+    /// [`start`](ProgressLog::start), when the overrun is detected at log time,
+    /// and can be used, for example, to set a flag that makes the activity
+    /// abort gracefully. Alternatively, you can poll
+    /// [`time_budget_exceeded`](ProgressLog::time_budget_exceeded).
+    ///
+    /// The callback is invoked while the logger is being updated, so it must
+    /// not use the logger, with the exception of [`ConcurrentWrapper`], which
+    /// invokes the callback after releasing the lock on the underlying logger.
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        let _ = callback;
+        self
     }
 
-    fn update_with_count(&mut self, count: usize) {
-        (**self).update_with_count(count);
+    /// Set a flag that, when set to true (e.g., by another thread), signals
+    /// that the activity should be cancelled.
+    ///
+    /// The flag can be checked cheaply with
+    /// [`is_cancelled`](ProgressLog::is_cancelled), so that loops that already
+    /// update a logger can bail out without having to pass around a second
+    /// handle. Note that loggers that do not log ignore the flag (see
+    /// [`is_cancelled`](ProgressLog::is_cancelled)).
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        let _ = cancel_flag;
+        self
     }
 
-    fn light_update(&mut self) {
-        (**self).light_update();
+    /// Set whether logs are formatted and emitted by a background worker
+    /// thread.
+    ///
+    /// If true, [`log`](ProgressLog::log) just sends a compact snapshot of the
+    /// state of the logger (time, counts, and
+    /// [metrics](ProgressLog::update_metric)) to a worker thread, which
+    /// refreshes system information, formats the logger, and emits the log. In
+    /// this way, the calling thread (and, for example, the lock of a
+    /// [`ConcurrentWrapper`]) is released immediately. The worker thread is
+    /// spawned at the first log and terminated when the logger is dropped or
+    /// deferred logging is disabled. Logs emitted by the calling thread (e.g.,
+    /// by [`start`](ProgressLog::start) or [`done`](ProgressLog::done)) wait
+    /// for the worker, so logs appear in the expected order.
+    ///
+    /// The statistics about the speed, the estimated time to end, the
+    /// [overshoot policy](ProgressLog::overshoot_policy), and the [time
+    /// budget](ProgressLog::time_budget) are still handled by the calling
+    /// thread. At most a few logs can be pending: if the worker falls behind,
+    /// further logs are dropped until it catches up. If the worker panics, the
+    /// panic is resumed in the calling thread at the next log or at
+    /// [`done`](ProgressLog::done).
+    ///
+    /// The configuration and the state of the logger are copied to the worker
+    /// at the first log after [`start`](ProgressLog::start), so later
+    /// configuration changes will be reflected only after the next start. The
+    /// default is `false`.
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        let _ = deferred_logging;
+        self
     }
 
-    fn update_and_display(&mut self) {
-        (**self).update_and_display();
+    /// Set whether to log the stats accumulated so far if the logger is dropped
+    /// while running.
+    ///
+    /// If true, dropping the logger after a call to
+    /// [`start`](ProgressLog::start) but before a call to
+    /// [`stop`](ProgressLog::stop) or [`done`](ProgressLog::done) (e.g.,
+    /// because of an early return or of the `?` operator) will log at the
+    /// `warn` level a line starting with `Dropped while running` and containing
+    /// the stats accumulated so far.
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        let _ = log_on_drop;
+        self
     }
 
-    fn stop(&mut self) {
-        (**self).stop();
+    /// Set whether [`done`](ProgressLog::done) logs the tree of the spans of
+    /// the nested activities (see [`span`](ProgressLog::span)).
+    ///
+    /// The tree is logged only if some nested activity has been
+    /// [added](ProgressLog::add_span). The default is `false`.
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        let _ = log_span_tree;
+        self
     }
 
-    fn done(&mut self) {
-        (**self).done();
+    /// Set a file to which a machine-readable line describing the progress is
+    /// written at each log, replacing the previous content.
+    ///
+    /// The line contains space-separated `key=value` pairs: `count`,
+    /// `expected`, `percent`, `elapsed_ms`, `eta_ms`, and `items_per_second`;
+    /// keys whose value is not known (e.g., `expected` when the expected number
+    /// of updates is not set) are omitted. External tools can thus poll the
+    /// file cheaply to track the progress of the activity.
+    ///
+    /// Regular files are replaced atomically by renaming a temporary file, so
+    /// readers never see partial content. Named pipes are written directly
+    /// and, on Unix, without blocking: if no reader has opened the pipe, or
+    /// the pipe is full, the line is skipped. Passing [`None`] disables the
+    /// output. Other errors are logged at the `warn` level.
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        let _ = path;
+        self
     }
 
-    fn done_with_count(&mut self, count: usize) {
-        (**self).done_with_count(count);
+    /// Set a file to which a self-contained report of the activity is written
+    /// by [`done`](ProgressLog::done).
+    ///
+    /// The report contains summary statistics (count, elapsed time, speed,
+    /// failed items, and [metrics](ProgressLog::update_metric)), a table of
+    /// the elapsed time of the nested activities (see
+    /// [`span`](ProgressLog::span)), if any, and a table of the count and of
+    /// the speed at each log; in HTML format, it contains also a chart of the
+    /// speed over time. At most
+    /// [`HISTORY_CAPACITY`](ProgressLogger::HISTORY_CAPACITY) evenly spaced
+    /// logs are recorded. The format is HTML if the extension of the file is
+    /// `html` or `htm`, and Markdown otherwise. Passing [`None`] disables the
+    /// report. Errors are logged at the `warn` level.
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let path = std::env::temp_dir().join("report.md");
+    /// let mut pl = progress_logger![report_file = Some(path.as_path())];
+    /// pl.start("Processing...");
+    /// for _ in 0..1000 {
+    ///     pl.update();
+    /// }
+    /// pl.done();
+    /// assert!(std::fs::read_to_string(&path)?.starts_with("# Processing..."));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        let _ = path;
+        self
     }
 
-    fn elapsed(&self) -> Option<Duration> {
-        (**self).elapsed()
+    /// Set a file to which a description of the activity in the JSON Chrome
+    /// trace-event format is written by [`done`](ProgressLog::done).
+    ///
+    /// The trace contains the activity as a complete event named after the
+    /// message passed to [`start`](ProgressLog::start), a complete event for
+    /// each nested activity (see [`add_span`](ProgressLog::add_span)), and a
+    /// counter event with the count at each log. Overlapping nested activities
+    /// are placed on separate tracks. Timestamps are in microseconds since
+    /// the Unix epoch, so traces can be loaded in
+    /// [Perfetto](https://ui.perfetto.dev/) together with traces from other
+    /// sources. Passing [`None`] disables the output. Errors are logged at the
+    /// `warn` level.
+    ///
+    /// The children of a [`RollUp`] never write a trace, as their spans are
+    /// written in the trace of the outermost logger.
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        let _ = path;
+        self
     }
 
-    fn refresh(&mut self) {
-        (**self).refresh();
+    /// Set a format for continuous-integration service messages that will be
+    /// printed on standard output at each log, in addition to the usual logs.
+    ///
+    /// In this way, long activities running under a continuous-integration
+    /// service show live progress in the service interface. Since GitHub
+    /// Actions notices are annotations of the run, they are printed only at
+    /// the start and at the end of an activity. Use [`CiFormat::detect`] to
+    /// choose the format depending on the environment.
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        let _ = ci_format;
+        self
     }
 
-    fn info(&self, args: Arguments<'_>) {
-        (**self).info(args);
-    }
-}
+    /// Set whether to log the configuration of the logger.
+    ///
+    /// If true, [`start`](ProgressLog::start) (and its variants) and
+    /// [`restart`](ProgressLog::restart) log a line summarizing the
+    /// configuration (log interval, expected number of updates, item name, and
+    /// memory display), and a note is logged whenever one of these settings is
+    /// changed while the logger is running. In this way, the settings used by a
+    /// run can be reconstructed from its output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![log_config = true];
+    /// // Logs "Configuration: log interval 10s, expected updates 1,000, ..."
+    /// pl.start_with_expected("Processing...", 1000);
+    /// // Logs "Configuration change: expected updates 1,000 -> 2,000"
+    /// pl.expected_updates(Some(2000));
+    /// pl.done();
+    /// ```
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        let _ = log_config;
+        self
+    }
 
-impl<P: ProgressLog> ProgressLog for Option<P> {
-    fn log(&mut self, now: Instant) {
-        if let Some(pl) = self {
-            pl.log(now);
-        }
+    /// Set the [`log`] target.
+    ///
+    /// This should often be the path of the module logging progress, which is
+    /// obtained with [`std::module_path!`].
+    ///
+    /// Note that the macro [`progress_logger!`] sets this field automatically
+    /// to [`std::module_path!`]. Loggers created by
+    /// [`ProgressLogger::default`] (or by the constructors of wrappers based on
+    /// it) derive instead a target from the path of the source file of the
+    /// caller (e.g., `mycrate::sort` for `mycrate/src/sort.rs`), which
+    /// approximates the module path; if the path is not inside a `src`
+    /// directory, the name of the executable is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    ///    env_logger::builder()
+    ///        .filter_level(log::LevelFilter::Info)
+    ///        .try_init()?;
+    ///
+    /// let mut pl = ProgressLogger::default();
+    /// pl.item_name("pumpkin");
+    /// pl.log_target(std::module_path!());
+    /// pl.start("Smashing pumpkins from a module...");
+    /// for _ in 0..100 {
+    ///    // do something on each pumpkin
+    ///    pl.update();
+    /// }
+    /// pl.done();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self;
+
+    /// Set the format of elapsed times.
+    ///
+    /// The default is [`ElapsedFormat::Pretty`]; the other formats are more
+    /// suitable for downstream parsing.
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        let _ = elapsed_format;
+        self
     }
 
-    fn log_if(&mut self) {
-        if let Some(pl) = self {
-            pl.log_if();
-        }
+    /// Set whether to display an absolute timestamp.
+    ///
+    /// If true, the logger is displayed preceded by the current time as an RFC
+    /// 3339 UTC timestamp with millisecond precision (e.g.,
+    /// `2025-01-31T12:34:56.789Z`), which makes it possible to correlate logs
+    /// from different hosts independently of the format used by the logging
+    /// backend.
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        let _ = display_timestamp;
+        self
     }
 
-    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
-        if let Some(pl) = self {
-            pl.display_memory(display_memory);
-        }
+    /// Add a ratio between two quantities (e.g., arcs per node, or output bytes
+    /// per input bytes) to be displayed at each log and by
+    /// [`done`](ProgressLog::done).
+    ///
+    /// Each quantity is either the last value of the
+    /// [metric](ProgressLog::update_metric) with the given name or, if the name
+    /// is equal to the item name, the current count. The ratio is not displayed
+    /// until both quantities are available, or while the denominator is zero.
+    /// Ratios are part of the configuration, so they are not cleared by
+    /// [`start`](ProgressLog::start); use
+    /// [`remove_ratio`](ProgressLog::remove_ratio) to stop displaying a ratio.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![item_name = "node"];
+    /// pl.display_ratio("arcs", "node");
+    /// pl.start("Compressing graph...");
+    /// let mut arcs = 0;
+    /// for node in 0..1000 {
+    ///     arcs += node % 10;
+    ///     pl.update_metric("arcs", arcs as f64);
+    ///     pl.light_update();
+    /// }
+    /// pl.done();
+    /// ```
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        let _ = (numerator, denominator);
         self
     }
 
-    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
-        if let Some(pl) = self {
-            pl.item_name(item_name);
-        }
+    /// Set whether to pad counts, elapsed times, percentages, and speeds to
+    /// fixed widths.
+    ///
+    /// With fixed widths, successive logs are vertically aligned, which makes it
+    /// easier to eyeball trends in long log files. The default is `false`.
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        let _ = fixed_width;
         self
     }
 
-    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
-        if let Some(pl) = self {
-            pl.log_interval(log_interval);
-        }
+    /// Set the fields to display.
+    ///
+    /// Each log is composed of several fields (see [`DisplayFields`]); by
+    /// default, all of them are displayed. Other components, such as the [local
+    /// speed](ProgressLog::local_speed) or the [memory
+    /// usage](ProgressLog::display_memory), have their own setters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![
+    ///     item_name = "node",
+    ///     expected_updates = Some(1000),
+    ///     display_fields = DisplayFields::COUNT | DisplayFields::PERCENT | DisplayFields::ETA,
+    /// ];
+    /// pl.start("Visiting graph...");
+    /// for _ in 0..1000 {
+    ///     pl.light_update();
+    /// }
+    /// pl.done();
+    /// ```
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        let _ = display_fields;
         self
     }
 
-    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
-        if let Some(pl) = self {
-            pl.expected_updates(expected_updates);
-        }
+    /// Set whether to display the time per item before the speed.
+    ///
+    /// By default, the speed (e.g., `12.35 items/s`) is displayed before the
+    /// time per item (e.g., `80.97 ms/item`). To display just one of them,
+    /// remove the other from the [displayed
+    /// fields](ProgressLog::display_fields).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// // Display just the time per item
+    /// let mut pl = progress_logger![display_fields = DisplayFields::ALL & !DisplayFields::SPEED];
+    /// // Display the time per item before the speed
+    /// let mut pl = progress_logger![timing_first = true];
+    /// ```
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        let _ = timing_first;
         self
     }
 
-    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
-        if let Some(pl) = self {
-            pl.time_unit(time_unit);
-        }
+    /// Stop displaying a ratio previously added with
+    /// [`display_ratio`](ProgressLog::display_ratio).
+    ///
+    /// Nothing happens if the ratio was not added.
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        let _ = (numerator, denominator);
         self
     }
 
-    /// Sets whether to display additionally the speed achieved during the last
-    /// log interval.
-    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
-        if let Some(pl) = self {
-            pl.local_speed(local_speed);
-        }
+    /// Set whether to display the number of items remaining to reach the
+    /// expected number of updates.
+    ///
+    /// If true and [`expected_updates`](ProgressLog::expected_updates) has
+    /// been set, the logger will display, besides the percentage of
+    /// completion, the number of remaining items (e.g., `1.20G to go`).
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        let _ = display_remaining;
         self
     }
 
-    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
-        if let Some(pl) = self {
-            pl.log_target(target);
-        }
+    /// Set whether to display the count as a fraction of the expected number of
+    /// updates.
+    ///
+    /// If true and [`expected_updates`](ProgressLog::expected_updates) has been
+    /// set, the logger will display, besides the percentage of completion, the
+    /// exact fraction of completed updates (e.g., `12,345,678/100,000,000`).
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        let _ = display_fraction;
         self
     }
 
-    fn start(&mut self, msg: impl AsRef<str>) {
-        if let Some(pl) = self {
-            pl.start(msg);
-        }
+    /// Start the logger, displaying the given message.
+    ///
+    /// You can pass the empty string to display nothing.
+    fn start(&mut self, msg: impl AsRef<str>);
+
+    /// Start the logger, displaying the given message, and set the expected
+    /// number of updates.
+    ///
+    /// The expected number of updates will be cleared by
+    /// [`done`](ProgressLog::done) and by the next call to
+    /// [`start`](ProgressLog::start), so that it cannot be inadvertently reused
+    /// when the logger is started again.
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.start(msg);
+        self.expected_updates(Some(expected_updates));
     }
 
-    fn update(&mut self) {
-        if let Some(pl) = self {
-            pl.update();
-        }
+    /// Start the logger for an activity resumed (e.g., from a checkpoint) after
+    /// `already_done` of `expected_updates` updates.
+    ///
+    /// The count displayed and the speed refer only to the updates performed
+    /// after this call, but the percentage of completion, the fraction, the
+    /// number of remaining updates and the time to end take into consideration
+    /// the updates already done. The expected number of updates will be cleared
+    /// by the next call to [`start`](ProgressLog::start), as in the case of
+    /// [`start_with_expected`](ProgressLog::start_with_expected).
+    ///
+    /// The default implementation calls
+    /// [`start_with_expected`](ProgressLog::start_with_expected) with the
+    /// number of updates still to be done.
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.start_with_expected(msg, expected_updates.saturating_sub(already_done));
     }
 
-    fn update_with_count(&mut self, count: usize) {
-        if let Some(pl) = self {
-            pl.update_with_count(count);
-        }
+    /// Restart the logger, reusing the message passed to the last call to
+    /// [`start`](ProgressLog::start) or
+    /// [`start_with_expected`](ProgressLog::start_with_expected).
+    ///
+    /// Timing and counters are reset, but the configuration, including the
+    /// expected number of updates, is preserved. If the message is not empty,
+    /// it is logged again, prefixed by `Restarting: `.
+    ///
+    /// This method is useful for retried operations, as it is not necessary to
+    /// keep track of the original message.
+    fn restart(&mut self);
+
+    /// Increase the count and the count of failed items, and check whether it
+    /// is time to log.
+    ///
+    /// If some item failed, the number of failed items and the failure rate
+    /// (i.e., the ratio between failed items and items) are displayed at each
+    /// log and by [`done`](ProgressLog::done). The count of failed items is
+    /// reset by [`start`](ProgressLog::start).
+    ///
+    /// The default implementation calls [`update`](ProgressUpdate::update),
+    /// so failed items are counted as the other ones.
+    fn update_failed(&mut self) {
+        self.update();
     }
 
-    fn light_update(&mut self) {
-        if let Some(pl) = self {
-            pl.light_update();
-        }
+    /// Update the value of a named floating-point metric (e.g., a loss, or a
+    /// compression ratio).
+    ///
+    /// A metric is registered the first time it is updated. The last value of
+    /// each metric is displayed at each log, and [`done`](ProgressLog::done)
+    /// logs the minimum, mean and maximum value of each metric. Metrics are
+    /// cleared by [`start`](ProgressLog::start).
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        let _ = (name, value);
     }
 
-    fn update_and_display(&mut self) {
-        if let Some(pl) = self {
-            pl.update_and_display();
-        }
+    /// Increase the count and forces a log.
+    fn update_and_display(&mut self);
+
+    /// Stop the logger, fixing the final time.
+    fn stop(&mut self);
+
+    /// Stop the logger, print `Completed.`, and display the final stats. The
+    /// number of expected updates will be cleared.
+    fn done(&mut self);
+
+    /// Stop the logger, sets the count, prints `Completed.`, and displays the
+    /// final stats. The number of expected updates will be cleared.
+    ///
+    /// This method is particularly useful in two circumstances:
+    /// * you have updated the logger with some approximate values (e.g., in a
+    ///   multicore computation) but before printing the final stats you want
+    ///   the internal counter to contain an exact value;
+    /// * you have used the logger as a handy timer, calling just
+    ///   [`start`](#fields.start) and this method.
+    fn done_with_count(&mut self, count: usize);
+
+    /// Return whether the elapsed time exceeds the [time
+    /// budget](ProgressLog::time_budget).
+    ///
+    /// This method returns false if the time budget is not set, or if the
+    /// logger has not been started.
+    fn time_budget_exceeded(&self) -> bool {
+        false
     }
 
-    fn stop(&mut self) {
-        if let Some(pl) = self {
-            pl.stop();
-        }
+    /// Return whether the [cancel flag](ProgressLog::cancel_flag) has been set.
+    ///
+    /// This method returns false if no cancel flag has been set.
+    ///
+    /// **Warning**: loggers that do not log, such as `Option::<P>::None` (and
+    /// thus [`no_logging!`]) and [`NoLogging`], cannot store a cancel flag, so
+    /// this method always returns false for them: switching off logging
+    /// switches off cancellation, too. If cancellation must work regardless of
+    /// logging, check the flag directly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let cancel = Arc::new(AtomicBool::new(false));
+    /// let mut pl = progress_logger![cancel_flag = Some(cancel.clone())];
+    /// pl.start("Processing...");
+    /// for i in 0..1000 {
+    ///     if i == 500 {
+    ///         // Usually done by another thread
+    ///         cancel.store(true, Ordering::Relaxed);
+    ///     }
+    ///     pl.update();
+    ///     if pl.is_cancelled() {
+    ///         break;
+    ///     }
+    /// }
+    /// pl.done();
+    /// ```
+    fn is_cancelled(&self) -> bool {
+        false
     }
 
-    fn done(&mut self) {
-        if let Some(pl) = self {
-            pl.done();
-        }
+    /// Return whether the logger has been started.
+    ///
+    /// A logger is started by [`start`](ProgressLog::start) (or one of its
+    /// variants), and remains started after it has been
+    /// [stopped](ProgressLog::is_stopped).
+    fn is_started(&self) -> bool {
+        false
     }
 
-    fn done_with_count(&mut self, count: usize) {
-        if let Some(pl) = self {
-            pl.done_with_count(count);
-        }
+    /// Return whether the logger has been stopped.
+    ///
+    /// A logger is stopped by [`stop`](ProgressLog::stop) or
+    /// [`done`](ProgressLog::done), and ceases to be stopped when it is started
+    /// again. Thus, a logger is running if it is started but not stopped.
+    fn is_stopped(&self) -> bool {
+        false
     }
 
-    fn elapsed(&self) -> Option<Duration> {
-        self.as_ref().and_then(|pl| pl.elapsed())
+    /// Return the average number of items per second since the logger was
+    /// started, or [`None`] if the logger has not been started or no time has
+    /// elapsed.
+    fn items_per_second(&self) -> Option<f64> {
+        None
     }
 
-    fn refresh(&mut self) {
-        if let Some(pl) = self {
-            pl.refresh();
-        }
+    /// Return the number of items per second during the last log interval (or
+    /// the last few, see
+    /// [`local_speed_window`](ProgressLog::local_speed_window)) up to now, or
+    /// [`None`] if the logger is not running or no time has elapsed.
+    fn local_items_per_second(&self) -> Option<f64> {
+        None
     }
 
-    fn info(&self, args: Arguments<'_>) {
-        if let Some(pl) = self {
-            pl.info(args);
-        }
+    /// Return an estimate of the time to completion, based on the average speed
+    /// since the logger was started, or [`None`] if the logger is not running,
+    /// the expected number of updates is not set, or no update has been
+    /// performed yet.
+    fn eta(&self) -> Option<Duration> {
+        None
     }
-}
 
-/// An implementation of [`ProgressLog`] with output generated using the
-/// [`log`](https://docs.rs/log) crate at the `info` level.
-///
-/// Instances can be created by using fluent setters, or by using the
-/// [`progress_logger`] macro.
-///
-/// You can [clone](#impl-Clone-for-ProgressLogger) a logger to create a new one
-/// with the same setup but with all the counters reset.
-///
-/// # Examples
-///
-/// A typical call sequence to a progress logger is as follows:
-///
-/// ```rust
-/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-/// use dsi_progress_logger::prelude::*;
-///
-/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
-///
-/// let mut pl = ProgressLogger::default();
-/// pl.item_name("pumpkin");
-/// pl.start("Smashing pumpkins...");
-/// for _ in 0..100 {
-///    // do something on each pumpkin
-///    pl.update();
-/// }
-/// pl.done();
-/// #     Ok(())
-/// # }
-/// ```
-///
-/// The [`progress_logger`] macro will create the progress logger for you and
-/// set its [`log_target`](ProgressLog::log_target) to [`std::module_path!()`],
-/// which is usually what you want. You can also call any setter with a
-/// key-value syntax:
-///
-/// ```rust
-/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-/// use dsi_progress_logger::prelude::*;
-///
-/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
-///
-/// let mut pl = progress_logger![item_name="pumpkin"];
-/// pl.start("Smashing pumpkins...");
-/// for _ in 0..100 {
+    /// Return the current count.
+    ///
+    /// For wrappers buffering updates (e.g., [`ConcurrentWrapper`]), the count
+    /// does not include updates that have not been passed yet to the underlying
+    /// logger.
+    fn count(&self) -> usize {
+        0
+    }
+
+    /// Return the elapsed time since the logger was started, or `None` if the
+    /// logger has not been started.
+    fn elapsed(&self) -> Option<Duration>;
+
+    /// Return the [log interval](ProgressLog::log_interval).
+    ///
+    /// Loggers that never log return a zero duration.
+    fn get_log_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Return the [name of an item](ProgressLog::item_name).
+    ///
+    /// Loggers that never log return an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// fn process(mut pl: impl ProgressLog) {
+    ///     let item_name = pl.get_item_name();
+    ///     pl.start(format!("Processing {}s...", item_name));
+    ///     pl.done();
+    /// }
+    ///
+    /// process(progress_logger![item_name = "record"]);
+    /// ```
+    fn get_item_name(&self) -> String {
+        String::new()
+    }
+
+    /// Return the [expected number of updates](ProgressLog::expected_updates),
+    /// if set.
+    fn get_expected_updates(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return the [time unit](ProgressLog::time_unit) used to display speeds,
+    /// if set.
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    /// Return the [`log`] [target](ProgressLog::log_target).
+    ///
+    /// Loggers that never log return an empty string.
+    fn get_log_target(&self) -> String {
+        String::new()
+    }
+
+    /// Return the span of the activity, that is, its name, its start and stop
+    /// times, and the spans of the nested activities added by
+    /// [`add_span`](ProgressLog::add_span), or [`None`] if the logger has not
+    /// been started.
+    ///
+    /// If the logger is running, the stop time is the current time.
+    fn span(&self) -> Option<Span> {
+        None
+    }
+
+    /// Add the span of a completed nested activity.
+    ///
+    /// The spans added are returned as children by [`span`](ProgressLog::span),
+    /// merging the spans with the same name (see [`Span`]), and logged as a
+    /// tree by [`done`](ProgressLog::done) if
+    /// [requested](ProgressLog::log_span_tree); they are cleared when the
+    /// logger is started. Usually, there is no need to call
+    /// this method directly, as a [`RollUp`] adds the span of the child logger
+    /// to the parent logger when the child is done.
+    fn add_span(&mut self, span: Span) {
+        let _ = span;
+    }
+
+    /// Refresh memory information, if previously requested with
+    /// [`display_memory`](#method.display_memory). You do not need to call this
+    /// method, as memory information is refreshed each time the logger is
+    /// displayed.
+    fn refresh(&mut self);
+
+    /// Output the given message.
+    ///
+    /// For maximum flexibility, this method takes as argument the result of a
+    /// [`std::format_args!`] macro. Note that there will be no output if the
+    /// logger is the [`None`] variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use dsi_progress_logger::*;
+    ///
+    ///    env_logger::builder()
+    ///        .filter_level(log::LevelFilter::Info)
+    ///        .try_init()?;
+    ///
+    /// let logger_name = "my_logger";
+    /// let mut pl = progress_logger![];
+    /// pl.info(format_args!("My logger named {}", logger_name));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn info(&self, args: Arguments<'_>);
+}
+
+impl<P: ProgressUpdate> ProgressUpdate for &mut P {
+    fn update(&mut self) {
+        (**self).update();
+    }
+
+    fn update_with_count(&mut self, count: usize) {
+        (**self).update_with_count(count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        (**self).update_with_time(now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        (**self).update_with_count_and_time(count, now);
+    }
+
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        (**self).update_from_counts(counts);
+    }
+
+    fn light_update(&mut self) {
+        (**self).light_update();
+    }
+}
+
+impl<P: ProgressLog> ProgressLog for &mut P {
+    fn log(&mut self, now: Instant) {
+        (**self).log(now);
+    }
+
+    fn log_if(&mut self) {
+        (**self).log_if();
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        (**self).display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        (**self).display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        (**self).display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        (**self).monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        (**self).memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        (**self).memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        (**self).item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        (**self).item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        (**self).log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        (**self).log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        (**self).expected_updates(expected_updates);
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        (**self).time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        (**self).local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        (**self).local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        (**self).min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        (**self).skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        (**self).max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        (**self).min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        (**self).target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        (**self).failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        (**self).eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        (**self).eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        (**self).speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        (**self).overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        (**self).expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        (**self).time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        (**self).on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        (**self).cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        (**self).deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        (**self).log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        (**self).log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        (**self).progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        (**self).report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        (**self).trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        (**self).ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        (**self).log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        (**self).log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        (**self).elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        (**self).display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        (**self).display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        (**self).fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        (**self).display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        (**self).timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        (**self).remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        (**self).display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        (**self).display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        (**self).start(msg);
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        (**self).start_with_expected(msg, expected_updates);
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        (**self).start_resumed(msg, already_done, expected_updates);
+    }
+
+    fn restart(&mut self) {
+        (**self).restart();
+    }
+
+    fn update_failed(&mut self) {
+        (**self).update_failed();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        (**self).update_metric(name, value);
+    }
+
+    fn update_and_display(&mut self) {
+        (**self).update_and_display();
+    }
+
+    fn stop(&mut self) {
+        (**self).stop();
+    }
+
+    fn done(&mut self) {
+        (**self).done();
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        (**self).done_with_count(count);
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        (**self).time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        (**self).is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        (**self).is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        (**self).items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        (**self).local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        (**self).eta()
+    }
+
+    fn count(&self) -> usize {
+        (**self).count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        (**self).elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        (**self).get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        (**self).get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        (**self).get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        (**self).get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        (**self).get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        (**self).span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        (**self).add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        (**self).refresh();
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        (**self).info(args);
+    }
+}
+
+impl<P: ProgressUpdate> ProgressUpdate for Option<P> {
+    fn update(&mut self) {
+        if let Some(pl) = self {
+            pl.update();
+        }
+    }
+
+    fn update_with_count(&mut self, count: usize) {
+        if let Some(pl) = self {
+            pl.update_with_count(count);
+        }
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        if let Some(pl) = self {
+            pl.update_with_time(now);
+        }
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        if let Some(pl) = self {
+            pl.update_with_count_and_time(count, now);
+        }
+    }
+
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        if let Some(pl) = self {
+            pl.update_from_counts(counts);
+        }
+    }
+
+    fn light_update(&mut self) {
+        if let Some(pl) = self {
+            pl.light_update();
+        }
+    }
+}
+
+impl<P: ProgressLog> ProgressLog for Option<P> {
+    fn log(&mut self, now: Instant) {
+        if let Some(pl) = self {
+            pl.log(now);
+        }
+    }
+
+    fn log_if(&mut self) {
+        if let Some(pl) = self {
+            pl.log_if();
+        }
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_memory(display_memory);
+        }
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_memory_delta(display_memory_delta);
+        }
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_load_average(display_load_average);
+        }
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        if let Some(pl) = self {
+            pl.monitor_free_space(path, min_free_space);
+        }
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.memory_warning_threshold(memory_warning_threshold);
+        }
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        if let Some(pl) = self {
+            pl.memory_refresh_interval(memory_refresh_interval);
+        }
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.item_name(item_name);
+        }
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.item_name_plural(item_name_plural);
+        }
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_interval(log_interval);
+        }
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_interval_jitter(jitter);
+        }
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.expected_updates(expected_updates);
+        }
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.time_unit(time_unit);
+        }
+        self
+    }
+
+    /// Sets whether to display additionally the speed achieved during the last
+    /// log interval.
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.local_speed(local_speed);
+        }
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        if let Some(pl) = self {
+            pl.local_speed_window(local_speed_window);
+        }
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.min_speed(min_speed);
+        }
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.skip_idle(skip_idle);
+        }
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.max_logs(max_logs);
+        }
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.min_duration(min_duration);
+        }
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.target_speed(target_speed);
+        }
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        if let Some(pl) = self {
+            pl.failure_rate_thresholds(warn_rate, error_rate);
+        }
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        if let Some(pl) = self {
+            pl.eta_smoothing(eta_smoothing);
+        }
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.eta_range_threshold(eta_range_threshold);
+        }
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.speed_sparkline(speed_sparkline);
+        }
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        if let Some(pl) = self {
+            pl.overshoot_policy(overshoot_policy);
+        }
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.expected_duration(expected_duration);
+        }
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.time_budget(time_budget);
+        }
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        if let Some(pl) = self {
+            pl.on_time_budget_exceeded(callback);
+        }
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.cancel_flag(cancel_flag);
+        }
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.deferred_logging(deferred_logging);
+        }
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_on_drop(log_on_drop);
+        }
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_span_tree(log_span_tree);
+        }
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.progress_file(path);
+        }
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.report_file(path);
+        }
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.trace_file(path);
+        }
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.ci_format(ci_format);
+        }
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_config(log_config);
+        }
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        if let Some(pl) = self {
+            pl.log_target(target);
+        }
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        if let Some(pl) = self {
+            pl.elapsed_format(elapsed_format);
+        }
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_timestamp(display_timestamp);
+        }
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_ratio(numerator, denominator);
+        }
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.fixed_width(fixed_width);
+        }
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_fields(display_fields);
+        }
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.timing_first(timing_first);
+        }
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        if let Some(pl) = self {
+            pl.remove_ratio(numerator, denominator);
+        }
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_remaining(display_remaining);
+        }
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        if let Some(pl) = self {
+            pl.display_fraction(display_fraction);
+        }
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        if let Some(pl) = self {
+            pl.start(msg);
+        }
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        if let Some(pl) = self {
+            pl.start_with_expected(msg, expected_updates);
+        }
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        if let Some(pl) = self {
+            pl.start_resumed(msg, already_done, expected_updates);
+        }
+    }
+
+    fn restart(&mut self) {
+        if let Some(pl) = self {
+            pl.restart();
+        }
+    }
+
+    fn update_failed(&mut self) {
+        if let Some(pl) = self {
+            pl.update_failed();
+        }
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        if let Some(pl) = self {
+            pl.update_metric(name, value);
+        }
+    }
+
+    fn update_and_display(&mut self) {
+        if let Some(pl) = self {
+            pl.update_and_display();
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(pl) = self {
+            pl.stop();
+        }
+    }
+
+    fn done(&mut self) {
+        if let Some(pl) = self {
+            pl.done();
+        }
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        if let Some(pl) = self {
+            pl.done_with_count(count);
+        }
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.as_ref().is_some_and(|pl| pl.time_budget_exceeded())
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.as_ref().is_some_and(|pl| pl.is_cancelled())
+    }
+
+    fn is_started(&self) -> bool {
+        self.as_ref().is_some_and(|pl| pl.is_started())
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.as_ref().is_some_and(|pl| pl.is_stopped())
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.as_ref().and_then(|pl| pl.items_per_second())
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.as_ref().and_then(|pl| pl.local_items_per_second())
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.as_ref().and_then(|pl| pl.eta())
+    }
+
+    fn count(&self) -> usize {
+        self.as_ref().map_or(0, |pl| pl.count())
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.as_ref().and_then(|pl| pl.elapsed())
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.as_ref()
+            .map_or(Duration::ZERO, |pl| pl.get_log_interval())
+    }
+
+    fn get_item_name(&self) -> String {
+        self.as_ref()
+            .map_or_else(String::new, |pl| pl.get_item_name())
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.as_ref().and_then(|pl| pl.get_expected_updates())
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.as_ref().and_then(|pl| pl.get_time_unit())
+    }
+
+    fn get_log_target(&self) -> String {
+        self.as_ref()
+            .map_or_else(String::new, |pl| pl.get_log_target())
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.as_ref().and_then(|pl| pl.span())
+    }
+
+    fn add_span(&mut self, span: Span) {
+        if let Some(pl) = self {
+            pl.add_span(span);
+        }
+    }
+
+    fn refresh(&mut self) {
+        if let Some(pl) = self {
+            pl.refresh();
+        }
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        if let Some(pl) = self {
+            pl.info(args);
+        }
+    }
+}
+
+#[cfg(feature = "either")]
+impl<L: ProgressUpdate, R: ProgressUpdate> ProgressUpdate for either::Either<L, R> {
+    fn update(&mut self) {
+        either::for_both!(self, pl => pl.update())
+    }
+
+    fn update_with_count(&mut self, count: usize) {
+        either::for_both!(self, pl => pl.update_with_count(count))
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        either::for_both!(self, pl => pl.update_with_time(now))
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        either::for_both!(self, pl => pl.update_with_count_and_time(count, now))
+    }
+
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        either::for_both!(self, pl => pl.update_from_counts(counts))
+    }
+
+    fn light_update(&mut self) {
+        either::for_both!(self, pl => pl.light_update())
+    }
+}
+
+/// This implementation makes it possible to choose at runtime between two
+/// different kinds of loggers without boxing.
+#[cfg(feature = "either")]
+impl<L: ProgressLog, R: ProgressLog> ProgressLog for either::Either<L, R> {
+    fn log(&mut self, now: Instant) {
+        either::for_both!(self, pl => pl.log(now))
+    }
+
+    fn log_if(&mut self) {
+        either::for_both!(self, pl => pl.log_if())
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_memory(display_memory);
+        });
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_memory_delta(display_memory_delta);
+        });
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_load_average(display_load_average);
+        });
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.monitor_free_space(path, min_free_space);
+        });
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.memory_warning_threshold(memory_warning_threshold);
+        });
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.memory_refresh_interval(memory_refresh_interval);
+        });
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.item_name(item_name);
+        });
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.item_name_plural(item_name_plural);
+        });
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_interval(log_interval);
+        });
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_interval_jitter(jitter);
+        });
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.expected_updates(expected_updates);
+        });
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.time_unit(time_unit);
+        });
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.local_speed(local_speed);
+        });
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.local_speed_window(local_speed_window);
+        });
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.min_speed(min_speed);
+        });
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.skip_idle(skip_idle);
+        });
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.max_logs(max_logs);
+        });
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.min_duration(min_duration);
+        });
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.target_speed(target_speed);
+        });
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.failure_rate_thresholds(warn_rate, error_rate);
+        });
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.eta_smoothing(eta_smoothing);
+        });
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.eta_range_threshold(eta_range_threshold);
+        });
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.speed_sparkline(speed_sparkline);
+        });
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.overshoot_policy(overshoot_policy);
+        });
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.expected_duration(expected_duration);
+        });
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.time_budget(time_budget);
+        });
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.on_time_budget_exceeded(callback);
+        });
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.cancel_flag(cancel_flag);
+        });
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.deferred_logging(deferred_logging);
+        });
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_on_drop(log_on_drop);
+        });
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_span_tree(log_span_tree);
+        });
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.progress_file(path);
+        });
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.report_file(path);
+        });
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.trace_file(path);
+        });
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.ci_format(ci_format);
+        });
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_config(log_config);
+        });
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.log_target(target);
+        });
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.elapsed_format(elapsed_format);
+        });
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_timestamp(display_timestamp);
+        });
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_ratio(numerator, denominator);
+        });
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.fixed_width(fixed_width);
+        });
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_fields(display_fields);
+        });
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.timing_first(timing_first);
+        });
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.remove_ratio(numerator, denominator);
+        });
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_remaining(display_remaining);
+        });
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        either::for_both!(self, pl => {
+            pl.display_fraction(display_fraction);
+        });
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        either::for_both!(self, pl => pl.start(msg))
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        either::for_both!(self, pl => pl.start_with_expected(msg, expected_updates))
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        either::for_both!(self, pl => pl.start_resumed(msg, already_done, expected_updates))
+    }
+
+    fn restart(&mut self) {
+        either::for_both!(self, pl => pl.restart())
+    }
+
+    fn update_failed(&mut self) {
+        either::for_both!(self, pl => pl.update_failed())
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        either::for_both!(self, pl => pl.update_metric(name, value))
+    }
+
+    fn update_and_display(&mut self) {
+        either::for_both!(self, pl => pl.update_and_display())
+    }
+
+    fn stop(&mut self) {
+        either::for_both!(self, pl => pl.stop())
+    }
+
+    fn done(&mut self) {
+        either::for_both!(self, pl => pl.done())
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        either::for_both!(self, pl => pl.done_with_count(count))
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        either::for_both!(self, pl => pl.time_budget_exceeded())
+    }
+
+    fn is_cancelled(&self) -> bool {
+        either::for_both!(self, pl => pl.is_cancelled())
+    }
+
+    fn is_started(&self) -> bool {
+        either::for_both!(self, pl => pl.is_started())
+    }
+
+    fn is_stopped(&self) -> bool {
+        either::for_both!(self, pl => pl.is_stopped())
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        either::for_both!(self, pl => pl.items_per_second())
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        either::for_both!(self, pl => pl.local_items_per_second())
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        either::for_both!(self, pl => pl.eta())
+    }
+
+    fn count(&self) -> usize {
+        either::for_both!(self, pl => pl.count())
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        either::for_both!(self, pl => pl.elapsed())
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        either::for_both!(self, pl => pl.get_log_interval())
+    }
+
+    fn get_item_name(&self) -> String {
+        either::for_both!(self, pl => pl.get_item_name())
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        either::for_both!(self, pl => pl.get_expected_updates())
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        either::for_both!(self, pl => pl.get_time_unit())
+    }
+
+    fn get_log_target(&self) -> String {
+        either::for_both!(self, pl => pl.get_log_target())
+    }
+
+    fn span(&self) -> Option<Span> {
+        either::for_both!(self, pl => pl.span())
+    }
+
+    fn add_span(&mut self, span: Span) {
+        either::for_both!(self, pl => pl.add_span(span))
+    }
+
+    fn refresh(&mut self) {
+        either::for_both!(self, pl => pl.refresh())
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        either::for_both!(self, pl => pl.info(args))
+    }
+}
+
+/// An implementation of [`ProgressLog`] with output generated using the
+/// [`log`](https://docs.rs/log) crate at the `info` level.
+///
+/// Instances can be created by using fluent setters, or by using the
+/// [`progress_logger`] macro.
+///
+/// You can [clone](#impl-Clone-for-ProgressLogger) a logger to create a new one
+/// with the same setup but with all the counters reset.
+///
+/// # Examples
+///
+/// A typical call sequence to a progress logger is as follows:
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dsi_progress_logger::prelude::*;
+///
+/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+///
+/// let mut pl = ProgressLogger::default();
+/// pl.item_name("pumpkin");
+/// pl.start("Smashing pumpkins...");
+/// for _ in 0..100 {
+///    // do something on each pumpkin
+///    pl.update();
+/// }
+/// pl.done();
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// The [`progress_logger`] macro will create the progress logger for you and
+/// set its [`log_target`](ProgressLog::log_target) to [`std::module_path!()`],
+/// which is usually what you want. You can also call any setter with a
+/// key-value syntax:
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dsi_progress_logger::prelude::*;
+///
+/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+///
+/// let mut pl = progress_logger![item_name="pumpkin"];
+/// pl.start("Smashing pumpkins...");
+/// for _ in 0..100 {
+///    // do something on each pumpkin
+///    pl.update();
+/// }
+/// pl.done();
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// A progress logger can also be used as a handy timer:
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dsi_progress_logger::prelude::*;
+///
+/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+///
+/// let mut pl = progress_logger![item_name="pumpkin"];
+/// pl.start("Smashing pumpkins...");
+/// for _ in 0..100 {
 ///    // do something on each pumpkin
-///    pl.update();
 /// }
-/// pl.done();
+/// pl.done_with_count(100);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// This progress logger will display information about  memory usage:
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dsi_progress_logger::prelude::*;
+///
+/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+///
+/// let mut pl = progress_logger![display_memory=true];
 /// #     Ok(())
 /// # }
 /// ```
+pub struct ProgressLogger {
+    /// The name of an item. Defaults to `item`.
+    item_name: String,
+    /// The plural form of the name of an item. Defaults to `items`.
+    item_name_plural: String,
+    /// The log interval. Defaults to 10 seconds.
+    log_interval: Duration,
+    /// The maximum relative random variation of the log interval.
+    log_interval_jitter: f64,
+    /// The state of the pseudorandom generator used for jitter.
+    rng_state: u64,
+    /// The expected number of updates. If set, the logger will display the percentage of completion and
+    /// an estimate of the time to completion.
+    expected_updates: Option<usize>,
+    /// Whether the expected number of updates was set by
+    /// [`start_with_expected`](ProgressLog::start_with_expected), in which
+    /// case it will be cleared by the next call to
+    /// [`start`](ProgressLog::start).
+    expected_updates_from_start: bool,
+    /// The time unit to use for speed. If set, the logger will always display the speed in this unit
+    /// instead of making a choice of readable unit based on the elapsed time. Moreover, large numbers
+    /// will not be thousands separated. This is useful when the output of the logger must be parsed.
+    time_unit: Option<TimeUnit>,
+    /// Display additionally the speed achieved during the last log intervals.
+    local_speed: bool,
+    /// The number of log intervals over which the local speed is computed.
+    local_speed_window: usize,
+    /// The speed, in items per second, below which logs are escalated to the
+    /// `warn` level.
+    min_speed: Option<f64>,
+    /// Whether to skip logs when no update happened during the log interval.
+    skip_idle: bool,
+    /// The number of log intervals skipped since the last log.
+    idle_intervals: usize,
+    /// The maximum number of periodic logs of an activity.
+    max_logs: Option<usize>,
+    /// The number of periodic logs of the current activity.
+    logs: usize,
+    /// The time before which no progress line is logged, if the [maximum
+    /// number of logs](ProgressLog::max_logs) is set.
+    next_line_time: Option<Instant>,
+    /// The minimum duration of an activity before any output.
+    min_duration: Option<Duration>,
+    /// Whether the start message of the current activity has been logged.
+    start_logged: bool,
+    /// The target speed, in items per second.
+    target_speed: Option<f64>,
+    /// The failure rates above which logs are escalated to the `warn` and
+    /// `error` levels.
+    failure_rate_thresholds: (Option<f64>, Option<f64>),
+    /// The policy to apply when the count exceeds the expected number of
+    /// updates.
+    overshoot_policy: OvershootPolicy,
+    /// Whether the overshoot of the expected number of updates has already
+    /// been reported.
+    overshoot_reported: bool,
+    /// The expected duration of the activity.
+    expected_duration: Option<Duration>,
+    /// The time budget for the activity.
+    time_budget: Option<Duration>,
+    /// Whether a projected overrun of the time budget has already been
+    /// reported.
+    projected_overrun_reported: bool,
+    /// Whether an overrun of the time budget has already been reported.
+    overrun_reported: bool,
+    /// A callback invoked when the elapsed time exceeds the time budget.
+    on_time_budget_exceeded: Option<Arc<dyn Fn() + Send + Sync>>,
+    /// A flag signalling that the activity should be cancelled.
+    cancel_flag: Option<Arc<AtomicBool>>,
+    /// A file to which a machine-readable progress line is written at each
+    /// log, and the temporary file used to replace it atomically.
+    progress_file: Option<(PathBuf, PathBuf)>,
+    /// A buffer for the progress line, reused to avoid allocations.
+    progress_line: String,
+    /// A file to which a report is written at completion.
+    report_file: Option<PathBuf>,
+    /// A file to which a Chrome trace is written at completion.
+    trace_file: Option<PathBuf>,
+    /// The elapsed time and the count at every `history_stride` logs,
+    /// recorded only if a report or a trace is written.
+    history: Vec<(Duration, usize)>,
+    /// The number of logs per entry of `history`.
+    history_stride: usize,
+    /// The number of logs not recorded in `history` since the last entry.
+    history_skipped: usize,
+    /// The spans of the nested activities added since the logger was started.
+    spans: Vec<Span>,
+    /// Whether to log the tree of spans at completion.
+    log_span_tree: bool,
+    /// The format of continuous-integration service messages.
+    ci_format: Option<CiFormat>,
+    /// Whether to log the stats if the logger is dropped while running.
+    log_on_drop: bool,
+    /// The snapshot of this logger in the global registry, if registered.
+    snapshot: Option<Arc<Mutex<registry::Snapshot>>>,
+    /// Display additionally the number of items remaining to reach the
+    /// expected number of updates.
+    display_remaining: bool,
+    /// The format of elapsed times.
+    elapsed_format: ElapsedFormat,
+    /// Whether to display an absolute timestamp.
+    display_timestamp: bool,
+    /// Display additionally the count as a fraction of the expected number of
+    /// updates.
+    display_fraction: bool,
+    /// Whether to log the configuration at start and its changes.
+    log_config: bool,
+    /// [`log`] target
+    ///
+    /// This is often the path of the module logging progress.
+    log_target: String,
+    /// The message passed to the last call to [`start`](ProgressLog::start).
+    start_msg: String,
+    /// When the logger was started.
+    start_time: Option<Instant>,
+    /// The time and count of the last
+    /// [`local_speed_window`](ProgressLog::local_speed_window) logs (to
+    /// compute local speed), oldest first.
+    log_history: VecDeque<(Instant, usize)>,
+    /// The minimum and maximum number of seconds per item over the log
+    /// intervals completed so far.
+    interval_seconds_per_item: Option<(f64, f64)>,
+    /// The next time we will log the activity.
+    next_log_time: Deadline,
+    /// When the logger was stopped.
+    stop_time: Option<Instant>,
+    /// The number of items.
+    count: usize,
+    /// The number of failed items.
+    failed: usize,
+    /// The number of items already done when the activity was
+    /// [resumed](ProgressLog::start_resumed).
+    count_offset: usize,
+    /// Display additionally the amount of used and free memory.
+    display_memory: bool,
+    /// Display additionally the load averages of the system.
+    display_load_average: bool,
+    /// A path whose available disk space is displayed, and the available
+    /// space below which a warning is logged.
+    free_space: Option<(PathBuf, u64)>,
+    /// Whether the available space is below the minimum and a warning has
+    /// already been logged.
+    free_space_warned: bool,
+    /// The [`sysinfo::System`] used to read memory information and load
+    /// averages, and the time of its last refresh.
+    ///
+    /// The system is protected by a mutex so that it can be refreshed by the
+    /// [`Display`] implementation.
+    system: Option<Mutex<(System, Option<Instant>)>>,
+    /// The minimum interval between two refreshes of `system`.
+    memory_refresh_interval: Duration,
+    /// Display additionally the change in resident-set size since the last
+    /// log.
+    display_memory_delta: bool,
+    /// The resident-set size at the last log (to compute its change).
+    last_res_mem: Option<u64>,
+    /// The resident-set size at each log, downsampled to the width of its
+    /// sparkline, recorded only if memory is displayed.
+    res_mem_history: Downsampled,
+    /// The fraction of the memory limit above which the resident-set size
+    /// triggers a warning.
+    memory_warning_threshold: Option<f64>,
+    /// Whether the memory warning has already been logged.
+    memory_warned: bool,
+    /// The memory limit of the control group of the current process, read at
+    /// the first memory check.
+    cgroup_memory_limit: Option<Option<u64>>,
+    /// The pid of the current process, if available on this platform
+    pid: Option<Pid>,
+    /// User-defined metrics.
+    metrics: Vec<Metric>,
+    /// The smoothing applied to the estimated time to end.
+    eta_smoothing: EtaSmoothing,
+    /// The smoothed estimated completion time, revised at each log.
+    eta_deadline: Option<Instant>,
+    /// The coefficient of variation of the speed of recent log intervals above
+    /// which the time to end is displayed as a range.
+    eta_range_threshold: Option<f64>,
+    /// The speeds, in items per second, of the last
+    /// [`RECENT_SPEEDS_WINDOW`](Self::RECENT_SPEEDS_WINDOW) log intervals, or
+    /// of the log intervals displayed in the sparkline, if more.
+    recent_speeds: VecDeque<f64>,
+    /// The number of log intervals whose speed is displayed as a sparkline.
+    speed_sparkline: Option<usize>,
+    /// Whether to pad numbers to fixed widths.
+    fixed_width: bool,
+    /// The fields to display.
+    display_fields: DisplayFields,
+    /// Whether to display the time per item before the speed.
+    timing_first: bool,
+    /// The count at which [`update`](ProgressUpdate::update) will check
+    /// again whether it is time to log.
+    next_check_count: usize,
+    /// The number of updates between the last two checks of the time.
+    check_stride: usize,
+    /// The number of updates between checks of the time predicted at the
+    /// last log.
+    predicted_stride: usize,
+    /// Whether logs are formatted and emitted by a worker thread.
+    deferred_logging: bool,
+    /// The worker thread used for deferred logging, if spawned.
+    worker: Option<Worker>,
+    /// Whether the configuration and the state of the logger have been sent
+    /// to the worker since the last start.
+    worker_started: bool,
+    /// The numerator and denominator names of the ratios to display.
+    ratios: Vec<(String, String)>,
+    /// Whether items are bytes, in which case counts and speeds are
+    /// displayed using unit prefixes.
+    bytes: bool,
+    /// The unit prefixes used when items are bytes.
+    byte_units: ByteUnits,
+    /// The state published for [handles](ProgressLogger::handle), if any.
+    handle: Option<Arc<HandleState>>,
+    /// The handles of the metrics published, registered at the first log
+    /// with the current log target.
+    #[cfg(feature = "metrics")]
+    metric_handles: std::sync::OnceLock<MetricHandles>,
+    /// The count when metrics were last published.
+    #[cfg(feature = "metrics")]
+    published_count: AtomicUsize,
+}
+
+/// The handles of the metrics published by a [`ProgressLogger`].
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct MetricHandles {
+    count: metrics::Counter,
+    items_per_second: metrics::Gauge,
+    percent: metrics::Gauge,
+}
+
+/// A named floating-point metric, with summary statistics.
+#[derive(Debug, Clone)]
+struct Metric {
+    name: String,
+    last: f64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    n: usize,
+}
+
+impl Metric {
+    fn new(name: &str, value: f64) -> Self {
+        Self {
+            name: name.into(),
+            last: value,
+            min: value,
+            max: value,
+            sum: value,
+            n: 1,
+        }
+    }
+
+    fn update(&mut self, value: f64) {
+        self.last = value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.n += 1;
+    }
+}
+
+/// Return the memory limit of the control group of the current process, if
+/// any.
+///
+/// The control group is read from `/proc/self/cgroup`; since the limits of
+/// the ancestors of a control group apply too, the smallest limit along the
+/// path is returned.
+#[cfg(target_os = "linux")]
+fn cgroup_memory_limit() -> Option<u64> {
+    let cgroups = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    cgroups
+        .lines()
+        .filter_map(|line| {
+            // Lines have the form hierarchy-ID:controller-list:cgroup-path
+            let mut fields = line.splitn(3, ':');
+            let (_, controllers, cgroup) = (fields.next()?, fields.next()?, fields.next()?);
+            // cgroup v2 has a single hierarchy with an empty controller list
+            let (root, file) = if controllers.is_empty() {
+                ("/sys/fs/cgroup", "memory.max")
+            } else if controllers
+                .split(',')
+                .any(|controller| controller == "memory")
+            {
+                ("/sys/fs/cgroup/memory", "memory.limit_in_bytes")
+            } else {
+                return None;
+            };
+            Path::new(cgroup)
+                .ancestors()
+                .filter_map(|dir| {
+                    let path = Path::new(root).join(dir.strip_prefix("/").ok()?).join(file);
+                    // cgroup v2 uses "max" for no limit
+                    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+                })
+                .min()
+        })
+        .min()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cgroup_memory_limit() -> Option<u64> {
+    None
+}
+
+/// The policy applied by a [`ProgressLogger`] when the count exceeds the
+/// [expected number of updates](ProgressLog::expected_updates).
+///
+/// The overshoot is reported at most once between two calls to
+/// [`start`](ProgressLog::start).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OvershootPolicy {
+    /// Do nothing.
+    Ignore,
+    /// Log a warning.
+    #[default]
+    Warn,
+    /// Log a warning and, in debug builds, panic.
+    DebugAssert,
+}
+
+/// The smoothing applied by a [`ProgressLogger`] to the estimated time to end.
+///
+/// See [`ProgressLog::eta_smoothing`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EtaSmoothing {
+    /// Use the raw estimate, based on the average speed since the start.
+    #[default]
+    Raw,
+    /// Revise the estimated completion time only downwards, unless it has
+    /// already passed.
+    Monotone,
+    /// Revise the estimated completion time only if the new estimate of the
+    /// time to end differs from the current one by more than the given
+    /// fraction of the latter (e.g., 0.2 for 20%), or if it has already
+    /// passed. A negative or NaN tolerance is treated as zero.
+    Hysteresis(f64),
+}
+
+/// The unit prefixes used by a [`ProgressLogger`] counting bytes.
+///
+/// See [`ProgressLogger::for_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteUnits {
+    /// SI prefixes (powers of 1000, e.g., `MB`).
+    #[default]
+    Si,
+    /// Binary prefixes (powers of 1024, e.g., `MiB`).
+    Binary,
+}
+
+/// A set of fields displayed by a [`ProgressLogger`].
+///
+/// Sets can be combined using the usual bitwise operators. See
+/// [`ProgressLog::display_fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayFields(u8);
+
+impl DisplayFields {
+    /// No field.
+    pub const NONE: Self = Self(0);
+    /// The number of items.
+    pub const COUNT: Self = Self(1 << 0);
+    /// The elapsed time.
+    pub const ELAPSED: Self = Self(1 << 1);
+    /// The speed (e.g., items per second).
+    pub const SPEED: Self = Self(1 << 2);
+    /// The time per item (e.g., seconds per item).
+    pub const TIMING: Self = Self(1 << 3);
+    /// The percentage of completion.
+    pub const PERCENT: Self = Self(1 << 4);
+    /// The estimated time to end.
+    pub const ETA: Self = Self(1 << 5);
+    /// All fields.
+    pub const ALL: Self = Self((1 << 6) - 1);
+
+    /// Return whether all fields of `other` are in this set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Return whether some field of `other` is in this set.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl Default for DisplayFields {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for DisplayFields {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for DisplayFields {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for DisplayFields {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for DisplayFields {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
+
+/// A format for continuous-integration service messages.
+///
+/// See [`ProgressLog::ci_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiFormat {
+    /// GitHub Actions `::notice` workflow commands, printed at the start and
+    /// at the end of an activity.
+    GitHubActions,
+    /// TeamCity `progressStart`, `progressMessage` and `progressFinish`
+    /// service messages.
+    TeamCity,
+}
+
+impl CiFormat {
+    /// Detect the continuous-integration service from the environment.
+    ///
+    /// Returns [`GitHubActions`](CiFormat::GitHubActions) if the environment
+    /// variable `GITHUB_ACTIONS` is `true`, [`TeamCity`](CiFormat::TeamCity)
+    /// if the environment variable `TEAMCITY_VERSION` is set, and [`None`]
+    /// otherwise.
+    pub fn detect() -> Option<Self> {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true") {
+            Some(CiFormat::GitHubActions)
+        } else if std::env::var_os("TEAMCITY_VERSION").is_some() {
+            Some(CiFormat::TeamCity)
+        } else {
+            None
+        }
+    }
+
+    /// Print the message signalling the start of an activity.
+    fn print_start(&self, msg: &str) {
+        match self {
+            CiFormat::GitHubActions if msg.is_empty() => {}
+            CiFormat::GitHubActions => {
+                println!("::notice::{}", Escaped(Escape::GitHubData, msg));
+            }
+            CiFormat::TeamCity => {
+                println!(
+                    "##teamcity[progressStart '{}']",
+                    Escaped(Escape::TeamCity, msg)
+                );
+            }
+        }
+    }
+
+    /// Print a progress message.
+    ///
+    /// GitHub Actions notices are annotations, so they are not printed at
+    /// each log, but only at the start and at the end of an activity.
+    fn print_progress(&self, progress: impl Display) {
+        if let CiFormat::TeamCity = self {
+            println!(
+                "##teamcity[progressMessage '{}']",
+                Escaped(Escape::TeamCity, progress)
+            );
+        }
+    }
+
+    /// Print the messages signalling the end of an activity.
+    fn print_finish(&self, msg: &str, progress: impl Display) {
+        match self {
+            CiFormat::GitHubActions if msg.is_empty() => {
+                println!("::notice::{}", Escaped(Escape::GitHubData, progress));
+            }
+            CiFormat::GitHubActions => {
+                println!(
+                    "::notice title={}::{}",
+                    Escaped(Escape::GitHubProperty, msg),
+                    Escaped(Escape::GitHubData, progress)
+                );
+            }
+            CiFormat::TeamCity => {
+                self.print_progress(progress);
+                println!(
+                    "##teamcity[progressFinish '{}']",
+                    Escaped(Escape::TeamCity, msg)
+                );
+            }
+        }
+    }
+}
+
+/// An escaping scheme for continuous-integration service messages.
+#[derive(Clone, Copy)]
+enum Escape {
+    /// Values of TeamCity service messages.
+    TeamCity,
+    /// Data of GitHub Actions workflow commands.
+    GitHubData,
+    /// Properties of GitHub Actions workflow commands, which require escaping
+    /// also `:` and `,`.
+    GitHubProperty,
+}
+
+impl Escape {
+    /// Return the escape sequence for the given character, if it must be
+    /// escaped.
+    fn escape(self, c: char) -> Option<&'static str> {
+        match (self, c) {
+            (Escape::TeamCity, '|') => Some("||"),
+            (Escape::TeamCity, '\'') => Some("|'"),
+            (Escape::TeamCity, '[') => Some("|["),
+            (Escape::TeamCity, ']') => Some("|]"),
+            (Escape::TeamCity, '\n') => Some("|n"),
+            (Escape::TeamCity, '\r') => Some("|r"),
+            (Escape::TeamCity, _) => None,
+            (_, '%') => Some("%25"),
+            (_, '\r') => Some("%0D"),
+            (_, '\n') => Some("%0A"),
+            (Escape::GitHubProperty, ':') => Some("%3A"),
+            (Escape::GitHubProperty, ',') => Some("%2C"),
+            _ => None,
+        }
+    }
+}
+
+/// Displays a value escaped for continuous-integration service messages,
+/// without allocating.
+struct Escaped<T>(Escape, T);
+
+impl<T: Display> Display for Escaped<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(EscapingWriter(self.0, f), "{}", self.1)
+    }
+}
+
+/// A writer escaping the strings written to a formatter.
+struct EscapingWriter<'a, 'b>(Escape, &'a mut Formatter<'b>);
+
+impl std::fmt::Write for EscapingWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> Result {
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            if let Some(escape) = self.0.escape(c) {
+                self.1.write_str(&s[start..i])?;
+                self.1.write_str(escape)?;
+                start = i + c.len_utf8();
+            }
+        }
+        self.1.write_str(&s[start..])
+    }
+}
+
+/// A locked system, with the time of its last refresh, dereferencing to the
+/// system.
+struct LockedSystem<'a>(MutexGuard<'a, (System, Option<Instant>)>);
+
+impl std::ops::Deref for LockedSystem<'_> {
+    type Target = System;
+
+    fn deref(&self) -> &System {
+        &self.0 .0
+    }
+}
+
+/// The labels of the process memory, of the swap, and of the change of the
+/// process memory in the display of memory information.
+#[cfg(not(windows))]
+const MEMORY_LABELS: [&str; 3] = ["res/vir", "used/total swap", "res"];
+/// The labels of the process memory, of the swap, and of the change of the
+/// process memory in the display of memory information.
+#[cfg(windows)]
+const MEMORY_LABELS: [&str; 3] = ["ws/private", "commit charge/limit", "ws"];
+
+/// The time and system information displayed by a [`ProgressLogger`].
+struct Readings {
+    /// The current time.
+    now: Instant,
+    /// The time elapsed since the logger was started, up to now or to the time
+    /// it was stopped.
+    elapsed: Duration,
+    /// The current time since the Unix epoch.
+    since_epoch: Duration,
+    /// Memory information, if memory is displayed.
+    memory: Option<MemoryReadings>,
+    /// The available space on the monitored disk, if any.
+    free_space: Option<u64>,
+    /// The load average over 1, 5, and 15 minutes, if it is displayed.
+    load_average: Option<[f64; 3]>,
+}
+
+/// Memory information, in bytes, displayed by a [`ProgressLogger`].
+struct MemoryReadings {
+    /// The resident-set size of the process, if available.
+    res: Option<u64>,
+    /// The virtual memory of the process, if available.
+    vir: Option<u64>,
+    available: u64,
+    free: u64,
+    total: u64,
+    used_swap: u64,
+    total_swap: u64,
+}
+
+/// Displays a [`ProgressLogger`] using given readings.
+struct Rendered<'a>(&'a ProgressLogger, &'a Readings);
+
+impl Display for Rendered<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.0.fmt_with(f, self.1)
+    }
+}
+
+/// A message for the worker thread of a [`ProgressLogger`] with [deferred
+/// logging](ProgressLog::deferred_logging).
+enum WorkerMessage {
+    /// Replace the logger used by the worker, which is a copy of the
+    /// configuration and of the state of the original logger.
+    Start(Box<ProgressLogger>),
+    /// Log with the given time, counts, and metrics (if changed).
+    Log {
+        now: Instant,
+        count: usize,
+        failed: usize,
+        metrics: Option<Vec<Metric>>,
+    },
+    /// Reply once all previous messages have been processed.
+    Flush(mpsc::Sender<()>),
+}
+
+/// A worker thread logging on behalf of a [`ProgressLogger`].
+struct Worker {
+    sender: mpsc::SyncSender<WorkerMessage>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl Worker {
+    /// The number of messages that can be pending; further logs are dropped
+    /// until the worker catches up.
+    const CAPACITY: usize = 16;
+
+    fn spawn() -> Self {
+        let (sender, receiver) = mpsc::sync_channel(Self::CAPACITY);
+        let handle = thread::spawn(move || {
+            let mut pl: Option<Box<ProgressLogger>> = None;
+            for message in receiver {
+                match message {
+                    WorkerMessage::Start(logger) => pl = Some(logger),
+                    WorkerMessage::Log {
+                        now,
+                        count,
+                        failed,
+                        metrics,
+                    } => {
+                        if let Some(pl) = &mut pl {
+                            pl.count = count;
+                            pl.failed = failed;
+                            if let Some(metrics) = metrics {
+                                pl.metrics = metrics;
+                            }
+                            pl.log(now);
+                        }
+                    }
+                    WorkerMessage::Flush(reply) => {
+                        let _ = reply.send(());
+                    }
+                }
+            }
+        });
+        Self { sender, handle }
+    }
+}
+
+/// Displays a speed, given as seconds per item, as a [`ProgressLogger`] would.
+struct Speed<'a>(&'a ProgressLogger, f64);
+
+impl Display for Speed<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.0.fmt_timing_speed(f, self.1)
+    }
+}
+
+/// Macro to create a [`ProgressLogger`] with default log target set to
+/// [`std::module_path!`], and key-value pairs instead of setters.
+///
+/// # Examples
+///
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut pl = progress_logger![item_name="pumpkin", display_memory=true];
+/// ```
+#[macro_export]
+macro_rules! progress_logger {
+    ($($method:ident = $arg:expr),* $(,)?) => {
+        {
+            let mut pl = ::dsi_progress_logger::ProgressLogger::default();
+            ::dsi_progress_logger::ProgressLog::log_target(&mut pl, ::std::module_path!());
+            $(
+                ::dsi_progress_logger::ProgressLog::$method(&mut pl, $arg);
+            )*
+            pl
+        }
+    }
+}
+
+/// Macro creating a [`ProgressLogger`] as [`progress_logger!`] does, starting it
+/// with a given message, and executing a block in which the logger is
+/// available with the given name.
+///
+/// The logger is wrapped in a [`DoneOnDrop`] guard, so
+/// [`done`](ProgressLog::done) is called at the end of the block, even if the
+/// block is left early (e.g., by the `?` operator). The value of the macro is
+/// the value of the block.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// fn increment(v: &mut [u64]) -> Result<(), std::fmt::Error> {
+///     progress! {
+///         pl: item_name = "arc", expected_updates = Some(v.len());
+///         "Incrementing arcs..." => {
+///             for x in v.iter_mut() {
+///                 *x = x.checked_add(1).ok_or(std::fmt::Error)?;
+///                 pl.light_update();
+///             }
+///         }
+///     }
+///     Ok(())
+/// }
+///
+/// increment(&mut [0; 1000]).unwrap();
+/// ```
+#[macro_export]
+macro_rules! progress {
+    ($pl:ident $(: $($method:ident = $arg:expr),* $(,)?)?; $msg:expr => $body:block) => {
+        {
+            #[allow(unused_mut)]
+            let mut $pl = ::dsi_progress_logger::DoneOnDrop::new(
+                ::dsi_progress_logger::progress_logger![$($($method = $arg),*)?]
+            );
+            ::dsi_progress_logger::ProgressLog::start(&mut *$pl, $msg);
+            $body
+        }
+    }
+}
+
+/// A guard calling [`done`](ProgressLog::done) on a [`ProgressLog`] when
+/// dropped, unless the logger has already been stopped.
+///
+/// The guard dereferences to the underlying logger. It is used by the
+/// [`progress!`] macro, but it can also be used directly to make sure that
+/// an activity is marked as done on all exit paths.
+#[derive(Debug)]
+pub struct DoneOnDrop<P: ProgressLog>(P);
+
+impl<P: ProgressLog> DoneOnDrop<P> {
+    /// Wrap a logger in a guard.
+    pub fn new(pl: P) -> Self {
+        Self(pl)
+    }
+}
+
+impl<P: ProgressLog> core::ops::Deref for DoneOnDrop<P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<P: ProgressLog> core::ops::DerefMut for DoneOnDrop<P> {
+    fn deref_mut(&mut self) -> &mut P {
+        &mut self.0
+    }
+}
+
+impl<P: ProgressLog> Drop for DoneOnDrop<P> {
+    fn drop(&mut self) {
+        if self.0.is_started() && !self.0.is_stopped() {
+            self.0.done();
+        }
+    }
+}
+
+/// Create a default [`ProgressLogger`] with a log interval of 10 seconds and
+/// item name set to “item”.
+impl Default for ProgressLogger {
+    #[track_caller]
+    fn default() -> Self {
+        Self {
+            item_name: "item".into(),
+            item_name_plural: "items".into(),
+            log_interval: Duration::from_secs(10),
+            log_interval_jitter: 0.0,
+            rng_state: RandomState::new().build_hasher().finish() | 1,
+            expected_updates: None,
+            expected_updates_from_start: false,
+            time_unit: None,
+            local_speed: false,
+            local_speed_window: 1,
+            min_speed: None,
+            skip_idle: false,
+            idle_intervals: 0,
+            max_logs: None,
+            logs: 0,
+            next_line_time: None,
+            min_duration: None,
+            start_logged: true,
+            target_speed: None,
+            failure_rate_thresholds: (None, None),
+            overshoot_policy: OvershootPolicy::Warn,
+            overshoot_reported: false,
+            expected_duration: None,
+            time_budget: None,
+            projected_overrun_reported: false,
+            overrun_reported: false,
+            on_time_budget_exceeded: None,
+            cancel_flag: None,
+            progress_file: None,
+            progress_line: String::new(),
+            report_file: None,
+            trace_file: None,
+            history: vec![],
+            history_stride: 1,
+            history_skipped: 0,
+            spans: vec![],
+            log_span_tree: false,
+            ci_format: None,
+            log_on_drop: false,
+            snapshot: None,
+            display_remaining: false,
+            elapsed_format: ElapsedFormat::Pretty,
+            display_timestamp: false,
+            display_fraction: false,
+            log_config: false,
+            log_target: utils::caller_target(Location::caller().file(), utils::executable_name)
+                .unwrap_or_else(utils::executable_name),
+            start_msg: String::new(),
+            start_time: None,
+            log_history: VecDeque::from([(clock::now(), 0)]),
+            interval_seconds_per_item: None,
+            next_log_time: Deadline::new(clock::now()),
+            stop_time: None,
+            count: 0,
+            count_offset: 0,
+            failed: 0,
+            display_memory: false,
+            display_load_average: false,
+            free_space: None,
+            system: None,
+            memory_refresh_interval: Duration::ZERO,
+            display_memory_delta: false,
+            last_res_mem: None,
+            res_mem_history: Downsampled::new(Self::MEMORY_SPARKLINE_WIDTH),
+            memory_warning_threshold: None,
+            memory_warned: false,
+            cgroup_memory_limit: None,
+            free_space_warned: false,
+            pid: get_current_pid().ok(),
+            metrics: vec![],
+            eta_smoothing: EtaSmoothing::default(),
+            eta_deadline: None,
+            eta_range_threshold: None,
+            recent_speeds: VecDeque::new(),
+            speed_sparkline: None,
+            fixed_width: false,
+            display_fields: DisplayFields::ALL,
+            timing_first: false,
+            next_check_count: 0,
+            check_stride: 1,
+            predicted_stride: 1,
+            deferred_logging: false,
+            worker: None,
+            worker_started: false,
+            ratios: vec![],
+            bytes: false,
+            byte_units: ByteUnits::Si,
+            handle: None,
+            #[cfg(feature = "metrics")]
+            metric_handles: std::sync::OnceLock::new(),
+            #[cfg(feature = "metrics")]
+            published_count: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ProgressLogger {
+    /// Calls to [light_update](ProgressUpdate::light_update) will cause a call to
+    /// [`Instant::now`] only if the current count is a multiple of this mask
+    /// plus one.
+    pub const LIGHT_UPDATE_MASK: usize = (1 << 20) - 1;
+
+    /// The maximum time between two checks of the time performed by
+    /// [`update`](ProgressUpdate::update) as predicted from the speed of the
+    /// last log interval.
+    pub const MAX_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// The number of log intervals over which the local speed of a logger
+    /// [counting bytes](Self::for_bytes) is smoothed.
+    pub const BYTES_LOCAL_SPEED_WINDOW: usize = 4;
+
+    /// The number of log intervals whose speed is used to decide whether to
+    /// display the time to end [as a range](ProgressLog::eta_range_threshold).
+    pub const RECENT_SPEEDS_WINDOW: usize = 10;
+
+    /// The maximum number of logs recorded for [reports](ProgressLog::report_file)
+    /// and [traces](ProgressLog::trace_file); beyond this number, the recorded
+    /// logs are downsampled.
+    pub const HISTORY_CAPACITY: usize = 1024;
+
+    /// The maximum width of the sparkline of the resident-set size displayed
+    /// by [`done`](ProgressLog::done).
+    pub const MEMORY_SPARKLINE_WIDTH: usize = 40;
+
+    /// The width of counts when [using fixed widths](ProgressLog::fixed_width).
+    pub const COUNT_WIDTH: usize = 15;
+
+    /// The width of amounts of bytes when [using fixed
+    /// widths](ProgressLog::fixed_width).
+    pub const BYTES_WIDTH: usize = 9;
+
+    /// The width of elapsed times when [using fixed
+    /// widths](ProgressLog::fixed_width).
+    pub const ELAPSED_WIDTH: usize = 14;
+
+    /// The width of percentages when [using fixed
+    /// widths](ProgressLog::fixed_width).
+    pub const PERCENT_WIDTH: usize = 6;
+
+    /// The width of speeds when [using fixed widths](ProgressLog::fixed_width).
+    pub const SPEED_WIDTH: usize = 10;
+
+    /// Create a default logger and [start](ProgressLog::start) it with the
+    /// given message.
+    ///
+    /// This constructor is handy when the logger is used just as a timer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = ProgressLogger::started("Computing the answer...");
+    /// let answer = 6 * 7;
+    /// pl.done();
+    /// ```
+    #[track_caller]
+    pub fn started(msg: impl AsRef<str>) -> Self {
+        let mut pl = Self::default();
+        pl.start(msg);
+        pl
+    }
+
+    /// Create a logger counting bytes.
+    ///
+    /// The logger displays the amount of data processed and the throughput
+    /// using unit prefixes (e.g., `12.34MB, 2s, 6.17MB/s`) instead of counts
+    /// of items and timings per item, and it can be used with a
+    /// [`ProgressReader`]. SI prefixes are used by default; use
+    /// [`byte_units`](Self::byte_units) to choose binary prefixes.
+    ///
+    /// Since writes through the page cache and network transfers are usually
+    /// bursty, the logger also displays the [local
+    /// speed](ProgressLog::local_speed) smoothed over the last
+    /// [`BYTES_LOCAL_SPEED_WINDOW`](Self::BYTES_LOCAL_SPEED_WINDOW) log
+    /// intervals (see [`local_speed_window`](ProgressLog::local_speed_window)).
+    /// The smoothing does not affect the estimate of the time to completion,
+    /// which is based on the overall speed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = ProgressLogger::for_bytes();
+    /// pl.byte_units(ByteUnits::Binary);
+    /// pl.start("Copying...");
+    /// for _ in 0..1000 {
+    ///     pl.update_with_count(1 << 16);
+    /// }
+    /// pl.done();
+    /// ```
+    #[track_caller]
+    pub fn for_bytes() -> Self {
+        let mut pl = Self::default();
+        pl.item_name("byte")
+            .local_speed(true)
+            .local_speed_window(Self::BYTES_LOCAL_SPEED_WINDOW);
+        pl.bytes = true;
+        pl
+    }
+
+    /// Set the unit prefixes used to display amounts of data and throughputs
+    /// when the logger [counts bytes](Self::for_bytes).
+    pub fn byte_units(&mut self, byte_units: ByteUnits) -> &mut Self {
+        self.byte_units = byte_units;
+        self
+    }
+
+    /// Create a logger for a transfer of bytes of known length (e.g., a
+    /// download with a known `Content-Length`).
+    ///
+    /// The logger displays the amount of data transferred and the speed using
+    /// SI prefixes (e.g., `12.34MB/100.00MB, 2s, 6.17MB/s; 12.34% done`), and
+    /// can be used with a [`ProgressReader`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    /// use std::io::Read;
+    ///
+    /// let data = vec![0_u8; 1 << 20];
+    /// let mut pl = ProgressLogger::with_content_length(data.len());
+    /// pl.log_target(std::module_path!());
+    /// pl.start("Downloading...");
+    /// let mut buffer = vec![];
+    /// ProgressReader::new(data.as_slice(), &mut pl).read_to_end(&mut buffer)?;
+    /// pl.done();
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_content_length(content_length: usize) -> Self {
+        let mut pl = Self::for_bytes();
+        pl.expected_updates(Some(content_length));
+        pl
+    }
+
+    /// Return a [`ConcurrentWrapper`] around a
+    /// [clone](#impl-Clone-for-ProgressLogger) of this logger, that is, a
+    /// logger with the same setup but with all the counters reset.
+    ///
+    /// See [`concurrent_shared`](Self::concurrent_shared) for a wrapper
+    /// sharing the state of this logger.
+    pub fn concurrent(&self) -> ConcurrentWrapper {
+        ConcurrentWrapper::wrap(self.clone())
+    }
+
+    /// Return a [`ConcurrentWrapper`] sharing the state of this logger.
+    ///
+    /// Updates to the wrapper (and to its clones) are applied to this logger,
+    /// so a sequential phase and a parallel phase can accumulate into the
+    /// same counters. This logger can be used again once all copies of the
+    /// wrapper have been dropped (and thus flushed).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![item_name = "pumpkin"];
+    /// pl.start("Smashing pumpkins...");
+    /// for _ in 0..1000 {
+    ///     pl.update();
+    /// }
+    ///
+    /// pl.concurrent_shared().scope(|s| {
+    ///     for _ in 0..4 {
+    ///         s.spawn(|mut cpl| {
+    ///             for _ in 0..1000 {
+    ///                 cpl.update();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(pl.count(), 5000);
+    /// pl.done();
+    /// ```
+    pub fn concurrent_shared(&mut self) -> ConcurrentWrapper<&mut Self> {
+        ConcurrentWrapper::wrap(self)
+    }
+
+    /// Return a [`ProgressHandle`] that other threads can use to poll the
+    /// state of this logger without touching it.
+    ///
+    /// All handles returned by this method share the same state, which is not
+    /// shared with [clones](#impl-Clone-for-ProgressLogger) of this logger.
+    pub fn handle(&mut self) -> ProgressHandle {
+        if self.handle.is_none() {
+            self.handle = Some(Arc::new(HandleState::new(
+                self.start_time.unwrap_or_else(clock::now),
+            )));
+            self.publish_handle();
+        }
+        ProgressHandle(self.handle.clone().unwrap())
+    }
+
+    /// Clone the logger, including its counters and timing.
+    ///
+    /// Unlike [`clone`](Clone::clone), which returns a logger with the same
+    /// setup but with all the counters reset, this method returns a snapshot
+    /// of the logger that can be compared later with the original one, or
+    /// displayed. The snapshot does not [log when
+    /// dropped](ProgressLog::log_on_drop), and it is not reported by the
+    /// [panic hook](install_panic_hook).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut pl = progress_logger![];
+    /// pl.start("Processing...");
+    /// pl.update_with_count(10);
+    /// let snapshot = pl.clone_with_state();
+    /// pl.update_with_count(5);
+    /// assert_eq!(snapshot.count(), 10);
+    /// assert_eq!(pl.count() - snapshot.count(), 5);
+    /// pl.done();
+    /// ```
+    pub fn clone_with_state(&self) -> Self {
+        let mut pl = self.clone();
+        pl.log_on_drop = false;
+        pl.rng_state = self.rng_state;
+        pl.expected_updates = self.expected_updates;
+        pl.expected_updates_from_start = self.expected_updates_from_start;
+        pl.overshoot_reported = self.overshoot_reported;
+        pl.projected_overrun_reported = self.projected_overrun_reported;
+        pl.overrun_reported = self.overrun_reported;
+        pl.log_target = self.log_target.clone();
+        pl.start_msg = self.start_msg.clone();
+        pl.start_time = self.start_time;
+        pl.log_history = self.log_history.clone();
+        pl.interval_seconds_per_item = self.interval_seconds_per_item;
+        pl.next_log_time = self.next_log_time.clone();
+        pl.stop_time = self.stop_time;
+        pl.count = self.count;
+        pl.failed = self.failed;
+        pl.count_offset = self.count_offset;
+        pl.last_res_mem = self.last_res_mem;
+        pl.res_mem_history = self.res_mem_history.clone();
+        pl.memory_warned = self.memory_warned;
+        pl.free_space_warned = self.free_space_warned;
+        pl.metrics = self.metrics.clone();
+        pl.history = self.history.clone();
+        pl.history_stride = self.history_stride;
+        pl.history_skipped = self.history_skipped;
+        pl.spans = self.spans.clone();
+        pl.eta_deadline = self.eta_deadline;
+        #[cfg(feature = "metrics")]
+        {
+            pl.published_count = AtomicUsize::new(self.published_count.load(Ordering::Relaxed));
+        }
+        pl.recent_speeds = self.recent_speeds.clone();
+        pl.idle_intervals = self.idle_intervals;
+        pl.logs = self.logs;
+        pl.next_line_time = self.next_line_time;
+        pl.start_logged = self.start_logged;
+        pl.next_check_count = self.next_check_count;
+        pl.check_stride = self.check_stride;
+        pl.predicted_stride = self.predicted_stride;
+        pl
+    }
+
+    /// Reset timing and counters.
+    fn reset(&mut self) {
+        let now = clock::now();
+        self.start_time = Some(now);
+        self.stop_time = None;
+        self.count = 0;
+        self.failed = 0;
+        self.log_history.clear();
+        self.log_history.push_back((now, 0));
+        self.interval_seconds_per_item = None;
+        self.eta_deadline = None;
+        self.recent_speeds.clear();
+        self.idle_intervals = 0;
+        self.logs = 0;
+        self.next_check_count = 0;
+        self.check_stride = 1;
+        self.predicted_stride = 1;
+        self.history.clear();
+        self.history_stride = 1;
+        self.history_skipped = 0;
+        self.spans.clear();
+        self.worker_started = false;
+        #[cfg(feature = "metrics")]
+        {
+            *self.published_count.get_mut() = 0;
+        }
+        self.last_res_mem = None;
+        self.res_mem_history.clear();
+        self.memory_warned = false;
+        self.free_space_warned = false;
+        self.overshoot_reported = false;
+        self.projected_overrun_reported = false;
+        self.overrun_reported = false;
+        self.next_line_time = None;
+        let log_interval = self.jittered_log_interval();
+        self.next_log_time.set(now, log_interval);
+        self.metrics.clear();
+    }
+
+    /// Return whether a progress line can be logged at `now`, given the
+    /// [maximum number of logs](ProgressLog::max_logs).
+    fn line_due(&self, now: Instant) -> bool {
+        self.max_logs.is_none_or(|max_logs| {
+            self.logs < max_logs
+                && self
+                    .next_line_time
+                    .is_none_or(|next_line_time| now >= next_line_time)
+        })
+    }
+
+    /// Schedule the next progress line after a line logged at `now`, spacing
+    /// the remaining lines evenly across the estimated remaining time if the
+    /// [maximum number of logs](ProgressLog::max_logs) is set.
+    fn schedule_line(&mut self, now: Instant) {
+        let Some(max_logs) = self.max_logs else {
+            return;
+        };
+        let remaining_time = match (self.expected_duration, self.start_time) {
+            (Some(expected_duration), Some(start_time)) => {
+                Some(expected_duration.saturating_sub(now - start_time))
+            }
+            _ => self.raw_eta(now),
+        };
+        self.next_line_time = remaining_time.map(|remaining_time| {
+            let remaining_logs = max_logs.saturating_sub(self.logs);
+            now + remaining_time.div_f64((remaining_logs + 1) as f64)
+        });
+    }
+
+    /// Return the log interval, applying jitter if required.
+    fn jittered_log_interval(&mut self) -> Duration {
+        if self.log_interval_jitter == 0.0 {
+            return self.log_interval;
+        }
+        // xorshift64*
+        self.rng_state ^= self.rng_state >> 12;
+        self.rng_state ^= self.rng_state << 25;
+        self.rng_state ^= self.rng_state >> 27;
+        let random = self.rng_state.wrapping_mul(0x2545F4914F6CDD1D);
+        // Uniform in [-1 . . 1)
+        let uniform = (random >> 11) as f64 / (1_u64 << 52) as f64 - 1.0;
+        self.log_interval
+            .mul_f64(1.0 + self.log_interval_jitter * uniform)
+    }
+
+    /// Register this logger in the global registry, if enabled.
+    fn register(&mut self) {
+        self.snapshot = registry::register(registry::Snapshot {
+            log_target: self.log_target.clone(),
+            item_name: self.item_name.clone(),
+            item_name_plural: self.item_name_plural.clone(),
+            start_msg: self.start_msg.clone(),
+            start_time: self.start_time.unwrap_or_else(clock::now),
+            count: self.total_count(),
+            expected_updates: self.expected_updates,
+        });
+    }
+
+    /// Start the activity as [`start`](ProgressLog::start) does, without
+    /// logging the configuration.
+    fn begin(&mut self, msg: &str) {
+        self.flush_worker();
+        if self.expected_updates_from_start {
+            self.expected_updates = None;
+            self.expected_updates_from_start = false;
+        }
+        self.reset();
+        self.count_offset = 0;
+        self.start_msg = msg.into();
+        self.start_logged = self.min_duration.is_none();
+        if self.start_logged {
+            self.log_start();
+        }
+        self.register();
+        self.publish_handle();
+    }
+
+    /// Set the expected number of updates of the activity just started, which
+    /// will be cleared by the next start.
+    fn begin_expected(&mut self, expected_updates: usize) {
+        self.expected_updates = Some(expected_updates);
+        self.expected_updates_from_start = true;
+        self.update_snapshot();
+        self.publish_handle();
+    }
+
+    /// Log the configuration, if required.
+    fn echo_config(&self) {
+        if self.log_config && self.start_logged {
+            info!(
+                target: &self.log_target,
+                "Configuration: log interval {}, expected updates {}, item name {:?}, memory display {}",
+                Pretty(self.log_interval.as_millis()),
+                Expected(self.expected_updates),
+                self.item_name,
+                OnOff(self.display_memory)
+            );
+        }
+    }
+
+    /// Return whether output is allowed at `now`, that is, whether the
+    /// [minimum duration](ProgressLog::min_duration) has elapsed since the
+    /// start or `force` is true, logging the start message and the
+    /// configuration the first time.
+    fn output_allowed(&mut self, now: Instant, force: bool) -> bool {
+        if self.start_logged {
+            return true;
+        }
+        let elapsed = self
+            .start_time
+            .map_or(Duration::ZERO, |start_time| now - start_time);
+        if !force
+            && self
+                .min_duration
+                .is_some_and(|min_duration| elapsed < min_duration)
+        {
+            return false;
+        }
+        self.start_logged = true;
+        self.log_start();
+        self.echo_config();
+        true
+    }
+
+    /// Log the start message, if any, and print the start message of the
+    /// continuous-integration format, if any.
+    fn log_start(&self) {
+        if !self.start_msg.is_empty() {
+            info!(target: &self.log_target, "{}", self.start_msg);
+        }
+        if let Some(ci_format) = self.ci_format {
+            ci_format.print_start(&self.start_msg);
+        }
+    }
+
+    /// Log whether the target speed, if any, has been met; if `output` is
+    /// false, only a missed target is logged, as a warning.
+    fn log_target_speed(&self, output: bool) {
+        let (Some(target_speed), Some(items_per_second)) =
+            (self.target_speed, self.items_per_second())
+        else {
+            return;
+        };
+        let level = if items_per_second >= target_speed {
+            Level::Info
+        } else {
+            Level::Warn
+        };
+        if output || level == Level::Warn {
+            log!(
+                target: &self.log_target,
+                level,
+                "Target speed {}: {:.2}% of target ({})",
+                if level == Level::Info { "met" } else { "missed" },
+                100.0 * items_per_second / target_speed,
+                Speed(self, 1.0 / target_speed)
+            );
+        }
+    }
+
+    /// Log a change of a setting, if the configuration is logged, the logger
+    /// is running, and the setting actually changes.
+    fn log_config_change(&self, name: &str, old: impl Display, new: impl Display) {
+        if !self.log_config
+            || !self.start_logged
+            || self.start_time.is_none()
+            || self.stop_time.is_some()
+        {
+            return;
+        }
+        let (old, new) = (old.to_string(), new.to_string());
+        if old != new {
+            info!(
+                target: &self.log_target,
+                "Configuration change: {} {} -> {}",
+                name,
+                old,
+                new
+            );
+        }
+    }
+
+    /// Record the elapsed time and the count at `now`, if required.
+    ///
+    /// When [`HISTORY_CAPACITY`](Self::HISTORY_CAPACITY) entries have been
+    /// recorded, every other entry is discarded, and from then on only half
+    /// of the logs are recorded, so that the entries remain evenly spaced.
+    fn record_history(&mut self, now: Instant) {
+        if self.report_file.is_none() && self.trace_file.is_none() {
+            return;
+        }
+        let Some(start_time) = self.start_time else {
+            return;
+        };
+        self.history_skipped += 1;
+        if self.history_skipped < self.history_stride {
+            return;
+        }
+        self.history_skipped = 0;
+        if self.history.len() == Self::HISTORY_CAPACITY {
+            let mut index = 0;
+            self.history.retain(|_| {
+                index += 1;
+                index % 2 == 0
+            });
+            self.history_stride *= 2;
+        }
+        self.history.push((now - start_time, self.count));
+    }
+
+    /// Publish the state of this logger for its handles, if any.
+    fn publish_handle(&self) {
+        if let Some(handle) = &self.handle {
+            handle.publish(
+                self.count,
+                self.count_offset,
+                self.expected_updates,
+                self.start_time,
+                self.stop_time,
+            );
+        }
+    }
+
+    /// Publish the count of this logger for its handles, if any.
+    #[inline(always)]
+    fn publish_count(&self) {
+        if let Some(handle) = &self.handle {
+            handle.publish_count(self.count);
+        }
+    }
+
+    /// Update the snapshot of this logger in the global registry, if
+    /// registered.
+    fn update_snapshot(&self) {
+        if let Some(snapshot) = &self.snapshot {
+            let mut snapshot = snapshot.lock().unwrap();
+            snapshot.count = self.total_count();
+            snapshot.expected_updates = self.expected_updates;
+        }
+    }
+
+    /// Update the statistics about the speed of log intervals with the
+    /// interval ending at `now`, returning its number of seconds per item, if
+    /// any item was processed.
+    fn update_interval_stats(&mut self, now: Instant) -> Option<f64> {
+        let &(last_log_time, last_count) = self.log_history.back()?;
+        if self.count <= last_count {
+            return None;
+        }
+        let seconds_per_item =
+            (now - last_log_time).as_secs_f64() / (self.count - last_count) as f64;
+        self.interval_seconds_per_item = Some(match self.interval_seconds_per_item {
+            Some((min, max)) => (min.min(seconds_per_item), max.max(seconds_per_item)),
+            None => (seconds_per_item, seconds_per_item),
+        });
+        let window = Self::RECENT_SPEEDS_WINDOW.max(self.speed_sparkline.unwrap_or(0));
+        while self.recent_speeds.len() >= window {
+            self.recent_speeds.pop_front();
+        }
+        self.recent_speeds.push_back(1.0 / seconds_per_item);
+        Some(seconds_per_item)
+    }
+
+    /// Return the raw estimate of the time to end at `now`, based on the
+    /// average speed since the start.
+    fn raw_eta(&self, now: Instant) -> Option<Duration> {
+        let expected_updates = self.expected_updates?;
+        if self.stop_time.is_some() || self.count == 0 {
+            return None;
+        }
+        let elapsed = now - self.start_time?;
+        Some(elapsed.mul_f64(
+            expected_updates.saturating_sub(self.total_count()) as f64 / self.count as f64,
+        ))
+    }
+
+    /// Revise the smoothed estimated completion time at `now`, following the
+    /// current smoothing policy.
+    fn update_eta_deadline(&mut self, now: Instant) {
+        let Some(raw_eta) = self.raw_eta(now) else {
+            self.eta_deadline = None;
+            return;
+        };
+        let raw_deadline = now + raw_eta;
+        self.eta_deadline = Some(match (self.eta_smoothing, self.eta_deadline) {
+            (EtaSmoothing::Monotone, Some(deadline)) if deadline > now => {
+                deadline.min(raw_deadline)
+            }
+            (EtaSmoothing::Hysteresis(tolerance), Some(deadline)) if deadline > now => {
+                let eta = deadline - now;
+                // Compare in floating point, as an infinite tolerance would
+                // make Duration::mul_f64 panic
+                if raw_eta.abs_diff(eta).as_secs_f64() <= eta.as_secs_f64() * tolerance {
+                    deadline
+                } else {
+                    raw_deadline
+                }
+            }
+            _ => raw_deadline,
+        });
+    }
+
+    /// Return the estimate of the time to end at `now`, smoothed following the
+    /// current smoothing policy.
+    fn smoothed_eta(&self, now: Instant) -> Option<Duration> {
+        match (self.eta_smoothing, self.eta_deadline) {
+            (EtaSmoothing::Raw, _) | (_, None) => self.raw_eta(now),
+            (_, Some(deadline)) => Some(deadline.saturating_duration_since(now)),
+        }
+    }
+
+    /// Return a range for the time needed to process `remaining` items, if the
+    /// speed of recent log intervals varies more than the
+    /// [threshold](ProgressLog::eta_range_threshold).
+    ///
+    /// The range is computed using the mean speed plus or minus its standard
+    /// deviation, clamped to the observed speeds.
+    fn eta_interval(&self, remaining: usize) -> Option<(Duration, Duration)> {
+        let threshold = self.eta_range_threshold?;
+        let recent_speeds = self.recent_speeds.range(
+            self.recent_speeds
+                .len()
+                .saturating_sub(Self::RECENT_SPEEDS_WINDOW)..,
+        );
+        if recent_speeds.len() < 2 {
+            return None;
+        }
+        let n = recent_speeds.len() as f64;
+        let mean = recent_speeds.clone().sum::<f64>() / n;
+        let std_dev = (recent_speeds
+            .clone()
+            .map(|speed| (speed - mean) * (speed - mean))
+            .sum::<f64>()
+            / (n - 1.0))
+            .sqrt();
+        if std_dev <= threshold * mean {
+            return None;
+        }
+        let (min, max) = recent_speeds.fold((f64::INFINITY, 0.0_f64), |(min, max), &speed| {
+            (min.min(speed), max.max(speed))
+        });
+        let fast = (mean + std_dev).min(max);
+        let slow = (mean - std_dev).max(min);
+        Some((
+            Duration::from_secs_f64(remaining as f64 / fast),
+            Duration::from_secs_f64(remaining as f64 / slow),
+        ))
+    }
+
+    /// Return the name of an item in singular or plural form, depending on
+    /// `count`.
+    fn item_name_for(&self, count: usize) -> &str {
+        if count == 1 {
+            &self.item_name
+        } else {
+            &self.item_name_plural
+        }
+    }
+
+    /// Log if it is time to do so, and compute the count at which the time
+    /// should be checked again.
+    ///
+    /// Whether it is time to log is checked using the fastest available
+    /// clock, and the current time is read only if so. At each log, the
+    /// stride between checks is predicted from the speed of the last log
+    /// interval so that, assuming the speed does not change, checks happen
+    /// about every [`MAX_CHECK_INTERVAL`](Self::MAX_CHECK_INTERVAL), but never
+    /// after more than [`LIGHT_UPDATE_MASK`](Self::LIGHT_UPDATE_MASK) + 1
+    /// updates. The stride never grows by more than a factor of two between
+    /// checks, which avoids unreliable extrapolations from the first few
+    /// updates, and it is one until the first log. The time bound limits the
+    /// delay caused by a sudden slowdown to the slowdown factor times
+    /// [`MAX_CHECK_INTERVAL`](Self::MAX_CHECK_INTERVAL).
+    fn check_log_time(&mut self) {
+        if self.next_log_time.passed() {
+            let &(last_log_time, last_count) = self.log_history.back().unwrap();
+            let now = clock::now();
+            self.log(now);
+            let elapsed = now - last_log_time;
+            let items = self.count.saturating_sub(last_count);
+            if !elapsed.is_zero() && items != 0 {
+                let expected =
+                    items as f64 * Self::MAX_CHECK_INTERVAL.as_secs_f64() / elapsed.as_secs_f64();
+                self.predicted_stride = (expected as usize).clamp(1, Self::LIGHT_UPDATE_MASK + 1);
+            }
+        }
+        self.check_stride = (2 * self.check_stride).min(self.predicted_stride);
+        self.next_check_count = self.count.saturating_add(self.check_stride);
+    }
+
+    /// Update the statistics about the speed and the estimated time to end
+    /// and, if `output` is true, send the current state to the worker thread,
+    /// spawning it and sending a copy of the logger if necessary.
+    ///
+    /// The statistics are computed by the calling thread, so they are up to
+    /// date; the checks of the overshoot policy and of the time budget are
+    /// performed by [`log`](ProgressLog::log), so panics and callbacks happen
+    /// in the calling thread. If the worker is too far behind, the log is
+    /// dropped.
+    fn log_deferred(&mut self, now: Instant, output: bool) {
+        self.update_interval_stats(now);
+        self.update_eta_deadline(now);
+        if !output {
+            return;
+        }
+        if !self.worker_started {
+            let mut pl = self.clone_with_state();
+            pl.deferred_logging = false;
+            pl.overshoot_policy = OvershootPolicy::Ignore;
+            pl.time_budget = None;
+            // The calling thread spaces lines, writes the progress file, and
+            // publishes metrics
+            pl.max_logs = None;
+            pl.progress_file = None;
+            #[cfg(feature = "metrics")]
+            let _ = pl.metric_handles.set(MetricHandles {
+                count: metrics::Counter::noop(),
+                items_per_second: metrics::Gauge::noop(),
+                percent: metrics::Gauge::noop(),
+            });
+            let sent = self
+                .worker
+                .get_or_insert_with(Worker::spawn)
+                .sender
+                .send(WorkerMessage::Start(Box::new(pl)));
+            if sent.is_err() {
+                self.propagate_worker_panic();
+            }
+            self.worker_started = true;
+        }
+        let worker = self.worker.as_ref().unwrap();
+        let sent = worker.sender.try_send(WorkerMessage::Log {
+            now,
+            count: self.count,
+            failed: self.failed,
+            metrics: (!self.metrics.is_empty()).then(|| self.metrics.clone()),
+        });
+        if let Err(mpsc::TrySendError::Disconnected(_)) = sent {
+            self.propagate_worker_panic();
+        }
+    }
+
+    /// Wait for the worker thread, if any, to process all pending logs,
+    /// returning whether it is still running.
+    fn wait_worker(&self) -> bool {
+        let Some(worker) = &self.worker else {
+            return true;
+        };
+        let (reply, receiver) = mpsc::channel();
+        worker.sender.send(WorkerMessage::Flush(reply)).is_ok() && receiver.recv().is_ok()
+    }
+
+    /// Wait for the worker thread, if any, to process all pending logs,
+    /// resuming in the calling thread the panic of the worker, if any.
+    fn flush_worker(&mut self) {
+        if !self.wait_worker() {
+            self.propagate_worker_panic();
+        }
+    }
+
+    /// Join the terminated worker thread and resume its panic in the calling
+    /// thread.
+    fn propagate_worker_panic(&mut self) -> ! {
+        let worker = self.worker.take().unwrap();
+        self.worker_started = false;
+        drop(worker.sender);
+        match worker.handle.join() {
+            Err(payload) => std::panic::resume_unwind(payload),
+            Ok(()) => panic!("The logging worker thread terminated unexpectedly"),
+        }
+    }
+
+    /// Terminate the worker thread, if any, after it has processed all
+    /// pending logs.
+    fn stop_worker(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            drop(worker.sender);
+            let _ = worker.handle.join();
+        }
+        self.worker_started = false;
+    }
+
+    /// Return the count plus the number of items already done when the
+    /// activity was resumed.
+    fn total_count(&self) -> usize {
+        self.count_offset + self.count
+    }
+
+    /// Return `width` if [using fixed widths](ProgressLog::fixed_width), zero
+    /// otherwise.
+    fn width(&self, width: usize) -> usize {
+        if self.fixed_width {
+            width
+        } else {
+            0
+        }
+    }
+
+    /// Return an object displaying an amount of bytes using the current unit
+    /// prefixes.
+    fn humanize_bytes(&self, bytes: f64) -> Humanized {
+        Humanized::new(bytes, self.byte_units == ByteUnits::Binary, "B")
+    }
+
+    fn fmt_count(&self, f: &mut Formatter<'_>) -> Result {
+        if self.bytes {
+            f.write_fmt(format_args!(
+                "{:>w$}",
+                self.humanize_bytes(self.count as f64),
+                w = self.width(Self::BYTES_WIDTH)
+            ))?;
+            if let Some(expected_updates) = self.expected_updates {
+                f.write_fmt(format_args!(
+                    "/{}",
+                    self.humanize_bytes(expected_updates as f64)
+                ))?;
+            }
+            return Ok(());
+        }
+
+        let w = self.width(Self::COUNT_WIDTH);
+        if self.time_unit.is_none() {
+            f.write_fmt(format_args!(
+                "{:>w$} {}",
+                Grouped(self.count),
+                self.item_name_for(self.count)
+            ))
+        } else {
+            f.write_fmt(format_args!(
+                "{:>w$} {}",
+                self.count,
+                self.item_name_for(self.count)
+            ))
+        }
+    }
+
+    /// Return the log level corresponding to the current failure rate.
+    fn failure_level(&self) -> Level {
+        let failure_rate = self.failed as f64 / self.count as f64;
+        match self.failure_rate_thresholds {
+            _ if self.failed == 0 => Level::Info,
+            (_, Some(error_rate)) if failure_rate >= error_rate => Level::Error,
+            (Some(warn_rate), _) if failure_rate >= warn_rate => Level::Warn,
+            _ => Level::Info,
+        }
+    }
+
+    /// Format the number of failed items and the failure rate, if some item
+    /// failed.
+    fn fmt_failed(&self, f: &mut Formatter<'_>) -> Result {
+        if self.failed == 0 {
+            return Ok(());
+        }
+        f.write_fmt(format_args!(
+            "; {} failed ({:>w$.2}%)",
+            Grouped(self.failed),
+            100.0 * self.failed as f64 / self.count as f64,
+            w = self.width(Self::PERCENT_WIDTH)
+        ))
+    }
+
+    /// Return the value of a quantity used in a ratio, that is, the count if
+    /// `name` is the item name, or the last value of the metric `name`.
+    fn ratio_quantity(&self, name: &str) -> Option<f64> {
+        if name == self.item_name {
+            return Some(self.count as f64);
+        }
+        self.metrics
+            .iter()
+            .find(|metric| metric.name == name)
+            .map(|metric| metric.last)
+    }
+
+    /// Format the ratios whose quantities are available and whose denominator
+    /// is nonzero.
+    fn fmt_ratios(&self, f: &mut Formatter<'_>) -> Result {
+        for (numerator, denominator) in &self.ratios {
+            if let (Some(n), Some(d)) = (
+                self.ratio_quantity(numerator),
+                self.ratio_quantity(denominator),
+            ) {
+                if d == 0.0 {
+                    continue;
+                }
+                f.write_fmt(format_args!("; {}/{} {:.4}", numerator, denominator, n / d))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Format the speed as a percentage of the target speed, if any.
+    fn fmt_target_speed(&self, f: &mut Formatter<'_>, seconds_per_item: f64) -> Result {
+        match self.target_speed {
+            Some(target_speed) => f.write_fmt(format_args!(
+                "; {:>w$.2}% of target speed",
+                100.0 / (seconds_per_item * target_speed),
+                w = self.width(Self::PERCENT_WIDTH)
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn fmt_timing_speed(&self, f: &mut Formatter<'_>, seconds_per_item: f64) -> Result {
+        let items_per_second = 1.0 / seconds_per_item;
+
+        if self.bytes {
+            if !self.display_fields.contains(DisplayFields::SPEED) {
+                return Ok(());
+            }
+            return f.write_fmt(format_args!(
+                "{:>w$}/s",
+                self.humanize_bytes(items_per_second),
+                w = self.width(Self::BYTES_WIDTH)
+            ));
+        }
+
+        let time_unit_timing = self
+            .time_unit
+            .unwrap_or_else(|| TimeUnit::nice_time_unit(seconds_per_item));
+
+        let time_unit_speed = self
+            .time_unit
+            .unwrap_or_else(|| TimeUnit::nice_speed_unit(seconds_per_item));
+
+        let w = self.width(Self::SPEED_WIDTH);
+        let speed = |f: &mut Formatter<'_>| {
+            f.write_fmt(format_args!(
+                "{:>w$.2} {}/{}",
+                items_per_second * time_unit_speed.as_seconds(),
+                self.item_name_plural,
+                time_unit_speed.label(),
+            ))
+        };
+        let timing = |f: &mut Formatter<'_>| {
+            f.write_fmt(format_args!(
+                "{:>w$.2} {}/{}",
+                seconds_per_item / time_unit_timing.as_seconds(),
+                time_unit_timing.label(),
+                self.item_name,
+            ))
+        };
+
+        match (
+            self.display_fields.contains(DisplayFields::SPEED),
+            self.display_fields.contains(DisplayFields::TIMING),
+        ) {
+            (true, true) if self.timing_first => {
+                timing(f)?;
+                f.write_str(", ")?;
+                speed(f)
+            }
+            (true, true) => {
+                speed(f)?;
+                f.write_str(", ")?;
+                timing(f)
+            }
+            (true, false) => speed(f),
+            (false, true) => timing(f),
+            (false, false) => Ok(()),
+        }
+    }
+
+    /// Create or drop the system, depending on whether some system
+    /// information is displayed.
+    fn update_system(&mut self) {
+        let needed =
+            (self.display_memory || self.display_load_average || self.free_space.is_some())
+                && System::IS_SUPPORTED;
+        match (needed, &self.system) {
+            (true, None) => {
+                self.system = Some(Mutex::new((
+                    System::new_with_specifics(RefreshKind::new().with_memory()),
+                    None,
+                )));
+            }
+            (false, Some(_)) => {
+                self.system = None;
+            }
+            _ => (),
+        }
+    }
+
+    /// Refresh the memory information of the system and of the process with
+    /// the given pid.
+    fn refresh_system(system: &mut System, pid: Option<Pid>) {
+        system.refresh_memory();
+        if let Some(pid) = pid {
+            system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        }
+    }
+
+    /// Lock the system, if memory is displayed, refreshing it if the memory
+    /// refresh interval has elapsed since the last refresh.
+    fn lock_system(&self) -> Option<LockedSystem<'_>> {
+        if !self.display_memory {
+            return None;
+        }
+        self.system.as_ref().map(|system| {
+            let mut system = system.lock().unwrap();
+            let now = clock::now();
+            if system
+                .1
+                .is_none_or(|last| now - last >= self.memory_refresh_interval)
+            {
+                Self::refresh_system(&mut system.0, self.pid);
+                system.1 = Some(now);
+            }
+            LockedSystem(system)
+        })
+    }
+
+    /// Return the available space on the disk containing the monitored path,
+    /// if any.
+    fn free_space(&self) -> Option<u64> {
+        let (path, _) = self.free_space.as_ref()?;
+        let mut system = self.system.as_ref()?.lock().unwrap();
+        let disk = system
+            .0
+            .disks_mut()
+            .iter_mut()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())?;
+        disk.refresh();
+        Some(disk.available_space())
+    }
+
+    /// Read the memory information of the given system and of the process
+    /// that created the logger.
+    fn memory_readings(&self, system: &System) -> MemoryReadings {
+        let process = self.pid.and_then(|pid| system.process(pid));
+        #[cfg_attr(not(windows), allow(unused_mut))]
+        let mut memory = MemoryReadings {
+            res: process.map(|process| process.memory()),
+            vir: process.map(|process| process.virtual_memory()),
+            available: system.available_memory(),
+            free: system.free_memory(),
+            total: system.total_memory(),
+            used_swap: system.used_swap(),
+            total_swap: system.total_swap(),
+        };
+        #[cfg(windows)]
+        {
+            if let Some((working_set, private_bytes)) = windows::process_memory() {
+                memory.res = Some(working_set);
+                memory.vir = Some(private_bytes);
+            }
+            if let Some((commit_charge, commit_limit)) = windows::commit() {
+                memory.used_swap = commit_charge;
+                memory.total_swap = commit_limit;
+            }
+        }
+        memory
+    }
+
+    /// Read the current time and the displayed system information.
+    fn readings(&self) -> Readings {
+        let now = clock::now();
+        let memory = self
+            .lock_system()
+            .map(|system| self.memory_readings(&system));
+        let load_average = if self.display_load_average {
+            self.system.as_ref().map(|system| {
+                let load_avg = system.lock().unwrap().0.load_average();
+                [load_avg.one, load_avg.five, load_avg.fifteen]
+            })
+        } else {
+            None
+        };
+        Readings {
+            now,
+            elapsed: self.start_time.map_or(Duration::ZERO, |start_time| {
+                self.stop_time.unwrap_or(now) - start_time
+            }),
+            since_epoch: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default(),
+            memory,
+            free_space: self.free_space(),
+            load_average,
+        }
+    }
+
+    /// Log a warning if the given available space is below the minimum
+    /// [free space](ProgressLog::monitor_free_space), unless a warning has
+    /// already been logged and the available space has not recovered since.
+    fn check_free_space(&mut self, free_space: Option<u64>) {
+        let (Some(free_space), Some((path, min_free_space))) = (free_space, &self.free_space)
+        else {
+            return;
+        };
+        if free_space >= *min_free_space {
+            self.free_space_warned = false;
+        } else if !self.free_space_warned {
+            self.free_space_warned = true;
+            warn!(
+                target: &self.log_target,
+                "Available space on the disk containing {} is {}B, below {}B",
+                path.display(),
+                humanize(free_space as _),
+                humanize(*min_free_space as _)
+            );
+        }
+    }
+
+    /// Log a warning if the resident-set size exceeds the memory warning
+    /// threshold, unless a warning has already been logged.
+    fn check_memory(&mut self) {
+        let Some(threshold) = self.memory_warning_threshold else {
+            return;
+        };
+        if self.memory_warned {
+            return;
+        }
+        // Reading the limit requires file I/O, so it is done only once
+        let cgroup_limit = *self
+            .cgroup_memory_limit
+            .get_or_insert_with(cgroup_memory_limit);
+        let Some(system) = self.lock_system() else {
+            return;
+        };
+        let Some(res_mem) = self
+            .pid
+            .and_then(|pid| system.process(pid))
+            .map(|process| process.memory())
+        else {
+            return;
+        };
+        let limit = cgroup_limit.map_or(system.total_memory(), |limit| {
+            limit.min(system.total_memory())
+        });
+        drop(system);
+        if res_mem as f64 > threshold * limit as f64 {
+            warn!(
+                target: &self.log_target,
+                "Resident-set size is {}B, {:.2}% of the memory limit of {}B",
+                humanize(res_mem as _),
+                100.0 * res_mem as f64 / limit as f64,
+                humanize(limit as _)
+            );
+            self.memory_warned = true;
+        }
+    }
+
+    /// Apply the overshoot policy if the count exceeds the expected number of
+    /// updates, unless the overshoot has already been reported.
+    fn check_overshoot(&mut self) {
+        let Some(expected_updates) = self.expected_updates else {
+            return;
+        };
+        if self.total_count() <= expected_updates || self.overshoot_reported {
+            return;
+        }
+        self.overshoot_reported = true;
+        let msg = format_args!(
+            "Count {} exceeds the expected number of updates {}",
+            self.total_count(),
+            expected_updates
+        );
+        match self.overshoot_policy {
+            OvershootPolicy::Ignore => {}
+            OvershootPolicy::Warn => warn!(target: &self.log_target, "{}", msg),
+            OvershootPolicy::DebugAssert => {
+                warn!(target: &self.log_target, "{}", msg);
+                debug_assert!(false, "{}", msg);
+            }
+        }
+    }
+
+    /// Log a warning if the elapsed time or the projected completion time
+    /// exceed the time budget, unless the overrun has already been reported.
+    fn check_time_budget(&mut self, now: Instant) {
+        let (Some(time_budget), Some(start_time)) = (self.time_budget, self.start_time) else {
+            return;
+        };
+        let elapsed = now - start_time;
+        if elapsed > time_budget {
+            if !self.overrun_reported {
+                self.overrun_reported = true;
+                warn!(
+                    target: &self.log_target,
+                    "Elapsed time {} exceeds the time budget of {}",
+                    TimeUnit::pretty_print(elapsed.as_millis()),
+                    TimeUnit::pretty_print(time_budget.as_millis())
+                );
+                if let Some(callback) = &self.on_time_budget_exceeded {
+                    callback();
+                }
+            }
+        } else if let Some(expected_updates) = self.expected_updates {
+            if self.projected_overrun_reported || self.count == 0 {
+                return;
+            }
+            let projected = elapsed.as_millis()
+                * expected_updates.saturating_sub(self.count_offset) as u128
+                / self.count as u128;
+            if projected > time_budget.as_millis() {
+                self.projected_overrun_reported = true;
+                warn!(
+                    target: &self.log_target,
+                    "Projected completion time {} exceeds the time budget of {}",
+                    TimeUnit::pretty_print(projected),
+                    TimeUnit::pretty_print(time_budget.as_millis())
+                );
+            }
+        }
+    }
+
+    /// Write a machine-readable progress line to the progress file, if any.
+    fn write_progress_file(&mut self, now: Instant) {
+        let (Some((path, tmp_path)), Some(start_time)) = (&self.progress_file, self.start_time)
+        else {
+            return;
+        };
+        let elapsed = self.stop_time.unwrap_or(now) - start_time;
+        let total_count = self.total_count();
+        let line = &mut self.progress_line;
+        line.clear();
+        // Writing to a String cannot fail
+        let _ = write!(line, "count={}", total_count);
+        if let Some(expected_updates) = self.expected_updates {
+            let _ = write!(
+                line,
+                " expected={} percent={:.2}",
+                expected_updates,
+                100.0 * total_count as f64 / expected_updates as f64
+            );
+        }
+        let _ = write!(line, " elapsed_ms={}", elapsed.as_millis());
+        if let Some(expected_updates) = self.expected_updates {
+            let _ = write!(
+                line,
+                " eta_ms={}",
+                expected_updates.saturating_sub(total_count) as u128 * elapsed.as_millis()
+                    / (self.count as u128 + 1)
+            );
+        }
+        if !elapsed.is_zero() {
+            let _ = write!(
+                line,
+                " items_per_second={:.2}",
+                self.count as f64 / elapsed.as_secs_f64()
+            );
+        }
+        line.push('\n');
+
+        let result = if path.is_file() || !path.exists() {
+            std::fs::write(tmp_path, line.as_bytes()).and_then(|_| std::fs::rename(tmp_path, path))
+        } else {
+            // Named pipes must not block the activity
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_NONBLOCK);
+            match options
+                .open(path)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+            {
+                // No reader, or the pipe is full
+                #[cfg(unix)]
+                Err(e) if e.raw_os_error() == Some(libc::ENXIO) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(()),
+                result => result,
+            }
+        };
+        if let Err(e) = result {
+            warn!(
+                target: &self.log_target,
+                "Cannot write progress file {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Publish the count, the speed, and the percentage of completion through
+    /// the [`metrics`] facade, labeled with the log target.
+    ///
+    /// The count is published as a counter increased by the items processed
+    /// since the last publication, so it keeps increasing across restarts.
+    #[cfg(feature = "metrics")]
+    fn publish_metrics(&self) {
+        let handles = self.metric_handles.get_or_init(|| {
+            let labels = [("target", self.log_target.clone())];
+            MetricHandles {
+                count: metrics::counter!("progress_count", &labels),
+                items_per_second: metrics::gauge!("progress_items_per_second", &labels),
+                percent: metrics::gauge!("progress_percent", &labels),
+            }
+        });
+        let published_count = self.published_count.swap(self.count, Ordering::Relaxed);
+        handles
+            .count
+            .increment(self.count.saturating_sub(published_count) as u64);
+        if let Some(items_per_second) = self.items_per_second() {
+            handles.items_per_second.set(items_per_second);
+        }
+        if let Some(expected_updates) = self.expected_updates {
+            handles
+                .percent
+                .set(100.0 * self.total_count() as f64 / expected_updates as f64);
+        }
+    }
+
+    /// Log `self` at the given level, using the given readings.
+    ///
+    /// If the `kv` feature is enabled, `event`, count, speed, percentage of
+    /// completion and memory information are attached to the record as
+    /// structured key-values.
+    #[cfg_attr(not(feature = "kv"), allow(unused_variables))]
+    fn log_self(&self, level: Level, event: &str, readings: &Readings) {
+        #[cfg(feature = "kv")]
+        {
+            let elapsed = self.start_time.map(|_| readings.elapsed);
+            let items_per_second = elapsed
+                .filter(|elapsed| !elapsed.is_zero())
+                .map(|elapsed| self.count as f64 / elapsed.as_secs_f64());
+            let percent = self.expected_updates.map(|expected_updates| {
+                100.0 * self.total_count() as f64 / expected_updates as f64
+            });
+            let memory = readings.memory.as_ref();
+
+            log!(
+                target: &self.log_target,
+                level,
+                event = event,
+                count = self.count,
+                items_per_second = items_per_second,
+                percent = percent,
+                res_mem = memory.and_then(|memory| memory.res),
+                vir_mem = memory.and_then(|memory| memory.vir),
+                avail_mem = memory.map(|memory| memory.available),
+                free_mem = memory.map(|memory| memory.free),
+                total_mem = memory.map(|memory| memory.total),
+                used_swap = memory.map(|memory| memory.used_swap),
+                total_swap = memory.map(|memory| memory.total_swap);
+                "{}", Rendered(self, readings)
+            );
+        }
+        #[cfg(not(feature = "kv"))]
+        log!(target: &self.log_target, level, "{}", Rendered(self, readings));
+    }
+}
+
+/// The time is checked, using the fastest available clock (see the `quanta`
+/// and `coarse-clock` features), only when the count reaches a threshold
+/// predicted from the speed of the last log interval so that, at that speed,
+/// checks happen about every
+/// [`MAX_CHECK_INTERVAL`](ProgressLogger::MAX_CHECK_INTERVAL), making
+/// [`update`](ProgressUpdate::update) almost as cheap as
+/// [`light_update`](ProgressUpdate::light_update). A sudden slowdown by a
+/// factor of _k_ might delay a log by at most _k_ times
+/// [`MAX_CHECK_INTERVAL`](ProgressLogger::MAX_CHECK_INTERVAL), and the time
+/// is checked at least as often as with
+/// [`light_update`](ProgressUpdate::light_update).
+impl ProgressUpdate for ProgressLogger {
+    #[inline(always)]
+    fn update(&mut self) {
+        self.count += 1;
+        self.publish_count();
+        if self.count >= self.next_check_count {
+            self.check_log_time();
+        }
+    }
+
+    #[inline(always)]
+    fn update_with_count(&mut self, count: usize) {
+        self.count += count;
+        self.publish_count();
+        if self.count >= self.next_check_count {
+            self.check_log_time();
+        }
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        self.update_with_count_and_time(1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        self.count += count;
+        self.publish_count();
+        if self.next_log_time.passed_at(now) {
+            self.log(now);
+        }
+    }
+
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    /// Increases the count and, once every
+    /// [`LIGHT_UPDATE_MASK`](#fields.LIGHT_UPDATE_MASK) + 1 calls, check
+    /// whether it is time to log.
+    #[inline(always)]
+    fn light_update(&mut self) {
+        self.count += 1;
+        if (self.count & Self::LIGHT_UPDATE_MASK) == 0 {
+            self.publish_count();
+            self.log_if();
+        }
+    }
+}
+
+impl ProgressLog for ProgressLogger {
+    fn log(&mut self, now: Instant) {
+        // Before the minimum duration has elapsed, checks are performed anyway
+        let output = self.output_allowed(now, false);
+        let idle = self.skip_idle
+            && self
+                .log_history
+                .back()
+                .is_some_and(|&(_, last_count)| last_count == self.count);
+        if idle {
+            if output {
+                self.idle_intervals += 1;
+            }
+        } else if output && self.idle_intervals != 0 {
+            info!(
+                target: &self.log_target,
+                "Idle for {} log interval{}",
+                self.idle_intervals,
+                if self.idle_intervals == 1 { "" } else { "s" }
+            );
+            self.idle_intervals = 0;
+        }
+        // Idle intervals and the spacing of lines suppress only the progress
+        // line
+        let output = output && !idle && self.line_due(now);
+        self.record_history(now);
+        if self.deferred_logging {
+            self.log_deferred(now, output);
+        } else {
+            let seconds_per_item = self.update_interval_stats(now);
+            self.update_eta_deadline(now);
+            let readings = self.readings();
+            if output {
+                let level = match self.min_speed {
+                    Some(min_speed) if seconds_per_item.map_or(0.0, |s| 1.0 / s) < min_speed => {
+                        Level::Warn
+                    }
+                    _ => Level::Info,
+                }
+                .min(self.failure_level());
+                self.log_self(level, "progress", &readings);
+                #[cfg(any(test, feature = "test-utils"))]
+                test_utils::record_tick(now, self.count);
+                if let Some(ci_format) = self.ci_format {
+                    ci_format.print_progress(&*self);
+                }
+            }
+            self.check_free_space(readings.free_space);
+            self.check_memory();
+            // The resident-set size displayed, to avoid refreshing the system again
+            let res_mem = readings.memory.as_ref().and_then(|memory| memory.res);
+            if let Some(res_mem) = res_mem {
+                self.res_mem_history.push(res_mem);
+            }
+            if self.display_memory_delta {
+                self.last_res_mem = res_mem;
+            }
+        }
+        if output {
+            self.logs += 1;
+            self.schedule_line(now);
+        }
+        self.write_progress_file(now);
+        self.update_snapshot();
+        self.publish_handle();
+        #[cfg(feature = "metrics")]
+        self.publish_metrics();
+        self.check_overshoot();
+        self.check_time_budget(now);
+        if self.log_history.len() == self.local_speed_window {
+            self.log_history.pop_front();
+        }
+        self.log_history.push_back((now, self.count));
+        let log_interval = self.jittered_log_interval();
+        self.next_log_time.set(now, log_interval);
+    }
+
+    fn log_if(&mut self) {
+        if self.next_log_time.passed() {
+            self.log(clock::now());
+        }
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.log_config_change(
+            "memory display",
+            OnOff(self.display_memory),
+            OnOff(display_memory),
+        );
+        self.display_memory = display_memory;
+        self.update_system();
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.display_memory_delta = display_memory_delta;
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.display_load_average = display_load_average;
+        self.update_system();
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.free_space = path.map(|path| {
+            (
+                path.canonicalize().unwrap_or_else(|_| path.to_owned()),
+                min_free_space,
+            )
+        });
+        self.update_system();
+        if self.free_space.is_some() {
+            if let Some(system) = &mut self.system {
+                system.get_mut().unwrap().0.refresh_disks_list();
+            }
+        }
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.memory_warning_threshold = memory_warning_threshold;
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.memory_refresh_interval = memory_refresh_interval;
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.log_config_change(
+            "item name",
+            format_args!("{:?}", self.item_name),
+            format_args!("{:?}", item_name.as_ref()),
+        );
+        self.item_name = item_name.as_ref().into();
+        self.item_name_plural = utils::plural(&self.item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.item_name_plural = item_name_plural.as_ref().into();
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.log_config_change(
+            "log interval",
+            Pretty(self.log_interval.as_millis()),
+            Pretty(log_interval.as_millis()),
+        );
+        self.log_interval = log_interval;
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        // NaN means no jitter
+        self.log_interval_jitter = if jitter.is_nan() {
+            0.0
+        } else {
+            jitter.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.log_config_change(
+            "expected updates",
+            Expected(self.expected_updates),
+            Expected(expected_updates),
+        );
+        self.expected_updates = expected_updates;
+        self.expected_updates_from_start = false;
+        self.publish_handle();
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.time_unit = time_unit;
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.local_speed = local_speed;
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.local_speed_window = local_speed_window.max(1);
+        while self.log_history.len() > self.local_speed_window {
+            self.log_history.pop_front();
+        }
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.min_speed = min_speed;
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.skip_idle = skip_idle;
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.max_logs = max_logs;
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.min_duration = min_duration;
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.target_speed = target_speed;
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.failure_rate_thresholds = (warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.eta_smoothing = match eta_smoothing {
+            EtaSmoothing::Hysteresis(tolerance) if tolerance.is_nan() || tolerance < 0.0 => {
+                EtaSmoothing::Hysteresis(0.0)
+            }
+            eta_smoothing => eta_smoothing,
+        };
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.eta_range_threshold = eta_range_threshold;
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.speed_sparkline = speed_sparkline;
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.overshoot_policy = overshoot_policy;
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        // A zero duration would make the percentage infinite
+        self.expected_duration = expected_duration.filter(|duration| !duration.is_zero());
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_time_budget_exceeded = Some(Arc::new(callback));
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.cancel_flag = cancel_flag;
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.deferred_logging = deferred_logging;
+        if !deferred_logging {
+            self.stop_worker();
+        }
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.log_on_drop = log_on_drop;
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.log_span_tree = log_span_tree;
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.progress_file = path.map(|path| {
+            let mut tmp_path = path.as_os_str().to_owned();
+            tmp_path.push(".tmp");
+            (path.to_owned(), tmp_path.into())
+        });
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.report_file = path.map(Path::to_owned);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.trace_file = path.map(Path::to_owned);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.ci_format = ci_format;
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.log_config = log_config;
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.log_target = target.as_ref().into();
+        #[cfg(feature = "metrics")]
+        {
+            self.metric_handles = std::sync::OnceLock::new();
+        }
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.elapsed_format = elapsed_format;
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.display_timestamp = display_timestamp;
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.ratios.push((
+            numerator.as_ref().to_owned(),
+            denominator.as_ref().to_owned(),
+        ));
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.fixed_width = fixed_width;
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.display_fields = display_fields;
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.timing_first = timing_first;
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.ratios
+            .retain(|(n, d)| n != numerator.as_ref() || d != denominator.as_ref());
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.display_remaining = display_remaining;
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.display_fraction = display_fraction;
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.begin(msg.as_ref());
+        self.echo_config();
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.log_interval
+    }
+
+    fn get_item_name(&self) -> String {
+        self.item_name.clone()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.expected_updates
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.time_unit
+    }
+
+    fn get_log_target(&self) -> String {
+        self.log_target.clone()
+    }
+
+    fn span(&self) -> Option<Span> {
+        let start = self.start_time?;
+        let mut span = Span::new(
+            self.start_msg.trim_end_matches(['.', ' ']),
+            start,
+            self.stop_time.unwrap_or_else(clock::now),
+        );
+        span.children = self.spans.clone();
+        Some(span)
+    }
+
+    fn add_span(&mut self, span: Span) {
+        span::add_span(&mut self.spans, span);
+    }
+
+    fn refresh(&mut self) {
+        if let Some(system) = &mut self.system {
+            let system = system.get_mut().unwrap();
+            Self::refresh_system(&mut system.0, self.pid);
+            system.1 = Some(clock::now());
+        }
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.begin(msg.as_ref());
+        self.begin_expected(expected_updates);
+        self.echo_config();
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.begin(msg.as_ref());
+        self.begin_expected(expected_updates);
+        self.count_offset = already_done;
+        self.update_snapshot();
+        self.publish_handle();
+        self.echo_config();
+    }
+
+    fn restart(&mut self) {
+        self.flush_worker();
+        self.reset();
+        self.start_logged = self.min_duration.is_none();
+        if self.start_logged && !self.start_msg.is_empty() {
+            info!(target: &self.log_target, "Restarting: {}", self.start_msg);
+        }
+        self.register();
+        self.publish_handle();
+        self.echo_config();
+    }
+
+    fn update_failed(&mut self) {
+        self.failed += 1;
+        self.update();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        let name = name.as_ref();
+        match self.metrics.iter_mut().find(|metric| metric.name == name) {
+            Some(metric) => metric.update(value),
+            None => self.metrics.push(Metric::new(name, value)),
+        }
+    }
+
+    fn update_and_display(&mut self) {
+        self.count += 1;
+        let now = clock::now();
+        self.output_allowed(now, true);
+        // A forced line bypasses the maximum number of logs
+        let max_logs = self.max_logs.take();
+        self.log(now);
+        self.max_logs = max_logs;
+    }
+
+    fn stop(&mut self) {
+        self.stop_time = Some(clock::now());
+        self.expected_updates = None;
+        self.snapshot = None;
+        self.publish_handle();
+    }
+
+    fn done(&mut self) {
+        self.flush_worker();
+        self.check_overshoot();
+        // Before stopping, as stopping clears the expected number of updates
+        self.write_progress_file(clock::now());
+        #[cfg(feature = "metrics")]
+        self.publish_metrics();
+        self.stop();
+        if !self.output_allowed(self.stop_time.unwrap_or_else(clock::now), false) {
+            self.expected_updates = None;
+            // Warnings are always logged
+            self.log_target_speed(false);
+            self.write_report();
+            self.write_trace();
+            return;
+        }
+        info!(target: &self.log_target, "Completed.");
+        // just to avoid wrong reuses
+        self.expected_updates = None;
+        let readings = self.readings();
+        self.log_self(self.failure_level(), "done", &readings);
+        self.log_target_speed(true);
+        if let Some(ci_format) = self.ci_format {
+            ci_format.print_finish(&self.start_msg, &*self);
+        }
+        if self.local_speed {
+            let now = self.stop_time.unwrap_or_else(clock::now);
+            if let Some(last) = self.update_interval_stats(now) {
+                let (fastest, slowest) = self.interval_seconds_per_item.unwrap();
+                info!(
+                    target: &self.log_target,
+                    "Last interval: {}; slowest interval: {}; fastest interval: {}",
+                    Speed(self, last),
+                    Speed(self, slowest),
+                    Speed(self, fastest)
+                );
+            }
+        }
+        for metric in &self.metrics {
+            info!(
+                target: &self.log_target,
+                "{}: min {:.4}, mean {:.4}, max {:.4}",
+                metric.name,
+                metric.min,
+                metric.sum / metric.n as f64,
+                metric.max
+            );
+        }
+        if let Some(res_mem) = readings.memory.as_ref().and_then(|memory| memory.res) {
+            self.res_mem_history.push(res_mem);
+        }
+        if let (Some(min), Some(max)) = (self.res_mem_history.min(), self.res_mem_history.max()) {
+            if self.res_mem_history.buckets().len() > 1 {
+                let values: Vec<f64> = self
+                    .res_mem_history
+                    .buckets()
+                    .iter()
+                    .map(|&v| v as f64)
+                    .collect();
+                info!(
+                    target: &self.log_target,
+                    "Resident-set size: {} (min {}B, max {}B)",
+                    sparkline(&values, Self::MEMORY_SPARKLINE_WIDTH),
+                    humanize(min as _),
+                    humanize(max as _)
+                );
+            }
+        }
+        if self.log_span_tree && !self.spans.is_empty() {
+            if let Some(span) = self.span() {
+                for line in span.to_string().lines() {
+                    info!(target: &self.log_target, "{}", line);
+                }
+            }
+        }
+        self.write_report();
+        self.write_trace();
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        self.count = count;
+        self.done();
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        match (self.time_budget, self.elapsed()) {
+            (Some(time_budget), Some(elapsed)) => elapsed > time_budget,
+            _ => false,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|cancel_flag| cancel_flag.load(Ordering::Relaxed))
+    }
+
+    fn is_started(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop_time.is_some()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        let start_time = self.start_time?;
+        let elapsed = self.stop_time.unwrap_or_else(clock::now) - start_time;
+        (!elapsed.is_zero()).then(|| self.count as f64 / elapsed.as_secs_f64())
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        if self.start_time.is_none() || self.stop_time.is_some() {
+            return None;
+        }
+        let (last_log_time, last_count) = self.log_history[0];
+        let elapsed = clock::now() - last_log_time;
+        (!elapsed.is_zero()).then(|| (self.count - last_count) as f64 / elapsed.as_secs_f64())
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.smoothed_eta(clock::now())
+    }
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        Some(clock::now() - self.start_time?)
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        self.wait_worker();
+        info!(target: &self.log_target, "{}", args);
+    }
+}
+
+/// Shows the configuration and the state of the logger, omitting system
+/// information, callbacks, and other internal machinery.
+impl Debug for ProgressLogger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("ProgressLogger")
+            .field("item_name", &self.item_name)
+            .field("item_name_plural", &self.item_name_plural)
+            .field("log_interval", &self.log_interval)
+            .field("log_interval_jitter", &self.log_interval_jitter)
+            .field("expected_updates", &self.expected_updates)
+            .field("time_unit", &self.time_unit)
+            .field("local_speed", &self.local_speed)
+            .field("local_speed_window", &self.local_speed_window)
+            .field("min_speed", &self.min_speed)
+            .field("skip_idle", &self.skip_idle)
+            .field("max_logs", &self.max_logs)
+            .field("min_duration", &self.min_duration)
+            .field("target_speed", &self.target_speed)
+            .field("failure_rate_thresholds", &self.failure_rate_thresholds)
+            .field("overshoot_policy", &self.overshoot_policy)
+            .field("expected_duration", &self.expected_duration)
+            .field("time_budget", &self.time_budget)
+            .field("progress_file", &self.progress_file)
+            .field("ci_format", &self.ci_format)
+            .field("log_on_drop", &self.log_on_drop)
+            .field("log_span_tree", &self.log_span_tree)
+            .field("display_remaining", &self.display_remaining)
+            .field("elapsed_format", &self.elapsed_format)
+            .field("display_timestamp", &self.display_timestamp)
+            .field("display_fraction", &self.display_fraction)
+            .field("log_config", &self.log_config)
+            .field("display_memory", &self.display_memory)
+            .field("display_load_average", &self.display_load_average)
+            .field("display_memory_delta", &self.display_memory_delta)
+            .field("memory_warning_threshold", &self.memory_warning_threshold)
+            .field("free_space", &self.free_space)
+            .field("eta_smoothing", &self.eta_smoothing)
+            .field("eta_range_threshold", &self.eta_range_threshold)
+            .field("speed_sparkline", &self.speed_sparkline)
+            .field("fixed_width", &self.fixed_width)
+            .field("display_fields", &self.display_fields)
+            .field("timing_first", &self.timing_first)
+            .field("deferred_logging", &self.deferred_logging)
+            .field("ratios", &self.ratios)
+            .field("bytes", &self.bytes)
+            .field("byte_units", &self.byte_units)
+            .field("log_target", &self.log_target)
+            .field("start_msg", &self.start_msg)
+            .field("start_time", &self.start_time)
+            .field("stop_time", &self.stop_time)
+            .field("count", &self.count)
+            .field("failed", &self.failed)
+            .field("count_offset", &self.count_offset)
+            .field("metrics", &self.metrics)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Displays an expected number of updates, or `none`.
+struct Expected(Option<usize>);
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.0 {
+            Some(expected_updates) => write!(f, "{}", Grouped(expected_updates)),
+            None => write!(f, "none"),
+        }
+    }
+}
+
+/// Displays a flag as `on` or `off`.
+struct OnOff(bool);
+
+impl Display for OnOff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(if self.0 { "on" } else { "off" })
+    }
+}
+
+impl Display for ProgressLogger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.fmt_with(f, &self.readings())
+    }
+}
+
+impl ProgressLogger {
+    /// Format the logger using the given time and system information.
+    fn fmt_with(&self, f: &mut Formatter<'_>, readings: &Readings) -> Result {
+        if self.display_timestamp {
+            f.write_fmt(format_args!("{} ", utils::rfc3339(readings.since_epoch)))?;
+        }
+
+        if self.start_time.is_some() {
+            if self.stop_time.is_some() {
+                let elapsed = readings.elapsed;
+                let seconds_per_item = elapsed.as_secs_f64() / self.count as f64;
+
+                let fields = self.display_fields;
+                let speed = fields.intersects(DisplayFields::SPEED | DisplayFields::TIMING);
+
+                if fields.contains(DisplayFields::ELAPSED) {
+                    f.write_fmt(format_args!(
+                        "Elapsed: {}",
+                        self.elapsed_format.display(elapsed)
+                    ))?;
+                }
+
+                if self.count != 0 && (fields.contains(DisplayFields::COUNT) || speed) {
+                    if fields.contains(DisplayFields::ELAPSED) {
+                        f.write_fmt(format_args!(" "))?;
+                    }
+                    f.write_fmt(format_args!("["))?;
+                    if fields.contains(DisplayFields::COUNT) {
+                        self.fmt_count(f)?;
+                        if speed {
+                            f.write_fmt(format_args!(", "))?;
+                        }
+                    }
+                    self.fmt_timing_speed(f, seconds_per_item)?;
+                    f.write_fmt(format_args!("]"))?;
+                    self.fmt_target_speed(f, seconds_per_item)?;
+                }
+
+                self.fmt_failed(f)?;
+                self.fmt_ratios(f)?;
+            } else {
+                let now = readings.now;
+
+                let elapsed = readings.elapsed;
+
+                let fields = self.display_fields;
+                let mut sep = "";
+
+                if fields.contains(DisplayFields::COUNT) {
+                    self.fmt_count(f)?;
+                    sep = ", ";
+                }
+                if fields.contains(DisplayFields::ELAPSED) {
+                    f.write_fmt(format_args!(
+                        "{}{:>w$}",
+                        sep,
+                        self.elapsed_format.display(elapsed),
+                        w = self.width(Self::ELAPSED_WIDTH)
+                    ))?;
+                    sep = ", ";
+                }
+
+                let seconds_per_item = elapsed.as_secs_f64() / self.count as f64;
+                if fields.intersects(DisplayFields::SPEED | DisplayFields::TIMING) {
+                    f.write_fmt(format_args!("{}", sep))?;
+                    self.fmt_timing_speed(f, seconds_per_item)?;
+                    sep = ", ";
+                }
+                self.fmt_target_speed(f, seconds_per_item)?;
+
+                if let Some(expected_updates) = self.expected_updates {
+                    let total_count = self.total_count();
+                    let millis_to_end: u128 = match (self.eta_smoothing, self.eta_deadline) {
+                        (EtaSmoothing::Raw, _) | (_, None) => {
+                            (expected_updates.saturating_sub(total_count) as u128
+                                * elapsed.as_millis())
+                                / (self.count as u128 + 1)
+                        }
+                        (_, Some(deadline)) => deadline.saturating_duration_since(now).as_millis(),
+                    };
+                    // Separate from the previous fields, if any
+                    let mut sep = if sep.is_empty() { "" } else { "; " };
+                    if self.display_fraction {
+                        if self.time_unit.is_none() {
+                            f.write_fmt(format_args!(
+                                "{}{}/{}",
+                                sep,
+                                Grouped(total_count),
+                                Grouped(expected_updates)
+                            ))?;
+                        } else {
+                            f.write_fmt(format_args!(
+                                "{}{}/{}",
+                                sep, total_count, expected_updates
+                            ))?;
+                        }
+                        sep = ", ";
+                    }
+                    if total_count > expected_updates {
+                        let excess = total_count - expected_updates;
+                        if self.time_unit.is_none() {
+                            f.write_fmt(format_args!("{}{} over expected", sep, Grouped(excess)))?;
+                        } else {
+                            f.write_fmt(format_args!("{}{} over expected", sep, excess))?;
+                        }
+                    } else {
+                        if fields.contains(DisplayFields::PERCENT) {
+                            f.write_fmt(format_args!(
+                                "{}{:>w$.2}% done",
+                                sep,
+                                100.0 * total_count as f64 / expected_updates as f64,
+                                w = self.width(Self::PERCENT_WIDTH)
+                            ))?;
+                            sep = ", ";
+                        }
+                        if self.display_remaining {
+                            let remaining = expected_updates.saturating_sub(total_count);
+                            if self.bytes {
+                                f.write_fmt(format_args!(
+                                    "{}{} to go",
+                                    sep,
+                                    self.humanize_bytes(remaining as f64)
+                                ))?;
+                            } else if self.time_unit.is_none() {
+                                f.write_fmt(format_args!(
+                                    "{}{} to go",
+                                    sep,
+                                    Humanized::new(remaining as f64, false, "")
+                                ))?;
+                            } else {
+                                f.write_fmt(format_args!("{}{} to go", sep, remaining))?;
+                            }
+                            sep = ", ";
+                        }
+                        if fields.contains(DisplayFields::ETA) {
+                            match self.eta_interval(expected_updates - total_count) {
+                                Some((fast, slow)) => f.write_fmt(format_args!(
+                                    "{}{}–{} to end",
+                                    sep,
+                                    Pretty(fast.as_millis()),
+                                    Pretty(slow.as_millis())
+                                ))?,
+                                None => f.write_fmt(format_args!(
+                                    "{}{} to end",
+                                    sep,
+                                    Pretty(millis_to_end)
+                                ))?,
+                            }
+                        }
+                    }
+                }
+
+                self.fmt_failed(f)?;
+
+                if let Some(expected_duration) = self.expected_duration {
+                    f.write_fmt(format_args!(
+                        "; {:>w$.2}% of {} elapsed",
+                        100.0 * elapsed.as_secs_f64() / expected_duration.as_secs_f64(),
+                        Pretty(expected_duration.as_millis()),
+                        w = self.width(Self::PERCENT_WIDTH)
+                    ))?;
+                }
+
+                if self.local_speed
+                    && self.stop_time.is_none()
+                    && fields.intersects(DisplayFields::SPEED | DisplayFields::TIMING)
+                {
+                    f.write_fmt(format_args!(" ["))?;
+
+                    let (last_log_time, last_count) = self.log_history[0];
+                    let elapsed = now - last_log_time;
+                    let seconds_per_item = elapsed.as_secs_f64() / (self.count - last_count) as f64;
+                    self.fmt_timing_speed(f, seconds_per_item)?;
+
+                    f.write_fmt(format_args!("]"))?;
+                }
+
+                if let Some(speed_sparkline) = self.speed_sparkline.filter(|&speed_sparkline| {
+                    speed_sparkline != 0
+                        && !self.recent_speeds.is_empty()
+                        && fields.intersects(DisplayFields::SPEED | DisplayFields::TIMING)
+                }) {
+                    f.write_char(' ')?;
+                    write_sparkline(
+                        f,
+                        self.recent_speeds
+                            .range(self.recent_speeds.len().saturating_sub(speed_sparkline)..)
+                            .copied(),
+                    )?;
+                }
+
+                for (i, metric) in self.metrics.iter().enumerate() {
+                    f.write_fmt(format_args!(
+                        "{}{} {:.4}",
+                        if i == 0 { "; " } else { ", " },
+                        metric.name,
+                        metric.last
+                    ))?;
+                }
+
+                self.fmt_ratios(f)?;
+            }
+
+            if let Some(memory) = &readings.memory {
+                f.write_fmt(format_args!(
+                    "; {}/avail/free/total mem {}/{}/{}B/{}B/{}B",
+                    MEMORY_LABELS[0],
+                    memory
+                        .res
+                        .map(|res| humanize(res as _) + "B")
+                        .unwrap_or("N/A".to_string()),
+                    memory
+                        .vir
+                        .map(|vir| humanize(vir as _) + "B")
+                        .unwrap_or("N/A".to_string()),
+                    humanize(memory.available as _),
+                    humanize(memory.free as _),
+                    humanize(memory.total as _)
+                ))?;
+
+                f.write_fmt(format_args!(
+                    ", {} {}B/{}B",
+                    MEMORY_LABELS[1],
+                    humanize(memory.used_swap as _),
+                    humanize(memory.total_swap as _)
+                ))?;
+
+                if self.display_memory_delta {
+                    if let (Some(last_res_mem), Some(res_mem)) = (self.last_res_mem, memory.res) {
+                        f.write_fmt(format_args!(
+                            ", {} mem delta {}{}B",
+                            MEMORY_LABELS[2],
+                            if res_mem >= last_res_mem { "+" } else { "-" },
+                            humanize(res_mem.abs_diff(last_res_mem) as _)
+                        ))?;
+                    }
+                }
+            }
+
+            if let Some(free_space) = readings.free_space {
+                f.write_fmt(format_args!("; free space {}B", humanize(free_space as _)))?;
+            }
+
+            if let Some([one, five, fifteen]) = readings.load_average {
+                f.write_fmt(format_args!(
+                    "; load avg {:.2}/{:.2}/{:.2}",
+                    one, five, fifteen
+                ))?;
+            }
+
+            Ok(())
+        } else {
+            write!(f, "ProgressLogger not started")
+        }
+    }
+}
+
+/// Clone the logger, returning a logger with the same setup but with all
+/// the counters reset.
+impl Clone for ProgressLogger {
+    fn clone(&self) -> Self {
+        // Since ProgressLogger implements Drop, we cannot use the struct update
+        // syntax
+        let mut pl = ProgressLogger::default();
+        pl.item_name = self.item_name.clone();
+        pl.item_name_plural = self.item_name_plural.clone();
+        pl.log_interval = self.log_interval;
+        pl.log_interval_jitter = self.log_interval_jitter;
+        pl.log_target = self.log_target.clone();
+        pl.time_unit = self.time_unit;
+        pl.local_speed = self.local_speed;
+        pl.local_speed_window = self.local_speed_window;
+        pl.min_speed = self.min_speed;
+        pl.skip_idle = self.skip_idle;
+        pl.max_logs = self.max_logs;
+        pl.min_duration = self.min_duration;
+        pl.target_speed = self.target_speed;
+        pl.failure_rate_thresholds = self.failure_rate_thresholds;
+        pl.overshoot_policy = self.overshoot_policy;
+        pl.eta_smoothing = self.eta_smoothing;
+        pl.eta_range_threshold = self.eta_range_threshold;
+        pl.speed_sparkline = self.speed_sparkline;
+        pl.expected_duration = self.expected_duration;
+        pl.time_budget = self.time_budget;
+        pl.on_time_budget_exceeded = self.on_time_budget_exceeded.clone();
+        pl.cancel_flag = self.cancel_flag.clone();
+        pl.memory_refresh_interval = self.memory_refresh_interval;
+        pl.display_memory_delta = self.display_memory_delta;
+        pl.memory_warning_threshold = self.memory_warning_threshold;
+        pl.cgroup_memory_limit = self.cgroup_memory_limit;
+        pl.display_remaining = self.display_remaining;
+        pl.elapsed_format = self.elapsed_format;
+        pl.display_timestamp = self.display_timestamp;
+        pl.display_fraction = self.display_fraction;
+        pl.log_config = self.log_config;
+        pl.ratios = self.ratios.clone();
+        pl.fixed_width = self.fixed_width;
+        pl.display_fields = self.display_fields;
+        pl.timing_first = self.timing_first;
+        pl.deferred_logging = self.deferred_logging;
+        pl.bytes = self.bytes;
+        pl.byte_units = self.byte_units;
+        pl.display_memory = self.display_memory;
+        pl.display_load_average = self.display_load_average;
+        pl.log_on_drop = self.log_on_drop;
+        pl.log_span_tree = self.log_span_tree;
+        pl.progress_file = self.progress_file.clone();
+        pl.report_file = self.report_file.clone();
+        pl.trace_file = self.trace_file.clone();
+        pl.ci_format = self.ci_format;
+        pl.update_system();
+        pl.monitor_free_space(
+            self.free_space.as_ref().map(|(path, _)| path.as_path()),
+            self.free_space.as_ref().map_or(0, |&(_, min)| min),
+        );
+        pl
+    }
+}
+
+impl Drop for ProgressLogger {
+    fn drop(&mut self) {
+        self.stop_worker();
+        if self.log_on_drop && self.start_time.is_some() && self.stop_time.is_none() {
+            self.stop();
+            warn!(target: &self.log_target, "Dropped while running: {}", self);
+        }
+    }
+}
+
+/// A concurrent wrapper for a [`ProgressLog`] implementation.
 ///
-/// A progress logger can also be used as a handy timer:
+/// This struct wraps a [`ProgressLog`] in such as way that multiple thread can
+/// write to it. Writes are synchronized using a mutex, but they are also
+/// buffered using a given threshold, so the mutex is not accessed too often.
 ///
-/// ```rust
-/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-/// use dsi_progress_logger::prelude::*;
+/// Once a [`ConcurrentWrapper`] is created, one can
+/// [clone](#impl-Clone-for-ConcurrentWrapper<P>) it to create any number of
+/// copies using the same underlying logger.
 ///
-/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+/// The methods [`update`](ProgressUpdate::update) and
+/// [`update_with_count`](ProgressUpdate::update_with_count) buffer the increment
+/// and add it to the underlying logger only when the buffer reaches a
+/// threshold; this prevents locking the underlying logger too often. The
+/// threshold is set at creation using the methods
+/// [`with_threshold`](Self::with_threshold) and
+/// [`wrap_with_threshold`](Self::wrap_with_threshold), or by calling the method
+/// [`threshold`](Self::threshold).
 ///
-/// let mut pl = progress_logger![item_name="pumpkin"];
-/// pl.start("Smashing pumpkins...");
-/// for _ in 0..100 {
-///    // do something on each pumpkin
-/// }
-/// pl.done_with_count(100);
-/// #     Ok(())
-/// # }
-/// ```
+/// The method [`light_update`](ProgressUpdate::light_update), as in the case of
+/// [`ProgressLogger`], further delays updates using an even faster check.
 ///
-/// This progress logger will display information about  memory usage:
+/// The methods [`count`](ProgressLog::count) and
+/// [`is_cancelled`](ProgressLog::is_cancelled) do not lock the underlying
+/// logger (the latter, if the [cancel flag](ProgressLog::cancel_flag) has
+/// been set through the wrapper), so they can be called often by all threads.
+///
+/// You can [create a duplicate](Self::dup) of a concurrent wrapper, which will
+/// use a cloned inner logger.
+///
+/// # Examples
 ///
 /// ```rust
-/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// use dsi_progress_logger::prelude::*;
+/// use std::thread;
 ///
-/// env_logger::builder().filter_level(log::LevelFilter::Info).try_init()?;
+/// let mut cpl = concurrent_progress_logger![item_name = "pumpkin"];
+/// cpl.start("Smashing pumpkins (using many threads)...");
+///
+/// std::thread::scope(|s| {
+///     for i in 0..100 {
+///         let mut pl = cpl.clone();
+///         s.spawn(move || {
+///             for _ in 0..100000 {
+///                 pl.update();
+///             }
+///         });
+///     }
+/// });
 ///
-/// let mut pl = progress_logger![display_memory=true];
-/// #     Ok(())
-/// # }
+/// cpl.done();
 /// ```
-pub struct ProgressLogger {
-    /// The name of an item. Defaults to `item`.
-    item_name: String,
-    /// The log interval. Defaults to 10 seconds.
-    log_interval: Duration,
-    /// The expected number of updates. If set, the logger will display the percentage of completion and
-    /// an estimate of the time to completion.
-    expected_updates: Option<usize>,
-    /// The time unit to use for speed. If set, the logger will always display the speed in this unit
-    /// instead of making a choice of readable unit based on the elapsed time. Moreover, large numbers
-    /// will not be thousands separated. This is useful when the output of the logger must be parsed.
-    time_unit: Option<TimeUnit>,
-    /// Display additionally the speed achieved during the last log interval.
-    local_speed: bool,
-    /// [`log`] target
-    ///
-    /// This is often the path of the module logging progress.
-    log_target: String,
-    /// When the logger was started.
-    start_time: Option<Instant>,
-    /// The last time we logged the activity (to compute speed).
-    last_log_time: Instant,
-    /// The next time we will log the activity.
-    next_log_time: Instant,
-    /// When the logger was stopped.
-    stop_time: Option<Instant>,
-    /// The number of items.
-    count: usize,
-    /// The number of items at the last log (to compute speed).
-    last_count: usize,
-    /// Display additionally the amount of used and free memory using this [`sysinfo::System`]
-    system: Option<System>,
-    /// The pid of the current process
-    pid: Pid,
+#[derive(Debug)]
+pub struct ConcurrentWrapper<P: ProgressLog = ProgressLogger> {
+    /// Underlying logger
+    inner: Arc<Mutex<P>>,
+    /// The number of items processed by the current thread.
+    local_count: u32,
+    /// The number of failed items processed by the current thread, which will
+    /// be passed to the underlying logger together with `local_count`.
+    local_failed: u32,
+    /// The threshold for updating the underlying logger.
+    threshold: u32,
+    /// The state shared by the copies of the wrapper outside the lock.
+    state: Arc<WrapperState>,
+    /// The cancel flag, if set through the wrapper.
+    cancel_flag: Option<Arc<AtomicBool>>,
 }
 
-/// Macro to create a [`ProgressLogger`] with default log target set to
-/// [`std::module_path!`], and key-value pairs instead of setters.
+/// The state of a [`ConcurrentWrapper`] that is shared by its copies and
+/// accessed without locking the underlying logger.
+#[derive(Default)]
+struct WrapperState {
+    /// The count of the underlying logger, updated each time the logger is
+    /// updated.
+    count: AtomicUsize,
+    /// Whether the underlying logger has detected an overrun of the time
+    /// budget that has not been passed to the callback yet.
+    time_budget_exceeded: AtomicBool,
+    /// The [time-budget callback](ProgressLog::on_time_budget_exceeded), which
+    /// is invoked after releasing the lock, so that it can use the wrapper.
+    time_budget_callback: Mutex<Option<Arc<dyn Fn() + Send + Sync>>>,
+}
+
+impl Debug for WrapperState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("WrapperState")
+            .field("count", &self.count)
+            .field("time_budget_exceeded", &self.time_budget_exceeded)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Macro to create a [`ConcurrentWrapper`] based on a
+/// [`ProgressLogger`], with default log target set to [`std::module_path!`],
+/// and key-value pairs instead of setters.
 ///
-/// # Examples
+/// Besides the setters of [`ProgressLog`], keys can be setters specific to
+/// [`ConcurrentWrapper`], such as [`threshold`](ConcurrentWrapper::threshold).
 ///
+/// # Examples
 ///
 /// ```rust
 /// use dsi_progress_logger::prelude::*;
 ///
-/// let mut pl = progress_logger![item_name="pumpkin", display_memory=true];
+/// let mut pl = concurrent_progress_logger![item_name="pumpkin", display_memory=true];
+/// let mut pl = concurrent_progress_logger![item_name="pumpkin", threshold=1 << 10];
 /// ```
 #[macro_export]
-macro_rules! progress_logger {
+macro_rules! concurrent_progress_logger {
     ($($method:ident = $arg:expr),* $(,)?) => {
         {
-            let mut pl = ::dsi_progress_logger::ProgressLogger::default();
-            ::dsi_progress_logger::ProgressLog::log_target(&mut pl, ::std::module_path!());
+            #[allow(unused_imports)]
+            use ::dsi_progress_logger::ProgressLog as _;
+            let mut cpl = ::dsi_progress_logger::ConcurrentWrapper::default();
+            cpl.log_target(::std::module_path!());
             $(
-                ::dsi_progress_logger::ProgressLog::$method(&mut pl, $arg);
+                cpl.$method($arg);
             )*
-            pl
+            cpl
         }
     }
 }
 
-/// Create a default [`ProgressLogger`] with a log interval of 10 seconds and
-/// item name set to “item”.
-impl Default for ProgressLogger {
+/// Create a new [`ConcurrentWrapper`] based on a default
+/// [`ProgressLogger`], with a threshold of
+/// [`DEFAULT_THRESHOLD`](Self::DEFAULT_THRESHOLD).
+impl Default for ConcurrentWrapper {
+    #[track_caller]
     fn default() -> Self {
         Self {
-            item_name: "item".into(),
-            log_interval: Duration::from_secs(10),
-            expected_updates: None,
-            time_unit: None,
-            local_speed: false,
-            log_target: std::env::current_exe()
-                .ok()
-                .and_then(|path| {
-                    path.file_name()
-                        .and_then(|s| s.to_owned().into_string().ok())
-                })
-                .unwrap_or_else(|| "main".to_string()),
-            start_time: None,
-            last_log_time: Instant::now(),
-            next_log_time: Instant::now(),
-            stop_time: None,
-            count: 0,
-            last_count: 0,
-            system: None,
-            pid: Pid::from(std::process::id() as usize),
+            inner: Arc::new(Mutex::new(ProgressLogger::default())),
+            local_count: 0,
+            local_failed: 0,
+            threshold: Self::DEFAULT_THRESHOLD,
+            state: Arc::default(),
+            cancel_flag: None,
         }
     }
 }
 
-impl ProgressLogger {
-    /// Calls to [light_update](ProgressLog::light_update) will cause a call to
-    /// [`Instant::now`] only if the current count is a multiple of this mask
-    /// plus one.
-    pub const LIGHT_UPDATE_MASK: usize = (1 << 20) - 1;
+impl ConcurrentWrapper {
+    /// Create a new [`ConcurrentWrapper`] based on a default
+    /// [`ProgressLogger`], using the [default
+    /// threshold](Self::DEFAULT_THRESHOLD).
+    #[track_caller]
+    pub fn new() -> Self {
+        Self::with_threshold(Self::DEFAULT_THRESHOLD)
+    }
 
-    fn fmt_timing_speed(&self, f: &mut Formatter<'_>, seconds_per_item: f64) -> Result {
-        let items_per_second = 1.0 / seconds_per_item;
+    /// Create a new [`ConcurrentWrapper`] wrapping a default
+    /// [`ProgressLogger`], using the given threshold.
+    #[track_caller]
+    pub fn with_threshold(threshold: u32) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ProgressLogger::default())),
+            local_count: 0,
+            local_failed: 0,
+            threshold,
+            state: Arc::default(),
+            cancel_flag: None,
+        }
+    }
 
-        let time_unit_timing = self
-            .time_unit
-            .unwrap_or_else(|| TimeUnit::nice_time_unit(seconds_per_item));
+    /// Create a new [`ConcurrentWrapper`] based on a default
+    /// [`ProgressLogger`], using the [default
+    /// threshold](Self::DEFAULT_THRESHOLD), and [start](ProgressLog::start) it
+    /// with the given message.
+    #[track_caller]
+    pub fn started(msg: impl AsRef<str>) -> Self {
+        let mut cpl = Self::new();
+        cpl.start(msg);
+        cpl
+    }
+}
 
-        let time_unit_speed = self
-            .time_unit
-            .unwrap_or_else(|| TimeUnit::nice_speed_unit(seconds_per_item));
+impl<P: ProgressLog> ConcurrentWrapper<P> {
+    /// The default threshold for updating the underlying logger.
+    pub const DEFAULT_THRESHOLD: u32 = 1 << 15;
 
-        f.write_fmt(format_args!(
-            "{:.2} {}/{}, {:.2} {}/{}",
-            items_per_second * time_unit_speed.as_seconds(),
-            pluralize(&self.item_name, 2, false),
-            time_unit_speed.label(),
-            seconds_per_item / time_unit_timing.as_seconds(),
-            time_unit_timing.label(),
-            self.item_name
-        ))?;
+    /// Calls to [`light_update`](ProgressUpdate::light_update) will cause a call
+    /// to [`update_with_count`](ProgressUpdate::update_with_count) only if the
+    /// current local count is a multiple of this mask plus one.
+    ///
+    /// Note that this constant is significantly smaller than the one used in
+    /// [`ProgressLogger`], as updates will be further delayed by the threshold
+    /// mechanism.
+    pub const LIGHT_UPDATE_MASK: u32 = (1 << 10) - 1;
 
-        Ok(())
+    /// Set the threshold for updating the underlying logger.
+    ///
+    /// Note concurrent loggers with the same underlying logger
+    /// have independent thresholds.
+    pub fn threshold(&mut self, threshold: u32) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Wrap a given [`ProgressLog`] in a [`ConcurrentWrapper`]
+    /// using the [default threshold](Self::DEFAULT_THRESHOLD).
+    pub fn wrap(inner: P) -> Self {
+        Self::wrap_with_threshold(inner, Self::DEFAULT_THRESHOLD)
+    }
+
+    /// Wrap a given [`ProgressLog`] in a [`ConcurrentWrapper`] using a
+    /// given threshold.
+    pub fn wrap_with_threshold(inner: P, threshold: u32) -> Self {
+        let state = WrapperState {
+            count: AtomicUsize::new(inner.count()),
+            ..Default::default()
+        };
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            local_count: 0,
+            local_failed: 0,
+            threshold,
+            state: Arc::new(state),
+            cancel_flag: None,
+        }
+    }
+
+    /// Force an update of the underlying logger with the current local count.
+    pub fn flush(&mut self) {
+        self.pass(0, None);
+    }
+
+    /// Pass the local count, and the given additional count, to the underlying
+    /// logger, using the given time, if any.
+    fn pass(&mut self, count: usize, now: Option<Instant>) {
+        let (local_count, local_failed) = (self.local_count, self.local_failed);
+        self.apply(|pl| Self::pass_to(pl, local_count, local_failed, count, now));
+        self.local_count = 0;
+        self.local_failed = 0;
+    }
+
+    /// Pass to the (locked) underlying logger a local count and an additional
+    /// count, including overall `local_failed` failed items, using the given
+    /// time, if any.
+    fn pass_to(
+        pl: &mut P,
+        local_count: u32,
+        local_failed: u32,
+        count: usize,
+        now: Option<Instant>,
+    ) {
+        let mut update = |count| match now {
+            Some(now) => pl.update_with_count_and_time(count, now),
+            None => pl.update_with_count(count),
+        };
+        match (local_count as usize).checked_add(count) {
+            Some(total_count) => update(total_count - local_failed as usize),
+            None => {
+                // Sum overflows, update in two steps; count is huge, so it
+                // covers the failed items
+                update(local_count as usize);
+                update(count - local_failed as usize);
+            }
+        }
+        // Failed items are passed one by one, as they are expected to be rare,
+        // after the other items, so that a log does not overstate the failure
+        // rate
+        for _ in 0..local_failed {
+            pl.update_failed();
+        }
+    }
+
+    /// Apply a function that might change the count to the underlying logger.
+    ///
+    /// The count is published to the copies of the wrapper before releasing
+    /// the lock, and the time-budget callback is invoked, if the underlying
+    /// logger has detected an overrun, after releasing the lock.
+    fn apply<T>(&self, f: impl FnOnce(&mut P) -> T) -> T {
+        let mut pl = self.inner.lock().unwrap();
+        let result = f(&mut pl);
+        self.publish_count(&pl);
+        drop(pl);
+        self.run_time_budget_callback();
+        result
+    }
+
+    /// Publish the count of the (locked) underlying logger to the copies of
+    /// the wrapper.
+    #[inline]
+    fn publish_count(&self, pl: &P) {
+        self.state.count.store(pl.count(), Ordering::Relaxed);
+    }
+
+    /// Invoke the time-budget callback if the underlying logger has detected
+    /// an overrun while it was locked.
+    ///
+    /// This method must be called after the lock has been released.
+    #[inline]
+    fn run_time_budget_callback(&self) {
+        if self
+            .state
+            .time_budget_exceeded
+            .swap(false, Ordering::Relaxed)
+        {
+            let callback = self.state.time_budget_callback.lock().unwrap().clone();
+            if let Some(callback) = callback {
+                callback();
+            }
+        }
+    }
+
+    /// Increase the local count by one, without ever blocking.
+    ///
+    /// See [`try_update_with_count`](Self::try_update_with_count).
+    #[inline]
+    pub fn try_update(&mut self) {
+        self.try_update_with_count(1);
+    }
+
+    /// Increase the local count by the given amount, without ever blocking.
+    ///
+    /// This method is analogous to
+    /// [`update_with_count`](ProgressUpdate::update_with_count), but when the
+    /// threshold is reached the local count is passed to the underlying logger
+    /// only if its lock can be acquired immediately; otherwise, the local count
+    /// is kept, and passing it is attempted again at the next call. Thus, the
+    /// calling thread never waits, at the price of a less fresh display. The
+    /// only exception is when the local count would overflow a `u32`, in which
+    /// case the method blocks. As with the other methods, the method panics if
+    /// the lock is poisoned.
+    #[inline]
+    pub fn try_update_with_count(&mut self, count: usize) {
+        let total_count = match (self.local_count as usize).checked_add(count) {
+            Some(total_count) if total_count < self.threshold as usize => {
+                // total_count is lower than self.threshold, which is a u32;
+                // so total_count fits in u32.
+                self.local_count = total_count as u32;
+                return;
+            }
+            Some(total_count) => total_count,
+            None => {
+                // Sum overflows, block
+                self.pass(count, None);
+                return;
+            }
+        };
+        let mut pl = match self.inner.try_lock() {
+            Ok(pl) => pl,
+            Err(TryLockError::WouldBlock) if total_count <= u32::MAX as usize => {
+                // Contended, keep the count for the next attempt
+                self.local_count = total_count as u32;
+                return;
+            }
+            // Block, or propagate poisoning
+            Err(_) => self.inner.lock().unwrap(),
+        };
+        Self::pass_to(&mut pl, self.local_count, self.local_failed, count, None);
+        self.publish_count(&pl);
+        drop(pl);
+        self.local_count = 0;
+        self.local_failed = 0;
+        self.run_time_budget_callback();
+    }
+
+    /// Return the underlying logger, if this is the only copy of the wrapper
+    /// sharing it, or the wrapper itself otherwise.
+    ///
+    /// The local count is flushed in both cases. This method mirrors
+    /// [`Arc::try_unwrap`], and it is useful to go back to a plain logger
+    /// (e.g., for a sequential phase) after a parallel phase.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut cpl = concurrent_progress_logger![item_name = "pumpkin"];
+    /// cpl.start("Parallel phase...");
+    /// std::thread::scope(|s| {
+    ///     for _ in 0..4 {
+    ///         let mut cpl = cpl.clone();
+    ///         s.spawn(move || {
+    ///             for _ in 0..1000 {
+    ///                 cpl.update();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let mut pl = cpl.into_inner().ok().unwrap();
+    /// pl.info(format_args!("Sequential phase..."));
+    /// for _ in 0..1000 {
+    ///     pl.update();
+    /// }
+    /// pl.done();
+    /// ```
+    pub fn into_inner(mut self) -> core::result::Result<P, Self> {
+        self.flush();
+        if Arc::strong_count(&self.inner) != 1 {
+            return Err(self);
+        }
+        let inner = self.inner.clone();
+        // Dropping self flushes a zero local count
+        drop(self);
+        Ok(Arc::try_unwrap(inner)
+            .ok()
+            .expect("the wrapper was the only owner")
+            .into_inner()
+            .unwrap())
+    }
+}
+impl<P: ProgressLog + Clone> ConcurrentWrapper<P> {
+    /// Clone the concurrent wrapper, obtaning a new one with the same
+    /// threshold, with a local count of zero, and with an inner [`ProgressLog`]
+    /// that is a clone of the original one.
+    ///
+    /// Note that the this method has the same sematics of [`ProgressLogser`'s
+    /// `Clone` implementation](ProgressLogger#impl-Clone-for-ProgressLogger),
+    /// but it is much more ergonomic here to have [cloning to generate copies
+    /// with the same underlying logger](#impl-Clone-for-ConcurrentWrapper<P>).
+    pub fn dup(&self) -> Self {
+        let mut cpl = Self::wrap_with_threshold(self.inner.lock().unwrap().clone(), self.threshold);
+        cpl.cancel_flag = self.cancel_flag.clone();
+        // The inner clone would signal overruns to this wrapper
+        let callback = self.state.time_budget_callback.lock().unwrap().clone();
+        if let Some(callback) = callback {
+            cpl.on_time_budget_exceeded(move || callback());
+        }
+        cpl
+    }
+}
+
+impl<P: ProgressLog + Send> ConcurrentWrapper<P> {
+    /// Create a scope for spawning threads, as in [`std::thread::scope`],
+    /// in which each spawned thread receives its own clone of this wrapper.
+    ///
+    /// Threads are spawned using [`ConcurrentScope::spawn`], whose closure
+    /// receives the clone as argument. Since all threads are joined, and thus
+    /// all clones are dropped (and flushed), before this method returns, the
+    /// count of the wrapper is exact when the method returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut cpl = concurrent_progress_logger![item_name = "pumpkin"];
+    /// cpl.start("Smashing pumpkins (using many threads)...");
+    ///
+    /// cpl.scope(|s| {
+    ///     for _ in 0..100 {
+    ///         s.spawn(|mut pl| {
+    ///             for _ in 0..100000 {
+    ///                 pl.update();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// cpl.done();
+    /// ```
+    pub fn scope<'env, F, T>(&'env self, f: F) -> T
+    where
+        F: for<'scope> FnOnce(&ConcurrentScope<'scope, 'env, P>) -> T,
+    {
+        std::thread::scope(|scope| f(&ConcurrentScope { scope, cpl: self }))
+    }
+}
+
+/// A scope for spawning threads created by [`ConcurrentWrapper::scope`].
+pub struct ConcurrentScope<'scope, 'env: 'scope, P: ProgressLog> {
+    /// The underlying scope.
+    scope: &'scope std::thread::Scope<'scope, 'env>,
+    /// The wrapper to be cloned for each thread.
+    cpl: &'env ConcurrentWrapper<P>,
+}
+
+impl<'scope, P: ProgressLog + Send> ConcurrentScope<'scope, '_, P> {
+    /// Spawn a scoped thread, as in [`std::thread::Scope::spawn`], passing to
+    /// `f` a clone of the wrapper.
+    pub fn spawn<F, R>(&self, f: F) -> std::thread::ScopedJoinHandle<'scope, R>
+    where
+        F: FnOnce(ConcurrentWrapper<P>) -> R + Send + 'scope,
+        R: Send + 'scope,
+    {
+        let cpl = self.cpl.clone();
+        self.scope.spawn(move || f(cpl))
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for ConcurrentWrapper<P> {
+    #[inline]
+    fn update(&mut self) {
+        self.update_with_count(1)
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        match (self.local_count as usize).checked_add(count) {
+            Some(total_count) if total_count < self.threshold as usize => {
+                // total_count is lower than self.threshold, which is a u32;
+                // so total_count fits in u32.
+                self.local_count = total_count as u32;
+            }
+            // Threshold reached (or sum overflowing), time to flush to the
+            // inner ProgressLog
+            _ => self.pass(count, None),
+        }
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        self.update_with_count_and_time(1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        match (self.local_count as usize).checked_add(count) {
+            Some(total_count) if total_count < self.threshold as usize => {
+                // total_count is lower than self.threshold, which is a u32;
+                // so total_count fits in u32.
+                self.local_count = total_count as u32;
+            }
+            // Threshold reached (or sum overflowing), time to flush to the
+            // inner ProgressLog
+            _ => self.pass(count, Some(now)),
+        }
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        self.local_count += 1;
+        if (self.local_count & Self::LIGHT_UPDATE_MASK) == 0 {
+            self.flush();
+        }
     }
 }
 
-impl ProgressLog for ProgressLogger {
+impl<P: ProgressLog> ProgressLog for ConcurrentWrapper<P> {
     fn log(&mut self, now: Instant) {
-        self.refresh();
-        info!(target: &self.log_target, "{}", self);
-        self.last_count = self.count;
-        self.last_log_time = now;
-        self.next_log_time = now + self.log_interval;
+        self.apply(|pl| pl.log(now));
+        self.local_count = 0;
+        self.local_failed = 0;
     }
 
     fn log_if(&mut self) {
-        let now = Instant::now();
-        if self.next_log_time <= now {
-            self.log(now);
-        }
+        self.apply(|pl| pl.log_if());
+        self.local_count = 0;
+        self.local_failed = 0;
     }
 
     fn display_memory(&mut self, display_memory: bool) -> &mut Self {
-        match (display_memory, &self.system) {
-            (true, None) => {
-                self.system = Some(System::new_with_specifics(RefreshKind::new().with_memory()));
-            }
-            (false, Some(_)) => {
-                self.system = None;
-            }
-            _ => (),
-        }
+        self.inner.lock().unwrap().display_memory(display_memory);
         self
     }
 
-    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
-        self.item_name = item_name.as_ref().into();
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .display_memory_delta(display_memory_delta);
         self
     }
 
-    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
-        self.log_interval = log_interval;
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .display_load_average(display_load_average);
         self
     }
 
-    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
-        self.expected_updates = expected_updates;
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .monitor_free_space(path, min_free_space);
         self
     }
 
-    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
-        self.time_unit = time_unit;
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .memory_warning_threshold(memory_warning_threshold);
         self
     }
 
-    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
-        self.local_speed = local_speed;
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .memory_refresh_interval(memory_refresh_interval);
         self
     }
 
-    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
-        self.log_target = target.as_ref().into();
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.inner.lock().unwrap().item_name(item_name);
         self
     }
 
-    fn start(&mut self, msg: impl AsRef<str>) {
-        let now = Instant::now();
-        self.start_time = Some(now);
-        self.stop_time = None;
-        self.count = 0;
-        self.last_count = 0;
-        self.last_log_time = now;
-        self.next_log_time = now + self.log_interval;
-        if !msg.as_ref().is_empty() {
-            info!(target: &self.log_target, "{}", msg.as_ref());
-        }
-    }
-
-    fn refresh(&mut self) {
-        if let Some(system) = &mut self.system {
-            system.refresh_process_specifics(self.pid, ProcessRefreshKind::new());
-        }
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .item_name_plural(item_name_plural);
+        self
     }
 
-    fn update(&mut self) {
-        self.count += 1;
-        self.log_if();
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.inner.lock().unwrap().log_interval(log_interval);
+        self
     }
 
-    fn update_with_count(&mut self, count: usize) {
-        self.count += count;
-        self.log_if();
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.inner.lock().unwrap().log_interval_jitter(jitter);
+        self
     }
 
-    /// Increases the count and, once every
-    /// [`LIGHT_UPDATE_MASK`](#fields.LIGHT_UPDATE_MASK) + 1 calls, check
-    /// whether it is time to log.
-    #[inline(always)]
-    fn light_update(&mut self) {
-        self.count += 1;
-        if (self.count & Self::LIGHT_UPDATE_MASK) == 0 {
-            self.log_if();
-        }
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .expected_updates(expected_updates);
+        self
     }
 
-    fn update_and_display(&mut self) {
-        self.count += 1;
-        self.log(Instant::now());
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.inner.lock().unwrap().time_unit(time_unit);
+        self
     }
 
-    fn stop(&mut self) {
-        self.stop_time = Some(Instant::now());
-        self.expected_updates = None;
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.inner.lock().unwrap().local_speed(local_speed);
+        self
     }
 
-    fn done(&mut self) {
-        self.stop();
-        info!(target: &self.log_target, "Completed.");
-        // just to avoid wrong reuses
-        self.expected_updates = None;
-        self.refresh();
-        info!(target: &self.log_target, "{}", self);
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .local_speed_window(local_speed_window);
+        self
     }
 
-    fn done_with_count(&mut self, count: usize) {
-        self.count = count;
-        self.done();
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.inner.lock().unwrap().min_speed(min_speed);
+        self
     }
 
-    fn elapsed(&self) -> Option<Duration> {
-        self.start_time?.elapsed().into()
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.inner.lock().unwrap().skip_idle(skip_idle);
+        self
     }
 
-    fn info(&self, args: Arguments<'_>) {
-        info!(target: &self.log_target, "{}", std::fmt::format(args));
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.inner.lock().unwrap().max_logs(max_logs);
+        self
     }
-}
-
-impl Display for ProgressLogger {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        if let Some(start_time) = self.start_time {
-            let count_fmtd = if self.time_unit.is_none() {
-                self.count.to_formatted_string(&Locale::en)
-            } else {
-                self.count.to_string()
-            };
-
-            if let Some(stop_time) = self.stop_time {
-                let elapsed = stop_time - start_time;
-                let seconds_per_item = elapsed.as_secs_f64() / self.count as f64;
-
-                f.write_fmt(format_args!(
-                    "Elapsed: {}",
-                    TimeUnit::pretty_print(elapsed.as_millis())
-                ))?;
-
-                if self.count != 0 {
-                    f.write_fmt(format_args!(
-                        " [{} {}, ",
-                        count_fmtd,
-                        pluralize(&self.item_name, self.count as isize, false)
-                    ))?;
-                    self.fmt_timing_speed(f, seconds_per_item)?;
-                    f.write_fmt(format_args!("]"))?
-                }
-            } else {
-                let now = Instant::now();
-
-                let elapsed = now - start_time;
 
-                f.write_fmt(format_args!(
-                    "{} {}, {}, ",
-                    count_fmtd,
-                    pluralize(&self.item_name, self.count as isize, false),
-                    TimeUnit::pretty_print(elapsed.as_millis()),
-                ))?;
-
-                let seconds_per_item = elapsed.as_secs_f64() / self.count as f64;
-                self.fmt_timing_speed(f, seconds_per_item)?;
-
-                if let Some(expected_updates) = self.expected_updates {
-                    let millis_to_end: u128 = (expected_updates.saturating_sub(self.count) as u128
-                        * elapsed.as_millis())
-                        / (self.count as u128 + 1);
-                    f.write_fmt(format_args!(
-                        "; {:.2}% done, {} to end",
-                        100.0 * self.count as f64 / expected_updates as f64,
-                        TimeUnit::pretty_print(millis_to_end)
-                    ))?;
-                }
-
-                if self.local_speed && self.stop_time.is_none() {
-                    f.write_fmt(format_args!(" ["))?;
-
-                    let elapsed = now - self.last_log_time;
-                    let seconds_per_item =
-                        elapsed.as_secs_f64() / (self.count - self.last_count) as f64;
-                    self.fmt_timing_speed(f, seconds_per_item)?;
-
-                    f.write_fmt(format_args!("]"))?;
-                }
-            }
-
-            // It would be ideal to refresh self.system here, but this operation
-            // would require an &mut self reference.
-            if let Some(system) = &self.system {
-                f.write_fmt(format_args!(
-                    "; res/vir/avail/free/total mem {}/{}/{}B/{}B/{}B",
-                    system
-                        .process(self.pid)
-                        .map(|process| humanize(process.memory() as _) + "B")
-                        .unwrap_or("N/A".to_string()),
-                    system
-                        .process(self.pid)
-                        .map(|process| humanize(process.virtual_memory() as _) + "B")
-                        .unwrap_or("N/A".to_string()),
-                    humanize(system.available_memory() as _),
-                    humanize(system.free_memory() as _),
-                    humanize(system.total_memory() as _)
-                ))?;
-            }
-
-            Ok(())
-        } else {
-            write!(f, "ProgressLogger not started")
-        }
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.inner.lock().unwrap().min_duration(min_duration);
+        self
     }
-}
 
-/// Clone the logger, returning a logger with the same setup but with all
-/// the counters reset.
-impl Clone for ProgressLogger {
-    #[allow(clippy::manual_map)]
-    fn clone(&self) -> Self {
-        Self {
-            item_name: self.item_name.clone(),
-            log_interval: self.log_interval,
-            time_unit: self.time_unit,
-            local_speed: self.local_speed,
-            system: match self.system {
-                Some(_) => Some(System::new_with_specifics(RefreshKind::new().with_memory())),
-                None => None,
-            },
-            ..ProgressLogger::default()
-        }
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.inner.lock().unwrap().target_speed(target_speed);
+        self
     }
-}
-
-/// A concurrent wrapper for a [`ProgressLog`] implementation.
-///
-/// This struct wraps a [`ProgressLog`] in such as way that multiple thread can
-/// write to it. Writes are synchronized using a mutex, but they are also
-/// buffered using a given threshold, so the mutex is not accessed too often.
-///
-/// Once a [`ConcurrentWrapper`] is created, one can
-/// [clone](#impl-Clone-for-ConcurrentWrapper<P>) it to create any number of
-/// copies using the same underlying logger.
-///
-/// The methods [`update`](ProgressLog::update) and
-/// [`update_with_count`](ProgressLog::update_with_count) buffer the increment
-/// and add it to the underlying logger only when the buffer reaches a
-/// threshold; this prevents locking the underlying logger too often. The
-/// threshold is set at creation using the methods
-/// [`with_threshold`](Self::with_threshold) and
-/// [`wrap_with_threshold`](Self::wrap_with_threshold), or by calling the method
-/// [`threshold`](Self::threshold).
-///
-/// The method [`light_update`](ProgressLog::light_update), as in the case of
-/// [`ProgressLogger`], further delays updates using an even faster check.
-///
-/// You can [create a duplicate](Self::dup) of a concurrent wrapper, which will
-/// use a cloned inner logger.
-///
-/// # Examples
-///
-/// ```rust
-/// use dsi_progress_logger::prelude::*;
-/// use std::thread;
-///
-/// let mut cpl = concurrent_progress_logger![item_name = "pumpkin"];
-/// cpl.start("Smashing pumpkins (using many threads)...");
-///
-/// std::thread::scope(|s| {
-///     for i in 0..100 {
-///         let mut pl = cpl.clone();
-///         s.spawn(move || {
-///             for _ in 0..100000 {
-///                 pl.update();
-///             }
-///         });
-///     }
-/// });
-///
-/// cpl.done();
-/// ```
-pub struct ConcurrentWrapper<P: ProgressLog = ProgressLogger> {
-    /// Underlying logger
-    inner: Arc<Mutex<P>>,
-    /// The number of items processed by the current thread.
-    local_count: u32,
-    /// The threshold for updating the underlying logger.
-    threshold: u32,
-}
 
-/// Macro to create a [`ConcurrentWrapper`] based on a
-/// [`ProgressLogger`], with default log target set to [`std::module_path!`],
-/// and key-value pairs instead of setters.
-///
-/// # Examples
-///
-/// ```rust
-/// use dsi_progress_logger::prelude::*;
-///
-/// let mut pl = concurrent_progress_logger![item_name="pumpkin", display_memory=true];
-/// ```
-#[macro_export]
-macro_rules! concurrent_progress_logger {
-    ($($method:ident = $arg:expr),* $(,)?) => {
-        {
-            let mut cpl = ::dsi_progress_logger::ConcurrentWrapper::default();
-            ::dsi_progress_logger::ProgressLog::log_target(&mut cpl, ::std::module_path!());
-            $(
-                ::dsi_progress_logger::ProgressLog::$method(&mut cpl, $arg);
-            )*
-            cpl
-        }
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .failure_rate_thresholds(warn_rate, error_rate);
+        self
     }
-}
 
-/// Create a new [`ConcurrentWrapper`] based on a default
-/// [`ProgressLogger`], with a threshold of
-/// [`DEFAULT_THRESHOLD`](Self::DEFAULT_THRESHOLD).
-impl Default for ConcurrentWrapper {
-    fn default() -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(ProgressLogger::default())),
-            local_count: 0,
-            threshold: Self::DEFAULT_THRESHOLD,
-        }
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.inner.lock().unwrap().eta_smoothing(eta_smoothing);
+        self
     }
-}
 
-impl ConcurrentWrapper {
-    /// Create a new [`ConcurrentWrapper`] based on a default
-    /// [`ProgressLogger`], using the [default
-    /// threshold](Self::DEFAULT_THRESHOLD).
-    pub fn new() -> Self {
-        Self::with_threshold(Self::DEFAULT_THRESHOLD)
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .eta_range_threshold(eta_range_threshold);
+        self
     }
 
-    /// Create a new [`ConcurrentWrapper`] wrapping a default
-    /// [`ProgressLogger`], using the given threshold.
-    pub fn with_threshold(threshold: u32) -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(ProgressLogger::default())),
-            local_count: 0,
-            threshold,
-        }
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.inner.lock().unwrap().speed_sparkline(speed_sparkline);
+        self
     }
-}
 
-impl<P: ProgressLog> ConcurrentWrapper<P> {
-    /// The default threshold for updating the underlying logger.
-    pub const DEFAULT_THRESHOLD: u32 = 1 << 15;
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .overshoot_policy(overshoot_policy);
+        self
+    }
 
-    /// Calls to [`light_update`](ProgressLog::light_update) will cause a call
-    /// to [`update_with_count`](ProgressLog::update_with_count) only if the
-    /// current local count is a multiple of this mask plus one.
-    ///
-    /// Note that this constant is significantly smaller than the one used in
-    /// [`ProgressLogger`], as updates will be further delayed by the threshold
-    /// mechanism.
-    pub const LIGHT_UPDATE_MASK: u32 = (1 << 10) - 1;
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .expected_duration(expected_duration);
+        self
+    }
 
-    /// Set the threshold for updating the underlying logger.
-    ///
-    /// Note concurrent loggers with the same underlying logger
-    /// have independent thresholds.
-    pub fn threshold(&mut self, threshold: u32) -> &mut Self {
-        self.threshold = threshold;
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.inner.lock().unwrap().time_budget(time_budget);
         self
     }
 
-    /// Wrap a given [`ProgressLog`] in a [`ConcurrentWrapper`]
-    /// using the [default threshold](Self::DEFAULT_THRESHOLD).
-    pub fn wrap(inner: P) -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(inner)),
-            local_count: 0,
-            threshold: Self::DEFAULT_THRESHOLD,
-        }
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        *self.state.time_budget_callback.lock().unwrap() = Some(Arc::new(callback));
+        let state = self.state.clone();
+        self.inner.lock().unwrap().on_time_budget_exceeded(move || {
+            state.time_budget_exceeded.store(true, Ordering::Relaxed)
+        });
+        self
     }
 
-    /// Wrap a given [`ProgressLog`] in a [`ConcurrentWrapper`] using a
-    /// given threshold.
-    pub fn wrap_with_threshold(inner: P, threshold: u32) -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(inner)),
-            local_count: 0,
-            threshold,
-        }
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.inner.lock().unwrap().cancel_flag(cancel_flag.clone());
+        self.cancel_flag = cancel_flag;
+        self
     }
 
-    /// Force an update of the underlying logger with the current local count.
-    pub fn flush(&mut self) {
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
         self.inner
             .lock()
             .unwrap()
-            .update_with_count(self.local_count as _);
-        self.local_count = 0;
+            .deferred_logging(deferred_logging);
+        self
     }
-}
-impl<P: ProgressLog + Clone> ConcurrentWrapper<P> {
-    /// Clone the concurrent wrapper, obtaning a new one with the same
-    /// threshold, with a local count of zero, and with an inner [`ProgressLog`]
-    /// that is a clone of the original one.
-    ///
-    /// Note that the this method has the same sematics of [`ProgressLogser`'s
-    /// `Clone` implementation](ProgressLogger#impl-Clone-for-ProgressLogger),
-    /// but it is much more ergonomic here to have [cloning to generate copies
-    /// with the same underlying logger](#impl-Clone-for-ConcurrentWrapper<P>).
-    pub fn dup(&self) -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(self.inner.lock().unwrap().clone())),
-            local_count: 0,
-            threshold: self.threshold,
-        }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.inner.lock().unwrap().log_on_drop(log_on_drop);
+        self
     }
-}
 
-impl<P: ProgressLog> ProgressLog for ConcurrentWrapper<P> {
-    fn log(&mut self, now: Instant) {
-        self.inner.lock().unwrap().log(now);
-        self.local_count = 0;
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.inner.lock().unwrap().log_span_tree(log_span_tree);
+        self
     }
 
-    fn log_if(&mut self) {
-        self.inner.lock().unwrap().log_if();
-        self.local_count = 0;
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.lock().unwrap().progress_file(path);
+        self
     }
 
-    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
-        self.inner.lock().unwrap().display_memory(display_memory);
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.lock().unwrap().report_file(path);
         self
     }
 
-    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
-        self.inner.lock().unwrap().item_name(item_name);
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.lock().unwrap().trace_file(path);
         self
     }
 
-    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
-        self.inner.lock().unwrap().log_interval(log_interval);
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.inner.lock().unwrap().ci_format(ci_format);
         self
     }
 
-    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.inner.lock().unwrap().log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.inner.lock().unwrap().log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.inner.lock().unwrap().elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
         self.inner
             .lock()
             .unwrap()
-            .expected_updates(expected_updates);
+            .display_timestamp(display_timestamp);
         self
     }
 
-    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
-        self.inner.lock().unwrap().time_unit(time_unit);
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .display_ratio(numerator, denominator);
         self
     }
 
-    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
-        self.inner.lock().unwrap().local_speed(local_speed);
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.inner.lock().unwrap().fixed_width(fixed_width);
         self
     }
 
-    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
-        self.inner.lock().unwrap().log_target(target);
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.inner.lock().unwrap().display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.inner.lock().unwrap().timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.inner
+            .lock()
+            .unwrap()
+            .display_fraction(display_fraction);
         self
     }
 
     fn start(&mut self, msg: impl AsRef<str>) {
-        self.inner.lock().unwrap().start(msg);
+        self.apply(|pl| pl.start(msg));
         self.local_count = 0;
+        self.local_failed = 0;
     }
 
-    #[inline]
-    fn update(&mut self) {
-        self.update_with_count(1)
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.apply(|pl| pl.start_with_expected(msg, expected_updates));
+        self.local_count = 0;
+        self.local_failed = 0;
     }
 
-    #[inline]
-    fn update_with_count(&mut self, count: usize) {
-        match (self.local_count as usize).checked_add(count) {
-            None => {
-                // Sum overflows, update in two steps
-                let mut pl = self.inner.lock().unwrap();
-                pl.update_with_count(self.local_count as _);
-                pl.update_with_count(count);
-                self.local_count = 0;
-            }
-            Some(total_count) => {
-                if total_count >= self.threshold as usize {
-                    // Threshold reached, time to flush to the inner ProgressLog
-                    self.inner.lock().unwrap().update_with_count(total_count);
-                    self.local_count = 0;
-                } else {
-                    // total_count is lower than self.threshold, which is a u32;
-                    // so total_count fits in u32.
-                    self.local_count = total_count as u32;
-                }
-            }
-        }
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.apply(|pl| pl.start_resumed(msg, already_done, expected_updates));
+        self.local_count = 0;
+        self.local_failed = 0;
     }
 
-    #[inline]
-    fn light_update(&mut self) {
-        self.local_count += 1;
-        if (self.local_count & Self::LIGHT_UPDATE_MASK) == 0 {
-            self.inner
-                .lock()
-                .unwrap()
-                .update_with_count(self.local_count as _);
-            self.local_count = 0;
-        }
+    fn restart(&mut self) {
+        self.apply(|pl| pl.restart());
+        self.local_count = 0;
+        self.local_failed = 0;
+    }
+
+    fn update_failed(&mut self) {
+        // Failed items are buffered with the other items, so that the failure
+        // rate of the underlying logger is not overstated
+        self.local_failed += 1;
+        self.update();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        self.inner.lock().unwrap().update_metric(name, value);
     }
 
     fn update_and_display(&mut self) {
         self.local_count += 1;
-        self.inner
-            .lock()
-            .unwrap()
-            .update_with_count(self.local_count as _);
-        self.local_count = 0;
+        self.flush();
     }
 
     fn stop(&mut self) {
-        self.inner.lock().unwrap().stop();
+        self.apply(|pl| pl.stop());
         self.local_count = 0;
+        self.local_failed = 0;
     }
 
     fn done(&mut self) {
-        self.inner.lock().unwrap().done();
+        self.apply(|pl| pl.done());
         self.local_count = 0;
+        self.local_failed = 0;
     }
 
     fn done_with_count(&mut self, count: usize) {
-        self.inner.lock().unwrap().done_with_count(count);
+        self.apply(|pl| pl.done_with_count(count));
         self.local_count = 0;
+        self.local_failed = 0;
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.inner.lock().unwrap().time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        match &self.cancel_flag {
+            Some(cancel_flag) => cancel_flag.load(Ordering::Relaxed),
+            // The flag might have been set before wrapping
+            None => self.inner.lock().unwrap().is_cancelled(),
+        }
+    }
+
+    fn is_started(&self) -> bool {
+        self.inner.lock().unwrap().is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.lock().unwrap().is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().eta()
+    }
+
+    fn count(&self) -> usize {
+        self.state.count.load(Ordering::Relaxed)
     }
 
     fn elapsed(&self) -> Option<Duration> {
         self.inner.lock().unwrap().elapsed()
     }
 
+    fn get_log_interval(&self) -> Duration {
+        self.inner.lock().unwrap().get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.inner.lock().unwrap().get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.inner.lock().unwrap().get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.inner.lock().unwrap().get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.inner.lock().unwrap().get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.inner.lock().unwrap().span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.inner.lock().unwrap().add_span(span);
+    }
+
     fn refresh(&mut self) {
         self.inner.lock().unwrap().refresh();
     }
@@ -1196,12 +7145,15 @@ impl<P: ProgressLog> ProgressLog for ConcurrentWrapper<P> {
 ///
 /// The resulting logger can be passed to other threads to perform
 /// concurrent progress logging.
-impl<P: ProgressLog + Clone> Clone for ConcurrentWrapper<P> {
+impl<P: ProgressLog> Clone for ConcurrentWrapper<P> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
             local_count: 0,
+            local_failed: 0,
             threshold: self.threshold,
+            state: self.state.clone(),
+            cancel_flag: self.cancel_flag.clone(),
         }
     }
 }
@@ -1214,13 +7166,15 @@ impl<P: ProgressLog> Drop for ConcurrentWrapper<P> {
     }
 }
 
-impl Display for ConcurrentWrapper {
+impl<P: ProgressLog + Display> Display for ConcurrentWrapper<P> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         self.inner.lock().unwrap().fmt(f)
     }
 }
 
 /// Convenience macro specifying that no logging should be performed.
+///
+/// See also [`NoLogging`], a zero-sized logger that does nothing.
 #[macro_export]
 macro_rules! no_logging {
     () => {
@@ -1229,8 +7183,336 @@ macro_rules! no_logging {
 }
 
 pub mod prelude {
+    #[cfg(feature = "macros")]
+    pub use super::logged_progress;
+    #[cfg(all(feature = "journald", unix))]
+    pub use super::JournaldLogger;
+    #[cfg(feature = "ratatui")]
+    pub use super::ProgressWidget;
     pub use super::{
-        concurrent_progress_logger, no_logging, progress_logger, ConcurrentWrapper, ProgressLog,
-        ProgressLogger,
+        concurrent_progress_logger, copy_file_with_progress, copy_with_progress, for_each_chunk,
+        for_each_chunk_mut, install_panic_hook, no_logging, progress, progress_logger,
+        AtomicWrapper, ByteUnits, CiFormat, ConcurrentWrapper, CountReducer, DisplayFields,
+        ElapsedFormat, EpochLogger, EtaSmoothing, NoLogging, OvershootPolicy, ProgressHandle,
+        ProgressLog, ProgressLogger, ProgressReader, ProgressReceiver, ProgressSnapshot,
+        ProgressUpdate, ReducingLogger, RollUp, SharedWrapper, Span,
     };
+    #[cfg(feature = "rayon")]
+    pub use super::{par_for_each_chunk, par_for_each_chunk_mut};
+    #[cfg(feature = "test-utils")]
+    pub use super::{MockClock, RenderValues};
+    #[cfg(all(feature = "shared-memory", unix))]
+    pub use super::{SharedCounter, SharedMemoryLogger};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::Fixture;
+
+    #[test]
+    fn test_time_budget_callback_outside_lock() {
+        let mut f = Fixture::new(ConcurrentWrapper::with_threshold(1));
+        f.pl.time_budget(Some(Duration::from_secs(5)));
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let flag = exceeded.clone();
+        let wrapper = f.pl.clone();
+        // The callback uses the wrapper, which would deadlock under the lock
+        f.pl.on_time_budget_exceeded(move || {
+            flag.store(wrapper.time_budget_exceeded(), Ordering::Relaxed)
+        });
+        f.pl.start("Testing...");
+        f.run(10, Duration::from_secs(1));
+        assert!(exceeded.load(Ordering::Relaxed));
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_log_interval_jitter() {
+        for jitter in [0.5, f64::NAN] {
+            let mut f = Fixture::new(ProgressLogger::default());
+            f.pl.log_interval_jitter(jitter);
+            f.pl.start("Testing...");
+            f.run(10_000, Duration::from_millis(10));
+            let ticks = f.clock.ticks();
+            let gaps: Vec<Duration> = ticks.windows(2).map(|w| w[1].0 - w[0].0).collect();
+            if jitter.is_nan() {
+                // A NaN jitter is treated as zero
+                assert!(gaps.iter().all(|&gap| gap == Duration::from_secs(1)));
+            } else {
+                assert!(gaps
+                    .iter()
+                    .all(|&gap| gap >= Duration::from_millis(500)
+                        && gap <= Duration::from_millis(1510)));
+                assert!(gaps.iter().any(|&gap| gap < Duration::from_millis(900)));
+                assert!(gaps.iter().any(|&gap| gap > Duration::from_millis(1100)));
+            }
+            f.pl.done();
+        }
+    }
+
+    #[test]
+    fn test_wrapper_buffers_failures() {
+        let mut cpl = ConcurrentWrapper::with_threshold(1000);
+        cpl.start("Testing...");
+        for i in 0..100 {
+            if i % 10 == 0 {
+                cpl.update_failed();
+            } else {
+                cpl.update();
+            }
+        }
+        // Failures are not passed before the other items
+        assert_eq!(cpl.count(), 0);
+        cpl.flush();
+        assert_eq!(cpl.count(), 100);
+        let pl = cpl.into_inner().ok().unwrap();
+        assert_eq!((pl.count, pl.failed), (100, 10));
+    }
+
+    #[test]
+    fn test_wrapper_failure_rate() {
+        let mut f = Fixture::new(ConcurrentWrapper::with_threshold(10));
+        f.pl.failure_rate_thresholds(Some(0.5), None);
+        f.pl.start("Testing...");
+        for i in 0..20 {
+            f.clock.advance(Duration::from_millis(100));
+            if i % 10 == 0 {
+                f.pl.update_failed();
+            } else {
+                f.pl.update();
+            }
+        }
+        // The logs happen after passing the other items, rather than after
+        // passing a single failed item, which would be escalated
+        assert_eq!(
+            f.clock.ticks(),
+            [(Duration::from_secs(1), 9), (Duration::from_secs(2), 19)]
+        );
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_wrapper_flushing() {
+        let mut f = Fixture::new(ConcurrentWrapper::with_threshold(10));
+        f.pl.start("Testing...");
+        let mut copy = f.pl.clone();
+        for _ in 0..25 {
+            f.clock.advance(Duration::from_millis(100));
+            copy.update();
+        }
+        // Updates are passed to the underlying logger when the threshold is
+        // reached
+        assert_eq!(f.pl.count(), 20);
+        assert_eq!(
+            f.clock.ticks(),
+            [(Duration::from_secs(1), 10), (Duration::from_secs(2), 20)]
+        );
+        // Dropping a copy flushes its updates
+        drop(copy);
+        assert_eq!(f.pl.count(), 25);
+        // Light updates are passed when the mask is reached
+        let mask = ConcurrentWrapper::<ProgressLogger>::LIGHT_UPDATE_MASK as usize;
+        for _ in 0..mask {
+            f.pl.light_update();
+        }
+        assert_eq!(f.pl.count(), 25);
+        f.pl.light_update();
+        assert_eq!(f.pl.count(), 26 + mask);
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_failure_escalation() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.failure_rate_thresholds(Some(0.1), Some(0.5));
+        f.pl.start("Testing...");
+        let mut levels = vec![];
+        for failed in [0, 2, 10, 10] {
+            for i in 0..10 {
+                f.clock.advance(Duration::from_millis(100));
+                if i < failed {
+                    f.pl.update_failed();
+                } else {
+                    f.pl.update();
+                }
+            }
+            levels.push(f.pl.failure_level());
+        }
+        assert_eq!(
+            levels,
+            [Level::Info, Level::Warn, Level::Warn, Level::Error]
+        );
+        // Without thresholds, failures are never escalated
+        f.pl.failure_rate_thresholds(None, None);
+        assert_eq!(f.pl.failure_level(), Level::Info);
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_eta_smoothing() {
+        // The raw estimated completion time is 20s for 10s, then it grows;
+        // hysteresis revises it only once, at 13s
+        for (eta_smoothing, eta) in [
+            (EtaSmoothing::Raw, 45.0 * 15.0 / 55.0),
+            (EtaSmoothing::Monotone, 5.0),
+            (EtaSmoothing::Hysteresis(0.5), 47.0 * 13.0 / 53.0 - 2.0),
+        ] {
+            let mut f = Fixture::new(ProgressLogger::default());
+            f.pl.expected_updates(Some(100))
+                .eta_smoothing(eta_smoothing);
+            f.pl.start("Testing...");
+            for _ in 0..10 {
+                f.clock.advance(Duration::from_secs(1));
+                f.pl.update_with_count(5);
+            }
+            f.run(5, Duration::from_secs(1));
+            let actual = f.pl.eta().unwrap().as_secs_f64();
+            assert!(
+                (actual - eta).abs() < 1E-6,
+                "{eta_smoothing:?}: {actual} != {eta}"
+            );
+            f.pl.done();
+        }
+    }
+
+    #[test]
+    fn test_hysteresis_invalid_tolerance() {
+        for tolerance in [f64::NAN, -1.0, f64::INFINITY] {
+            let mut f = Fixture::new(ProgressLogger::default());
+            f.pl.expected_updates(Some(100))
+                .eta_smoothing(EtaSmoothing::Hysteresis(tolerance));
+            f.pl.start("Testing...");
+            f.run(10, Duration::from_secs(1));
+            assert!(f.pl.eta().is_some());
+            f.pl.done();
+        }
+    }
+
+    #[test]
+    fn test_update_speed_drop() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.log_interval(Duration::from_secs(10));
+        f.pl.start("Testing...");
+        // 100,000 items/s for 15s, then 1,000 items/s
+        for _ in 0..15_000 {
+            f.clock.advance(Duration::from_millis(1));
+            f.pl.update_with_count(100);
+        }
+        for _ in 0..150 {
+            f.clock.advance(Duration::from_millis(100));
+            f.pl.update_with_count(100);
+        }
+        // The log due at 20s is delayed by at most about 100 times
+        // MAX_CHECK_INTERVAL
+        let ticks = f.clock.ticks();
+        assert!(
+            ticks
+                .iter()
+                .any(|&(elapsed, _)| elapsed >= Duration::from_secs(20)
+                    && elapsed <= Duration::from_millis(21_500)),
+            "{:?}",
+            ticks
+        );
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_deferred_logging_state() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.expected_updates(Some(5))
+            .overshoot_policy(OvershootPolicy::DebugAssert)
+            .deferred_logging(true);
+        f.pl.start("Testing...");
+        f.run(3, Duration::from_secs(1));
+        // The speed statistics are computed by the calling thread
+        assert!(f.pl.interval_seconds_per_item.is_some());
+        // The overshoot policy is applied by the calling thread
+        let overshoot = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            f.run(3, Duration::from_secs(1));
+        }));
+        assert_eq!(overshoot.is_err(), cfg!(debug_assertions));
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_deferred_logging_callback() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.time_budget(Some(Duration::from_secs(5)))
+            .deferred_logging(true);
+        let thread = Arc::new(std::sync::Mutex::new(None));
+        let callback_thread = thread.clone();
+        f.pl.on_time_budget_exceeded(move || {
+            *callback_thread.lock().unwrap() = Some(std::thread::current().id())
+        });
+        f.pl.start("Testing...");
+        f.run(10, Duration::from_secs(1));
+        // The time budget is checked by the calling thread, and the worker
+        // thread does not record ticks on the mock clock
+        assert_eq!(*thread.lock().unwrap(), Some(std::thread::current().id()));
+        assert!(f.clock.ticks().is_empty());
+        assert_eq!(f.pl.count(), 10);
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_skip_idle_checks() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.skip_idle(true)
+            .time_budget(Some(Duration::from_secs(5)));
+        f.pl.start("Testing...");
+        f.run(1, Duration::from_secs(1));
+        for _ in 0..10 {
+            f.clock.advance(Duration::from_secs(1));
+            f.pl.log(clock::now());
+        }
+        // Only the first progress line, but the time budget has been checked
+        assert_eq!(f.clock.ticks(), [(Duration::from_secs(1), 1)]);
+        assert!(f.exceeded());
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_max_logs_checks() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.max_logs(Some(2))
+            .expected_duration(Some(Duration::from_secs(10)))
+            .time_budget(Some(Duration::from_secs(3)));
+        f.pl.start("Testing...");
+        f.run(4, Duration::from_secs(1));
+        // The time budget has been checked at the log interval
+        assert!(f.exceeded());
+        f.run(6, Duration::from_secs(1));
+        // The second line is due after half of the remaining nine seconds
+        assert_eq!(
+            f.clock.ticks(),
+            [(Duration::from_secs(1), 1), (Duration::from_secs(6), 6)]
+        );
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_max_logs_forced_line() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.max_logs(Some(0));
+        f.pl.start("Testing...");
+        f.run(1, Duration::from_secs(1));
+        assert!(f.clock.ticks().is_empty());
+        f.pl.update_and_display();
+        assert_eq!(f.clock.ticks(), [(Duration::from_secs(1), 2)]);
+        f.pl.done();
+    }
+
+    #[test]
+    fn test_min_duration_checks() {
+        let mut f = Fixture::new(ProgressLogger::default());
+        f.pl.min_duration(Some(Duration::from_secs(100)))
+            .time_budget(Some(Duration::from_secs(5)));
+        f.pl.start("Testing...");
+        f.run(10, Duration::from_secs(1));
+        // No output, but the time budget has been checked
+        assert!(f.clock.ticks().is_empty());
+        assert!(f.exceeded());
+        f.pl.done();
+    }
 }
@@ -0,0 +1,224 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{Instant, ProgressLog, ProgressUpdate, Span, TimeUnit};
+use std::fmt::Arguments;
+use std::time::Duration;
+
+/// A zero-sized [`ProgressLog`] that does nothing.
+///
+/// All methods of this logger are empty and inlined, so they are optimized
+/// away entirely. It is a typed alternative to `Option::<P>::None` and to the
+/// [`no_logging!`](crate::no_logging) macro: generic code instantiated with
+/// this type is guaranteed to have no logging overhead, and signatures can
+/// state explicitly that no logging will happen. Since the type is [`Copy`],
+/// it can also be freely passed to multiple threads.
+///
+/// Queries return `false` or `None`, as for a `None` logger.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// fn sum(v: &[u64], pl: &mut impl ProgressLog) -> u64 {
+///     pl.start("Summing...");
+///     let mut sum = 0;
+///     for x in v {
+///         sum += x;
+///         pl.light_update();
+///     }
+///     pl.done();
+///     sum
+/// }
+///
+/// assert_eq!(sum(&[1, 2, 3], &mut NoLogging), 6);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoLogging;
+
+impl ProgressUpdate for NoLogging {
+    #[inline(always)]
+    fn update(&mut self) {}
+
+    #[inline(always)]
+    fn update_with_count(&mut self, _count: usize) {}
+
+    #[inline(always)]
+    fn update_with_time(&mut self, _now: Instant) {}
+
+    #[inline(always)]
+    fn update_with_count_and_time(&mut self, _count: usize, _now: Instant) {}
+
+    #[inline(always)]
+    fn update_from_counts(&mut self, _counts: impl IntoIterator<Item = usize>) {}
+
+    #[inline(always)]
+    fn light_update(&mut self) {}
+}
+
+impl ProgressLog for NoLogging {
+    #[inline(always)]
+    fn log(&mut self, _now: Instant) {}
+
+    #[inline(always)]
+    fn log_if(&mut self) {}
+
+    #[inline(always)]
+    fn display_memory(&mut self, _display_memory: bool) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn item_name(&mut self, _item_name: impl AsRef<str>) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn log_interval(&mut self, _log_interval: Duration) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn expected_updates(&mut self, _expected_updates: Option<usize>) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn time_unit(&mut self, _time_unit: Option<TimeUnit>) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn local_speed(&mut self, _local_speed: bool) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn log_target(&mut self, _target: impl AsRef<str>) -> &mut Self {
+        self
+    }
+
+    #[inline(always)]
+    fn start(&mut self, _msg: impl AsRef<str>) {}
+
+    #[inline(always)]
+    fn start_with_expected(&mut self, _msg: impl AsRef<str>, _expected_updates: usize) {}
+
+    #[inline(always)]
+    fn start_resumed(
+        &mut self,
+        _msg: impl AsRef<str>,
+        _already_done: usize,
+        _expected_updates: usize,
+    ) {
+    }
+
+    #[inline(always)]
+    fn restart(&mut self) {}
+
+    #[inline(always)]
+    fn update_failed(&mut self) {}
+
+    #[inline(always)]
+    fn update_metric(&mut self, _name: impl AsRef<str>, _value: f64) {}
+
+    #[inline(always)]
+    fn update_and_display(&mut self) {}
+
+    #[inline(always)]
+    fn stop(&mut self) {}
+
+    #[inline(always)]
+    fn done(&mut self) {}
+
+    #[inline(always)]
+    fn done_with_count(&mut self, _count: usize) {}
+
+    #[inline(always)]
+    fn time_budget_exceeded(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_started(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_stopped(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn items_per_second(&self) -> Option<f64> {
+        None
+    }
+
+    #[inline(always)]
+    fn local_items_per_second(&self) -> Option<f64> {
+        None
+    }
+
+    #[inline(always)]
+    fn eta(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline(always)]
+    fn count(&self) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    fn elapsed(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline(always)]
+    fn get_log_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    #[inline(always)]
+    fn get_item_name(&self) -> String {
+        String::new()
+    }
+
+    #[inline(always)]
+    fn get_expected_updates(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline(always)]
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    #[inline(always)]
+    fn get_log_target(&self) -> String {
+        String::new()
+    }
+
+    #[inline(always)]
+    fn span(&self) -> Option<Span> {
+        None
+    }
+
+    #[inline(always)]
+    fn add_span(&mut self, _span: Span) {}
+
+    #[inline(always)]
+    fn refresh(&mut self) {}
+
+    #[inline(always)]
+    fn info(&self, _args: Arguments<'_>) {}
+}
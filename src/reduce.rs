@@ -0,0 +1,586 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{
+    clock, CiFormat, DisplayFields, ElapsedFormat, EtaSmoothing, Instant, OvershootPolicy,
+    ProgressLog, ProgressLogger, ProgressUpdate, Span, TimeUnit,
+};
+use std::fmt::{Arguments, Display, Formatter, Result};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A hook combining the counts of multiple participants (e.g., the ranks of
+/// an MPI job) of a distributed activity.
+///
+/// Implementations wrap a collective operation of the underlying
+/// communication layer (e.g., a sum reduction to rank 0).
+pub trait CountReducer {
+    /// Combine the local counts of all participants.
+    ///
+    /// This is a collective operation: it is called on all participants at
+    /// the same logical point. It must return the sum of the local counts on
+    /// the [root](CountReducer::is_root) participant, and it can return
+    /// anything on the other participants.
+    fn reduce(&mut self, local_count: usize) -> usize;
+
+    /// Return whether this participant is the one logging the global
+    /// progress (e.g., rank 0).
+    fn is_root(&self) -> bool;
+}
+
+/// A [`ProgressLog`] for distributed activities, in which only the root
+/// participant logs the global progress.
+///
+/// Updates just increase a local count. When [`reduce`](Self::reduce) is
+/// called, the local counts of all participants are combined using a
+/// [`CountReducer`], and on the [root](CountReducer::is_root) participant the
+/// underlying logger is updated with the global count, and logs if it is time
+/// to do so. On the other participants the underlying logger is silent: all
+/// methods starting, logging, or stopping it are no-ops.
+///
+/// Since reductions are collective operations, [`reduce`](Self::reduce) must
+/// be called by all participants at the same logical points (e.g., at the end
+/// of each batch), and the same is true of [`stop`](ProgressLog::stop),
+/// [`done`](ProgressLog::done), and
+/// [`done_with_count`](ProgressLog::done_with_count), which perform a final
+/// reduction.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// // A trivial reducer for a single participant; an MPI implementation would
+/// // perform, for example, a sum reduction to rank 0
+/// struct SingleRank;
+///
+/// impl CountReducer for SingleRank {
+///     fn reduce(&mut self, local_count: usize) -> usize {
+///         local_count
+///     }
+///
+///     fn is_root(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// let mut pl = ReducingLogger::new(SingleRank);
+/// pl.item_name("batch");
+/// pl.start("Processing batches...");
+/// for _ in 0..100 {
+///     pl.update();
+///     pl.reduce();
+/// }
+/// pl.done();
+/// ```
+#[derive(Debug)]
+pub struct ReducingLogger<R: CountReducer, P: ProgressLog = ProgressLogger> {
+    /// Underlying logger, used only on the root participant.
+    inner: P,
+    /// The reducer.
+    reducer: R,
+    /// The local count.
+    local_count: usize,
+    /// The global count already passed to the underlying logger.
+    reduced: usize,
+}
+
+impl<R: CountReducer> ReducingLogger<R> {
+    /// Create a new [`ReducingLogger`] wrapping a default [`ProgressLogger`].
+    #[track_caller]
+    pub fn new(reducer: R) -> Self {
+        Self::wrap(ProgressLogger::default(), reducer)
+    }
+}
+
+impl<R: CountReducer, P: ProgressLog> ReducingLogger<R, P> {
+    /// Wrap a given [`ProgressLog`] in a [`ReducingLogger`].
+    pub fn wrap(inner: P, reducer: R) -> Self {
+        Self {
+            inner,
+            reducer,
+            local_count: 0,
+            reduced: 0,
+        }
+    }
+
+    /// Combine the local counts of all participants and, on the root
+    /// participant, update the underlying logger with the global count.
+    ///
+    /// This is a collective operation that must be called by all participants.
+    pub fn reduce(&mut self) {
+        let count = self.reducer.reduce(self.local_count);
+        if self.reducer.is_root() {
+            self.inner
+                .update_with_count(count.saturating_sub(self.reduced));
+            self.reduced = self.reduced.max(count);
+        }
+    }
+
+    /// Return the local count.
+    pub fn local_count(&self) -> usize {
+        self.local_count
+    }
+
+    /// Return the underlying logger and the reducer.
+    pub fn into_inner(self) -> (P, R) {
+        (self.inner, self.reducer)
+    }
+}
+
+impl<R: CountReducer, P: ProgressLog> ProgressUpdate for ReducingLogger<R, P> {
+    #[inline]
+    fn update(&mut self) {
+        self.local_count += 1;
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        self.local_count += count;
+    }
+
+    #[inline]
+    fn update_with_time(&mut self, _now: Instant) {
+        self.local_count += 1;
+    }
+
+    #[inline]
+    fn update_with_count_and_time(&mut self, count: usize, _now: Instant) {
+        self.local_count += count;
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.local_count += counts.into_iter().sum::<usize>();
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        self.local_count += 1;
+    }
+}
+
+impl<R: CountReducer, P: ProgressLog> ProgressLog for ReducingLogger<R, P> {
+    fn log(&mut self, now: Instant) {
+        if self.reducer.is_root() {
+            self.inner.log(now);
+        }
+    }
+
+    fn log_if(&mut self) {
+        if self.reducer.is_root() {
+            self.inner.log_if();
+        }
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.inner.display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.inner.display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.inner.display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.inner.monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.inner.memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.inner.item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.inner.item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.inner.log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.inner.log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.inner.expected_updates(expected_updates);
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.inner.time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.inner.local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.inner.local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.inner.min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.inner.skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.inner.max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.inner.min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.inner.target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.inner.failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.inner.eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.inner.eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.inner.speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.inner.overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.inner.expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.inner.time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.inner.on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.inner.cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.inner.deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.inner.log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.inner.log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.inner.ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.inner.log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.inner.log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.inner.elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.inner.display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner.display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.inner.fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.inner.display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.inner.timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner.remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.inner.display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.inner.display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.local_count = 0;
+        self.reduced = 0;
+        if self.reducer.is_root() {
+            self.inner.start(msg);
+        }
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.local_count = 0;
+        self.reduced = 0;
+        if self.reducer.is_root() {
+            self.inner.start_with_expected(msg, expected_updates);
+        }
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.local_count = 0;
+        self.reduced = 0;
+        if self.reducer.is_root() {
+            self.inner
+                .start_resumed(msg, already_done, expected_updates);
+        }
+    }
+
+    fn restart(&mut self) {
+        self.local_count = 0;
+        self.reduced = 0;
+        if self.reducer.is_root() {
+            self.inner.restart();
+        }
+    }
+
+    fn update_failed(&mut self) {
+        // Failures are not reduced
+        self.local_count += 1;
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        if self.reducer.is_root() {
+            self.inner.update_metric(name, value);
+        }
+    }
+
+    fn update_and_display(&mut self) {
+        self.local_count += 1;
+        if self.reducer.is_root() {
+            self.inner.log(clock::now());
+        }
+    }
+
+    fn stop(&mut self) {
+        self.reduce();
+        if self.reducer.is_root() {
+            self.inner.stop();
+        }
+    }
+
+    fn done(&mut self) {
+        self.reduce();
+        if self.reducer.is_root() {
+            self.inner.done();
+        }
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        self.local_count = count;
+        self.reduce();
+        if self.reducer.is_root() {
+            self.inner.done();
+        }
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.inner.time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        self.inner.is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.inner.items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.inner.local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.inner.eta()
+    }
+
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.inner.elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.inner.get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.inner.get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.inner.get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.inner.get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.inner.get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.inner.span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.inner.add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        if self.reducer.is_root() {
+            self.inner.refresh();
+        }
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        if self.reducer.is_root() {
+            self.inner.info(args);
+        }
+    }
+}
+
+impl<R: CountReducer, P: ProgressLog + Display> Display for ReducingLogger<R, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.inner.fmt(f)
+    }
+}
@@ -0,0 +1,137 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! A global registry of running loggers, used to report their state when the
+//! process is interrupted or panics.
+
+use crate::{clock, Instant, TimeUnit};
+use log::warn;
+use num_format::{Locale, ToFormattedString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// The state of a running logger, as of its last log.
+pub(crate) struct Snapshot {
+    pub(crate) log_target: String,
+    pub(crate) item_name: String,
+    pub(crate) item_name_plural: String,
+    pub(crate) start_msg: String,
+    pub(crate) start_time: Instant,
+    pub(crate) count: usize,
+    pub(crate) expected_updates: Option<usize>,
+}
+
+/// Whether loggers should register themselves when started.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The snapshots of the running loggers.
+static REGISTRY: Mutex<Vec<Weak<Mutex<Snapshot>>>> = Mutex::new(Vec::new());
+
+/// Register a snapshot, if the registry is enabled.
+///
+/// The snapshot is unregistered when the returned [`Arc`] is dropped.
+pub(crate) fn register(snapshot: Snapshot) -> Option<Arc<Mutex<Snapshot>>> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let snapshot = Arc::new(Mutex::new(snapshot));
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    registry.retain(|snapshot| snapshot.strong_count() > 0);
+    registry.push(Arc::downgrade(&snapshot));
+    Some(snapshot)
+}
+
+/// Enable the registration of loggers.
+fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Log at the `warn` level the state of all running loggers, prefixed by
+/// `reason`.
+fn log_running(reason: &str) {
+    let now = clock::now();
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    for snapshot in registry.iter().filter_map(Weak::upgrade) {
+        // The snapshot might be locked by the thread we are reporting about
+        let Ok(snapshot) = snapshot.try_lock() else {
+            continue;
+        };
+        let percent = snapshot
+            .expected_updates
+            .map(|expected_updates| {
+                format!(
+                    " ({:.2}% done)",
+                    100.0 * snapshot.count as f64 / expected_updates as f64
+                )
+            })
+            .unwrap_or_default();
+        warn!(
+            target: &snapshot.log_target,
+            "{}{}: {} {} after {}{}",
+            reason,
+            if snapshot.start_msg.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", snapshot.start_msg)
+            },
+            snapshot.count.to_formatted_string(&Locale::en),
+            if snapshot.count == 1 {
+                &snapshot.item_name
+            } else {
+                &snapshot.item_name_plural
+            },
+            TimeUnit::pretty_print((now - snapshot.start_time).as_millis()),
+            percent
+        );
+    }
+}
+
+/// Install a handler for `SIGINT` (i.e., Ctrl-C) that logs the state of all
+/// running loggers and exits the process with status 130.
+///
+/// Only loggers started after the handler has been installed are reported,
+/// and their count is the one at their last log. This function is available
+/// only if the `ctrlc` feature is enabled.
+///
+/// # Errors
+///
+/// This function fails if a handler has already been installed (e.g., by
+/// another call to this function).
+#[cfg(feature = "ctrlc")]
+pub fn install_ctrlc_handler() -> Result<(), ctrlc::Error> {
+    enable();
+    ctrlc::set_handler(|| {
+        log_running("Interrupted");
+        std::process::exit(130);
+    })
+}
+
+/// Install a panic hook that logs the state of all running loggers and then
+/// calls the previous hook.
+///
+/// Knowing the count of a logger at the time of a panic can help locating the
+/// input that caused it. Only loggers started after the hook has been
+/// installed are reported, and their count is the one at their last log.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// install_panic_hook();
+/// let mut pl = progress_logger![item_name = "record"];
+/// pl.start("Parsing records...");
+/// // If a panic happens here, the number of records parsed so far is logged
+/// pl.done();
+/// ```
+pub fn install_panic_hook() {
+    enable();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log_running("Panicked");
+        previous(info);
+    }));
+}
@@ -0,0 +1,381 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Reports written at completion (see [`ProgressLog::report_file`]).
+
+use crate::{clock, Grouped, Pretty, ProgressLog, ProgressLogger, Span, Speed};
+use log::warn;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The width of the speed chart in HTML reports.
+const CHART_WIDTH: f64 = 600.0;
+/// The height of the speed chart in HTML reports.
+const CHART_HEIGHT: f64 = 200.0;
+
+/// A row of the table of logs of a report.
+struct Row {
+    elapsed: Duration,
+    count: usize,
+    /// The number of seconds per item since the previous row, if any item
+    /// was processed.
+    seconds_per_item: Option<f64>,
+}
+
+/// A row of the table of phases of a report.
+struct Phase {
+    /// The nesting depth of the phase.
+    depth: usize,
+    name: String,
+    /// The number of merged activities.
+    count: usize,
+    elapsed: Duration,
+    /// The percentage of the elapsed time of the whole activity, if nonzero.
+    percent: Option<f64>,
+}
+
+/// Escape the characters that are special in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape the characters that are special in Markdown table cells.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Append to `phases` the rows of the nested activities of `span`, depth
+/// first.
+fn collect_phases(phases: &mut Vec<Phase>, span: &Span, depth: usize, total: Duration) {
+    for child in &span.children {
+        phases.push(Phase {
+            depth,
+            name: child.name.clone(),
+            count: child.count,
+            elapsed: child.elapsed(),
+            percent: (!total.is_zero())
+                .then(|| 100.0 * child.elapsed().as_secs_f64() / total.as_secs_f64()),
+        });
+        collect_phases(phases, child, depth + 1, total);
+    }
+}
+
+impl ProgressLogger {
+    /// Write the report of the activity, if a report file is set.
+    pub(crate) fn write_report(&self) {
+        let Some(path) = &self.report_file else {
+            return;
+        };
+        let html = path
+            .extension()
+            .is_some_and(|extension| extension == "html" || extension == "htm");
+        let report = if html {
+            self.html_report()
+        } else {
+            self.markdown_report()
+        };
+        if let Err(e) = std::fs::write(path, report) {
+            warn!(
+                target: &self.log_target,
+                "Cannot write report file {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Return the title of the report.
+    fn report_title(&self) -> &str {
+        if self.start_msg.is_empty() {
+            "Progress report"
+        } else {
+            &self.start_msg
+        }
+    }
+
+    /// Return the summary statistics of the report, as name-value pairs.
+    fn report_summary(&self) -> Vec<(String, String)> {
+        let elapsed = self.start_time.map_or(Duration::ZERO, |start_time| {
+            self.stop_time.unwrap_or_else(clock::now) - start_time
+        });
+        let mut summary = vec![
+            (
+                "Count".to_string(),
+                format!("{} {}", Grouped(self.count), self.item_name_for(self.count)),
+            ),
+            (
+                "Elapsed".to_string(),
+                Pretty(elapsed.as_millis()).to_string(),
+            ),
+        ];
+        if self.count != 0 && !elapsed.is_zero() {
+            summary.push((
+                "Speed".to_string(),
+                Speed(self, elapsed.as_secs_f64() / self.count as f64)
+                    .to_string()
+                    .trim()
+                    .to_string(),
+            ));
+        }
+        if self.failed != 0 {
+            summary.push(("Failed".to_string(), Grouped(self.failed).to_string()));
+        }
+        for metric in &self.metrics {
+            summary.push((
+                metric.name.clone(),
+                format!(
+                    "min {:.4}, mean {:.4}, max {:.4}",
+                    metric.min,
+                    metric.sum / metric.n as f64,
+                    metric.max
+                ),
+            ));
+        }
+        summary
+    }
+
+    /// Return the rows of the table of phases, built from the spans of the
+    /// nested activities (see [`ProgressLog::span`]).
+    fn report_phases(&self) -> Vec<Phase> {
+        let mut phases = vec![];
+        if let Some(span) = self.span() {
+            collect_phases(&mut phases, &span, 0, span.elapsed());
+        }
+        phases
+    }
+
+    /// Return the rows of the table of logs, including a final row for the
+    /// completion of the activity.
+    fn report_rows(&self) -> Vec<Row> {
+        let end = self.start_time.map_or(Duration::ZERO, |start_time| {
+            self.stop_time.unwrap_or_else(clock::now) - start_time
+        });
+        let mut rows = vec![];
+        let (mut last_elapsed, mut last_count) = (Duration::ZERO, 0);
+        for &(elapsed, count) in self.history.iter().chain([(end, self.count)].iter()) {
+            if elapsed <= last_elapsed && !rows.is_empty() {
+                continue;
+            }
+            rows.push(Row {
+                elapsed,
+                count,
+                seconds_per_item: (count > last_count)
+                    .then(|| (elapsed - last_elapsed).as_secs_f64() / (count - last_count) as f64),
+            });
+            (last_elapsed, last_count) = (elapsed, count);
+        }
+        rows
+    }
+
+    /// Return a report in Markdown format.
+    fn markdown_report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "# {}\n", escape_markdown(self.report_title()));
+        let _ = writeln!(report, "| | |\n|---|---|");
+        for (name, value) in self.report_summary() {
+            let _ = writeln!(
+                report,
+                "| {} | {} |",
+                escape_markdown(&name),
+                escape_markdown(&value)
+            );
+        }
+        let phases = self.report_phases();
+        if !phases.is_empty() {
+            let _ = writeln!(report, "\n## Phases\n");
+            let _ = writeln!(
+                report,
+                "| Phase | Count | Elapsed | % |\n|---|---:|---:|---:|"
+            );
+            for phase in phases {
+                let _ = writeln!(
+                    report,
+                    "| {:\u{a0}<indent$}{} | {} | {} | {} |",
+                    "",
+                    escape_markdown(&phase.name),
+                    Grouped(phase.count),
+                    Pretty(phase.elapsed.as_millis()),
+                    phase
+                        .percent
+                        .map(|percent| format!("{:.2}%", percent))
+                        .unwrap_or_default(),
+                    indent = 2 * phase.depth
+                );
+            }
+        }
+        let _ = writeln!(report, "\n## Logs\n");
+        let _ = writeln!(report, "| Elapsed | Count | Speed |\n|---:|---:|---:|");
+        for row in self.report_rows() {
+            let _ = writeln!(
+                report,
+                "| {} | {} | {} |",
+                Pretty(row.elapsed.as_millis()),
+                Grouped(row.count),
+                row.seconds_per_item
+                    .map(|seconds_per_item| Speed(self, seconds_per_item).to_string())
+                    .unwrap_or_default()
+                    .trim()
+            );
+        }
+        report
+    }
+
+    /// Return a report in HTML format, including a chart of the speed over
+    /// time.
+    fn html_report(&self) -> String {
+        let title = escape_html(self.report_title());
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>",
+            title, title
+        );
+        let _ = writeln!(report, "<table>");
+        for (name, value) in self.report_summary() {
+            let _ = writeln!(
+                report,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape_html(&name),
+                escape_html(&value)
+            );
+        }
+        let _ = writeln!(report, "</table>");
+
+        let phases = self.report_phases();
+        if !phases.is_empty() {
+            let _ = writeln!(
+                report,
+                "<h2>Phases</h2>\n<table>\n<tr><th>Phase</th><th>Count</th><th>Elapsed</th><th>%</th></tr>"
+            );
+            for phase in phases {
+                let _ = writeln!(
+                    report,
+                    "<tr><td>{:\u{a0}<indent$}{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    "",
+                    escape_html(&phase.name),
+                    Grouped(phase.count),
+                    Pretty(phase.elapsed.as_millis()),
+                    phase
+                        .percent
+                        .map(|percent| format!("{:.2}%", percent))
+                        .unwrap_or_default(),
+                    indent = 2 * phase.depth
+                );
+            }
+            let _ = writeln!(report, "</table>");
+        }
+
+        let rows = self.report_rows();
+        let end = rows.last().map_or(0.0, |row| row.elapsed.as_secs_f64());
+        let max_speed = rows
+            .iter()
+            .filter_map(|row| row.seconds_per_item)
+            .map(|seconds_per_item| 1.0 / seconds_per_item)
+            .fold(0.0, f64::max);
+        if end > 0.0 && max_speed > 0.0 {
+            let points = rows
+                .iter()
+                .map(|row| {
+                    let speed = row.seconds_per_item.map_or(0.0, |s| 1.0 / s);
+                    format!(
+                        "{:.1},{:.1}",
+                        CHART_WIDTH * row.elapsed.as_secs_f64() / end,
+                        CHART_HEIGHT * (1.0 - speed / max_speed)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(
+                report,
+                "<h2>Speed over time</h2>\n<svg width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n<polyline fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\" points=\"{}\"/>\n</svg>",
+                CHART_WIDTH, CHART_HEIGHT, CHART_WIDTH, CHART_HEIGHT, points
+            );
+        }
+
+        let _ = writeln!(
+            report,
+            "<h2>Logs</h2>\n<table>\n<tr><th>Elapsed</th><th>Count</th><th>Speed</th></tr>"
+        );
+        for row in rows {
+            let _ = writeln!(
+                report,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Pretty(row.elapsed.as_millis()),
+                Grouped(row.count),
+                escape_html(
+                    row.seconds_per_item
+                        .map(|seconds_per_item| Speed(self, seconds_per_item).to_string())
+                        .unwrap_or_default()
+                        .trim()
+                )
+            );
+        }
+        let _ = writeln!(report, "</table>\n</body>\n</html>");
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ProgressUpdate, RollUp};
+
+    #[test]
+    fn test_markdown_report() {
+        let path = std::env::temp_dir().join("dsi_progress_logger_test_report.md");
+        let mut pl = ProgressLogger::default();
+        pl.report_file(Some(&path));
+        pl.start("Testing | reports...");
+        pl.update_with_count(1000);
+        pl.update_metric("a|b", 1.0);
+        let mut child = RollUp::new(ProgressLogger::default(), &mut pl);
+        child.start("Phase...");
+        child.done();
+        pl.done();
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(report.starts_with("# Testing \\| reports...\n"));
+        assert!(report.contains("| Count | 1,000 items |"));
+        assert!(report.contains("| a\\|b | min 1.0000"));
+        assert!(report.contains("| Phase | Count | Elapsed | % |"));
+        assert!(report.contains("| Phase | 1 |"));
+        assert!(report.contains("| Elapsed | Count | Speed |"));
+    }
+
+    #[test]
+    fn test_html_report() {
+        let path = std::env::temp_dir().join("dsi_progress_logger_test_report.html");
+        let mut pl = ProgressLogger::default();
+        pl.report_file(Some(&path));
+        pl.start("<Testing>");
+        pl.update_with_count(1000);
+        pl.done();
+        let report = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(report.contains("<h1>&lt;Testing&gt;</h1>"));
+        assert!(report.contains("<tr><th>Count</th><td>1,000 items</td></tr>"));
+    }
+
+    #[test]
+    fn test_history_capacity() {
+        let path = std::env::temp_dir().join("dsi_progress_logger_test_history.md");
+        let mut pl = ProgressLogger::default();
+        pl.report_file(Some(&path));
+        pl.start("Testing...");
+        for _ in 0..3 * ProgressLogger::HISTORY_CAPACITY {
+            pl.update_with_count(10);
+            pl.log(clock::now());
+        }
+        // The recorded logs are downsampled, but still cover the activity
+        assert!(pl.history.len() <= ProgressLogger::HISTORY_CAPACITY);
+        assert!(pl.history.len() >= ProgressLogger::HISTORY_CAPACITY / 2);
+        assert!(pl.history.last().unwrap().1 > pl.count - 10 * pl.history_stride);
+        pl.done();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
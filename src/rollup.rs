@@ -0,0 +1,660 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{
+    CiFormat, DisplayFields, ElapsedFormat, EtaSmoothing, Instant, OvershootPolicy, ProgressLog,
+    ProgressUpdate, Span, TimeUnit,
+};
+use std::fmt::{Arguments, Display, Formatter, Result};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A [`ProgressLog`] rolling up the updates of a child logger into a parent
+/// logger.
+///
+/// All methods are delegated to the child logger, which keeps its own
+/// configuration and display; additionally, updates are forwarded to the
+/// parent logger, so that the parent reflects the total progress of all its
+/// children. Since [`ProgressLog`] is implemented for `&mut P`, the parent is
+/// usually a mutable reference to a logger.
+///
+/// Note that [`done_with_count`](ProgressLog::done_with_count) sets only the
+/// count of the child, that updates forwarded to the parent are not undone by
+/// [`restart`](ProgressLog::restart), and that the parent is never started or
+/// stopped by this structure. The child never writes a [trace
+/// file](ProgressLog::trace_file), as its span is added to the parent, and
+/// thus written in the trace of the outermost logger.
+///
+/// A roll-up can also be [weighted](RollUp::with_weight): in this case, the
+/// child represents a subtask accounting for a given number of updates of the
+/// parent, which are forwarded proportionally to the completion of the child.
+/// In this way, the percentage of completion of the parent is a weighted
+/// combination of the completion of its subtasks.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut parent = progress_logger![item_name = "file"];
+/// parent.start("Processing files...");
+/// for dir in 0..3 {
+///     let mut pl = RollUp::new(progress_logger![item_name = "file"], &mut parent);
+///     pl.start(format!("Processing directory {}...", dir));
+///     for _ in 0..100 {
+///         pl.update();
+///     }
+///     pl.done();
+/// }
+/// parent.done();
+/// ```
+#[derive(Debug)]
+pub struct RollUp<C: ProgressLog, P: ProgressLog> {
+    child: C,
+    parent: P,
+    /// The number of updates of the parent this child accounts for, if
+    /// weighted.
+    weight: Option<usize>,
+    /// The expected number of updates of the child.
+    expected_updates: Option<usize>,
+    /// The number of updates of the child, if weighted.
+    count: usize,
+    /// The number of updates forwarded to the parent, if weighted.
+    forwarded: usize,
+}
+
+impl<C: ProgressLog, P: ProgressLog> RollUp<C, P> {
+    /// Create a new roll-up forwarding the updates of `child` to `parent`.
+    pub fn new(child: C, parent: P) -> Self {
+        Self {
+            child,
+            parent,
+            weight: None,
+            expected_updates: None,
+            count: 0,
+            forwarded: 0,
+        }
+    }
+
+    /// Create a new weighted roll-up in which `child` accounts for `weight`
+    /// updates of `parent`.
+    ///
+    /// The updates of the child are not forwarded directly; rather, the parent
+    /// is updated so that its count increases by `weight` times the fraction
+    /// of [expected updates](ProgressLog::expected_updates) of the child that
+    /// have been completed. If the expected updates of the child are not set,
+    /// nothing is forwarded until [`done`](ProgressLog::done) is called.
+    /// [`done`](ProgressLog::done) and
+    /// [`done_with_count`](ProgressLog::done_with_count) forward the remaining
+    /// part of the weight.
+    ///
+    /// Typically, the expected updates of the parent are the sum of the weights
+    /// of its subtasks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut parent = progress_logger![item_name = "unit", expected_updates = Some(100)];
+    /// parent.start("Building index...");
+    ///
+    /// // Parsing accounts for 5% of the total time
+    /// let mut pl = RollUp::with_weight(progress_logger![item_name = "line"], &mut parent, 5);
+    /// pl.expected_updates(Some(1000));
+    /// pl.start("Parsing...");
+    /// for _ in 0..1000 {
+    ///     pl.update();
+    /// }
+    /// pl.done();
+    ///
+    /// // Compression accounts for 95% of the total time
+    /// let mut pl = RollUp::with_weight(progress_logger![item_name = "block"], &mut parent, 95);
+    /// pl.expected_updates(Some(10));
+    /// pl.start("Compressing...");
+    /// for _ in 0..10 {
+    ///     pl.update();
+    /// }
+    /// pl.done();
+    ///
+    /// parent.done();
+    /// ```
+    pub fn with_weight(child: C, parent: P, weight: usize) -> Self {
+        Self {
+            weight: Some(weight),
+            ..Self::new(child, parent)
+        }
+    }
+
+    /// Set the log target of the child logger to that of the parent logger
+    /// followed by `::` and the given phase name, or to the phase name alone
+    /// if the log target of the parent logger is empty.
+    ///
+    /// In this way, the phases of a pipeline have hierarchical log targets
+    /// (e.g., `myapp::sort` and `myapp::write`), and standard filtering (e.g.,
+    /// `RUST_LOG=myapp::sort=off` with `env_logger`) can silence individual
+    /// phases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    ///
+    /// let mut parent = progress_logger![log_target = "myapp"];
+    /// parent.start("Building index...");
+    /// let mut pl = RollUp::new(progress_logger![], &mut parent).phase("sort");
+    /// assert_eq!(pl.get_log_target(), "myapp::sort");
+    /// pl.start("Sorting...");
+    /// pl.update_with_count(1000);
+    /// pl.done();
+    /// parent.done();
+    ///
+    /// let mut parent = progress_logger![log_target = ""];
+    /// let pl = RollUp::new(progress_logger![], &mut parent).phase("sort");
+    /// assert_eq!(pl.get_log_target(), "sort");
+    /// ```
+    pub fn phase(mut self, name: impl AsRef<str>) -> Self {
+        let parent_target = self.parent.get_log_target();
+        let target = if parent_target.is_empty() {
+            name.as_ref().to_owned()
+        } else {
+            format!("{}::{}", parent_target, name.as_ref())
+        };
+        self.child.log_target(target);
+        self
+    }
+
+    /// Return a reference to the child logger.
+    pub fn child(&self) -> &C {
+        &self.child
+    }
+
+    /// Return a reference to the parent logger.
+    pub fn parent(&self) -> &P {
+        &self.parent
+    }
+
+    /// Return the child and the parent logger.
+    pub fn into_inner(self) -> (C, P) {
+        (self.child, self.parent)
+    }
+
+    /// Forward `count` updates of the child to the parent, scaling them if
+    /// this roll-up is weighted.
+    fn forward(&mut self, count: usize) {
+        let Some(weight) = self.weight else {
+            self.parent.update_with_count(count);
+            return;
+        };
+
+        self.count += count;
+        if let Some(expected_updates) = self.expected_updates.filter(|&e| e != 0) {
+            let done = (weight as u128 * self.count.min(expected_updates) as u128
+                / expected_updates as u128) as usize;
+            if done > self.forwarded {
+                self.parent.update_with_count(done - self.forwarded);
+                self.forwarded = done;
+            }
+        }
+    }
+
+    /// Forward the remaining part of the weight to the parent, if this
+    /// roll-up is weighted.
+    fn forward_rest(&mut self) {
+        if let Some(weight) = self.weight {
+            if weight > self.forwarded {
+                self.parent.update_with_count(weight - self.forwarded);
+                self.forwarded = weight;
+            }
+        }
+    }
+}
+
+impl<C: ProgressLog, P: ProgressLog> ProgressUpdate for RollUp<C, P> {
+    fn update(&mut self) {
+        self.child.update();
+        self.forward(1);
+    }
+
+    fn update_with_count(&mut self, count: usize) {
+        self.child.update_with_count(count);
+        self.forward(count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        self.child.update_with_time(now);
+        self.forward(1);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        self.child.update_with_count_and_time(count, now);
+        self.forward(count);
+    }
+
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    fn light_update(&mut self) {
+        self.child.light_update();
+        if self.weight.is_some() {
+            self.forward(1);
+        } else {
+            self.parent.light_update();
+        }
+    }
+}
+
+impl<C: ProgressLog, P: ProgressLog> ProgressLog for RollUp<C, P> {
+    fn log(&mut self, now: Instant) {
+        self.child.log(now);
+    }
+
+    fn log_if(&mut self) {
+        self.child.log_if();
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.child.display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.child.display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.child.display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.child.monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.child
+            .memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.child.memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.child.item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.child.item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.child.log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.child.log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.child.expected_updates(expected_updates);
+        self.expected_updates = expected_updates;
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.child.time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.child.local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.child.local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.child.min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.child.skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.child.max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.child.min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.child.target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.child.failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.child.eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.child.eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.child.speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.child.overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.child.expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.child.time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.child.on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.child.cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.child.deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.child.log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.child.log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.child.progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.child.report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.child.trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.child.ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.child.log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.child.log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.child.elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.child.display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.child.display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.child.fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.child.display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.child.timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.child.remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.child.display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.child.display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.child.start(msg);
+        self.count = 0;
+        self.forwarded = 0;
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.child.start_with_expected(msg, expected_updates);
+        self.expected_updates = Some(expected_updates);
+        self.count = 0;
+        self.forwarded = 0;
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.child
+            .start_resumed(msg, already_done, expected_updates);
+        self.expected_updates = Some(expected_updates);
+        // The parent is assumed to account already for the updates done
+        self.count = already_done;
+        self.forwarded = self.weight.map_or(0, |weight| {
+            (weight as u128 * already_done.min(expected_updates) as u128
+                / expected_updates.max(1) as u128) as usize
+        });
+    }
+
+    fn restart(&mut self) {
+        self.child.restart();
+        // Updates already forwarded are not forwarded again
+        self.count = 0;
+    }
+
+    fn update_failed(&mut self) {
+        self.child.update_failed();
+        self.forward(1);
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        self.child.update_metric(name, value);
+    }
+
+    fn update_and_display(&mut self) {
+        self.child.update_and_display();
+        self.forward(1);
+    }
+
+    fn stop(&mut self) {
+        self.child.stop();
+    }
+
+    fn done(&mut self) {
+        // The span of the child is written in the trace of the root logger
+        self.child.trace_file(None);
+        self.child.done();
+        self.forward_rest();
+        if let Some(span) = self.child.span() {
+            self.parent.add_span(span);
+        }
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        // The span of the child is written in the trace of the root logger
+        self.child.trace_file(None);
+        self.child.done_with_count(count);
+        self.forward_rest();
+        if let Some(span) = self.child.span() {
+            self.parent.add_span(span);
+        }
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.child.time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.child.is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        self.child.is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.child.is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.child.items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.child.local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.child.eta()
+    }
+
+    fn count(&self) -> usize {
+        self.child.count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.child.elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.child.get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.child.get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.child.get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.child.get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.child.get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.child.span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.child.add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        self.child.refresh();
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        self.child.info(args);
+    }
+}
+
+impl<C: ProgressLog + Display, P: ProgressLog> Display for RollUp<C, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.child.fmt(f)
+    }
+}
@@ -0,0 +1,734 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{
+    CiFormat, DisplayFields, ElapsedFormat, EtaSmoothing, Instant, OvershootPolicy, ProgressLog,
+    ProgressLogger, ProgressUpdate, Span, TimeUnit,
+};
+use std::fmt::{Arguments, Display, Formatter, Result};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A per-thread count, aligned to avoid false sharing.
+#[repr(align(128))]
+#[derive(Debug, Default)]
+struct Slot(AtomicUsize);
+
+/// The source of thread indices.
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The index of the current thread, used to choose a slot.
+    static THREAD_INDEX: usize = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A concurrent wrapper for a [`ProgressLog`] implementation that can be
+/// updated through a shared reference.
+///
+/// Differently from a [`ConcurrentWrapper`](crate::ConcurrentWrapper), which
+/// must be cloned for each thread (or task) so that each copy can buffer its
+/// own updates, this wrapper has a single handle: updates are buffered in a
+/// per-thread slot, and the slot is flushed to the underlying logger, which is
+/// protected by a mutex, when it reaches a threshold. Thus, the wrapper can be
+/// shared by reference among threads, as in closures passed to
+/// [rayon](https://docs.rs/rayon/)'s parallel iterators, without cloning it per
+/// task.
+///
+/// The methods [`update`](Self::update),
+/// [`update_with_count`](Self::update_with_count), and
+/// [`light_update`](Self::light_update) take `&self`. [`ProgressUpdate`] is
+/// implemented both for the wrapper and for shared references to the wrapper,
+/// so the latter can be passed to functions accepting an `impl
+/// ProgressUpdate`. [`ProgressLog`] is implemented for the wrapper, and its
+/// methods flush all slots when needed (e.g., in [`done`](ProgressLog::done)).
+///
+/// Threads are assigned slots in round-robin fashion, so slots might be shared
+/// by multiple threads; this affects only contention, not correctness.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut spl = SharedWrapper::new();
+/// spl.item_name("pumpkin");
+/// spl.start("Smashing pumpkins (using many threads)...");
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..100 {
+///         // A shared reference is all we need
+///         let spl = &spl;
+///         s.spawn(move || {
+///             for _ in 0..100000 {
+///                 spl.update();
+///             }
+///         });
+///     }
+/// });
+///
+/// spl.done();
+/// ```
+#[derive(Debug)]
+pub struct SharedWrapper<P: ProgressLog = ProgressLogger> {
+    /// Underlying logger.
+    inner: Mutex<P>,
+    /// The per-thread counts; the length is a power of two.
+    slots: Box<[Slot]>,
+    /// The threshold for updating the underlying logger.
+    threshold: usize,
+}
+
+impl Default for SharedWrapper {
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedWrapper {
+    /// Create a new [`SharedWrapper`] wrapping a default [`ProgressLogger`],
+    /// using the [default threshold](Self::DEFAULT_THRESHOLD).
+    #[track_caller]
+    pub fn new() -> Self {
+        Self::wrap(ProgressLogger::default())
+    }
+
+    /// Create a new [`SharedWrapper`] wrapping a default [`ProgressLogger`],
+    /// using the given threshold.
+    #[track_caller]
+    pub fn with_threshold(threshold: usize) -> Self {
+        Self::wrap_with_threshold(ProgressLogger::default(), threshold)
+    }
+}
+
+impl<P: ProgressLog> SharedWrapper<P> {
+    /// The default threshold for updating the underlying logger.
+    pub const DEFAULT_THRESHOLD: usize = 1 << 15;
+
+    /// Wrap a given [`ProgressLog`] in a [`SharedWrapper`] using the [default
+    /// threshold](Self::DEFAULT_THRESHOLD).
+    pub fn wrap(inner: P) -> Self {
+        Self::wrap_with_threshold(inner, Self::DEFAULT_THRESHOLD)
+    }
+
+    /// Wrap a given [`ProgressLog`] in a [`SharedWrapper`] using a given
+    /// threshold.
+    pub fn wrap_with_threshold(inner: P, threshold: usize) -> Self {
+        let num_slots = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .next_power_of_two()
+            * 2;
+        Self {
+            inner: Mutex::new(inner),
+            slots: (0..num_slots).map(|_| Slot::default()).collect(),
+            threshold,
+        }
+    }
+
+    /// Set the threshold for updating the underlying logger.
+    pub fn threshold(&mut self, threshold: usize) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Return the slot of the current thread.
+    #[inline(always)]
+    fn slot(&self) -> &AtomicUsize {
+        let index = THREAD_INDEX.with(|index| *index);
+        &self.slots[index & (self.slots.len() - 1)].0
+    }
+
+    /// Increase the count of the current thread by one, passing the count to
+    /// the underlying logger if the threshold is reached.
+    #[inline]
+    pub fn update(&self) {
+        self.update_with_count(1);
+    }
+
+    /// Increase the count of the current thread by the given amount, passing
+    /// the count to the underlying logger if the threshold is reached.
+    #[inline]
+    pub fn update_with_count(&self, count: usize) {
+        let slot = self.slot();
+        if slot.fetch_add(count, Ordering::Relaxed) + count >= self.threshold {
+            // Another thread sharing the slot might have emptied it
+            let count = slot.swap(0, Ordering::Relaxed);
+            if count != 0 {
+                self.inner.lock().unwrap().update_with_count(count);
+            }
+        }
+    }
+
+    /// Increase the count of the current thread by the given amount, passing
+    /// the count to the underlying logger, with `now` as the current time, if
+    /// the threshold is reached.
+    pub fn update_with_count_and_time(&self, count: usize, now: Instant) {
+        let slot = self.slot();
+        if slot.fetch_add(count, Ordering::Relaxed) + count >= self.threshold {
+            let count = slot.swap(0, Ordering::Relaxed);
+            if count != 0 {
+                self.inner
+                    .lock()
+                    .unwrap()
+                    .update_with_count_and_time(count, now);
+            }
+        }
+    }
+
+    /// Increase the count of the current thread by one.
+    ///
+    /// Since updates are already buffered, this method is equivalent to
+    /// [`update`](Self::update).
+    #[inline]
+    pub fn light_update(&self) {
+        self.update_with_count(1);
+    }
+
+    /// Pass the counts of all threads to the underlying logger.
+    pub fn flush(&self) {
+        let count = self
+            .slots
+            .iter()
+            .map(|slot| slot.0.swap(0, Ordering::Relaxed))
+            .sum::<usize>();
+        if count != 0 {
+            self.inner.lock().unwrap().update_with_count(count);
+        }
+    }
+
+    /// Discard the counts of all threads.
+    fn clear(&self) {
+        for slot in self.slots.iter() {
+            slot.0.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Flush the counts of all threads and return the underlying logger.
+    pub fn into_inner(self) -> P {
+        self.flush();
+        self.inner.into_inner().unwrap()
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for SharedWrapper<P> {
+    #[inline]
+    fn update(&mut self) {
+        Self::update_with_count(self, 1);
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        Self::update_with_count(self, count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        Self::update_with_count_and_time(self, 1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        Self::update_with_count_and_time(self, count, now);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        Self::update_with_count(self, counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        Self::update_with_count(self, 1);
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for &SharedWrapper<P> {
+    #[inline]
+    fn update(&mut self) {
+        SharedWrapper::update_with_count(self, 1);
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        SharedWrapper::update_with_count(self, count);
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        SharedWrapper::update_with_count_and_time(self, 1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        SharedWrapper::update_with_count_and_time(self, count, now);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        SharedWrapper::update_with_count(self, counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        SharedWrapper::update_with_count(self, 1);
+    }
+}
+
+impl<P: ProgressLog> ProgressLog for SharedWrapper<P> {
+    fn log(&mut self, now: Instant) {
+        self.flush();
+        self.inner.get_mut().unwrap().log(now);
+    }
+
+    fn log_if(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().log_if();
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.inner.get_mut().unwrap().item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.inner.get_mut().unwrap().log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.inner.get_mut().unwrap().log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .expected_updates(expected_updates);
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.inner.get_mut().unwrap().time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.inner.get_mut().unwrap().min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.inner.get_mut().unwrap().max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.inner.get_mut().unwrap().min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.inner.get_mut().unwrap().target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.inner.get_mut().unwrap().eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.inner.get_mut().unwrap().time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.inner.get_mut().unwrap().cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.get_mut().unwrap().trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.inner.get_mut().unwrap().ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.inner.get_mut().unwrap().log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.inner.get_mut().unwrap().elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.inner.get_mut().unwrap().display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.inner.get_mut().unwrap().timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.inner
+            .get_mut()
+            .unwrap()
+            .display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.clear();
+        self.inner.get_mut().unwrap().start(msg);
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.clear();
+        self.inner
+            .get_mut()
+            .unwrap()
+            .start_with_expected(msg, expected_updates);
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.clear();
+        self.inner
+            .get_mut()
+            .unwrap()
+            .start_resumed(msg, already_done, expected_updates);
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+        self.inner.get_mut().unwrap().restart();
+    }
+
+    fn update_failed(&mut self) {
+        self.inner.get_mut().unwrap().update_failed();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        self.inner.get_mut().unwrap().update_metric(name, value);
+    }
+
+    fn update_and_display(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().update_and_display();
+    }
+
+    fn stop(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().stop();
+    }
+
+    fn done(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().done();
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        self.flush();
+        self.inner.get_mut().unwrap().done_with_count(count);
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.inner.lock().unwrap().time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.lock().unwrap().is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        self.inner.lock().unwrap().is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.lock().unwrap().is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.inner.lock().unwrap().local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().eta()
+    }
+
+    fn count(&self) -> usize {
+        self.inner.lock().unwrap().count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.inner.lock().unwrap().get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.inner.lock().unwrap().get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.inner.lock().unwrap().get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.inner.lock().unwrap().get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.inner.lock().unwrap().get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.inner.lock().unwrap().span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.inner.get_mut().unwrap().add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        self.flush();
+        self.inner.get_mut().unwrap().refresh();
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        self.inner.lock().unwrap().info(args)
+    }
+}
+
+/// This implementation flushes the counts of all threads before displaying the
+/// underlying logger.
+impl Display for SharedWrapper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.flush();
+        self.inner.lock().unwrap().fmt(f)
+    }
+}
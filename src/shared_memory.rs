@@ -0,0 +1,629 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{
+    CiFormat, DisplayFields, ElapsedFormat, EtaSmoothing, Instant, OvershootPolicy, ProgressLog,
+    ProgressLogger, ProgressUpdate, Span, TimeUnit,
+};
+use std::fmt::{Arguments, Display, Formatter, Result};
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An atomic integer in an anonymous shared memory mapping, which is
+/// inherited by forked children.
+#[derive(Debug)]
+struct Mapping(NonNull<AtomicUsize>);
+
+// SAFETY: the mapping contains just an atomic integer
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new() -> std::io::Result<Self> {
+        // SAFETY: we map a fresh anonymous region, which is zero-filled, and
+        // thus contains a valid AtomicUsize
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                std::mem::size_of::<AtomicUsize>(),
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self(NonNull::new(ptr.cast()).unwrap()))
+    }
+
+    #[inline(always)]
+    fn counter(&self) -> &AtomicUsize {
+        // SAFETY: the mapping is valid until self is dropped
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: the pointer was returned by mmap with the same size
+        unsafe {
+            libc::munmap(self.0.as_ptr().cast(), std::mem::size_of::<AtomicUsize>());
+        }
+    }
+}
+
+/// A [`ProgressLog`] whose count lives in shared memory, so that forked
+/// children can update it, while the parent logs the combined progress.
+///
+/// The count is kept in an anonymous shared memory mapping, which is
+/// inherited by children created by `fork` (e.g., by a pre-forking server or
+/// by a multiprocess tool). Children should not use the logger directly, but
+/// rather a [`SharedCounter`] obtained with [`counter`](Self::counter) before
+/// forking, which just increases the shared count. The parent process uses
+/// the logger as usual (e.g., by calling [`log_if`](ProgressLog::log_if)
+/// periodically while waiting for the children): the increments of the shared
+/// count since the last check are passed to the underlying logger before
+/// logging.
+///
+/// This structure is available only on Unix systems, and only if the
+/// `shared-memory` feature is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut pl = SharedMemoryLogger::new()?;
+/// pl.item_name("job");
+/// let counter = pl.counter();
+/// pl.start("Running jobs in children...");
+/// // Here children would be forked, each with a copy of counter;
+/// // we simulate them with threads
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         let mut counter = counter.clone();
+///         s.spawn(move || {
+///             for _ in 0..1000 {
+///                 counter.update();
+///             }
+///         });
+///     }
+/// });
+/// pl.done();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SharedMemoryLogger<P: ProgressLog = ProgressLogger> {
+    /// Underlying logger.
+    inner: P,
+    /// The shared count.
+    mapping: Arc<Mapping>,
+    /// The part of the shared count already passed to the underlying logger.
+    synced: usize,
+}
+
+impl SharedMemoryLogger {
+    /// Create a new [`SharedMemoryLogger`] wrapping a default
+    /// [`ProgressLogger`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the shared memory mapping cannot be created.
+    #[track_caller]
+    pub fn new() -> std::io::Result<Self> {
+        Self::wrap(ProgressLogger::default())
+    }
+}
+
+impl<P: ProgressLog> SharedMemoryLogger<P> {
+    /// Wrap a given [`ProgressLog`] in a [`SharedMemoryLogger`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the shared memory mapping cannot be created.
+    pub fn wrap(inner: P) -> std::io::Result<Self> {
+        Ok(Self {
+            inner,
+            mapping: Arc::new(Mapping::new()?),
+            synced: 0,
+        })
+    }
+
+    /// Return a handle to the shared count, to be used by forked children.
+    pub fn counter(&self) -> SharedCounter {
+        SharedCounter(self.mapping.clone())
+    }
+
+    /// Pass the increments of the shared count since the last call to the
+    /// underlying logger.
+    pub fn sync(&mut self) {
+        let count = self.mapping.counter().load(Ordering::Relaxed);
+        let delta = count.wrapping_sub(self.synced);
+        self.synced = count;
+        if delta != 0 {
+            self.inner.update_with_count(delta);
+        }
+    }
+
+    /// Reset the shared count.
+    fn clear(&mut self) {
+        self.mapping.counter().store(0, Ordering::Relaxed);
+        self.synced = 0;
+    }
+
+    /// Return the underlying logger.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// A handle to the count of a [`SharedMemoryLogger`].
+///
+/// The handle implements [`ProgressUpdate`] by increasing the shared count;
+/// no logging is performed.
+#[derive(Debug, Clone)]
+pub struct SharedCounter(Arc<Mapping>);
+
+impl ProgressUpdate for SharedCounter {
+    #[inline]
+    fn update(&mut self) {
+        self.update_with_count(1);
+    }
+
+    #[inline]
+    fn update_with_count(&mut self, count: usize) {
+        self.0.counter().fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn update_with_time(&mut self, _now: Instant) {
+        self.update_with_count(1);
+    }
+
+    #[inline]
+    fn update_with_count_and_time(&mut self, count: usize, _now: Instant) {
+        self.update_with_count(count);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        self.update_with_count(1);
+    }
+}
+
+impl<P: ProgressLog> ProgressUpdate for SharedMemoryLogger<P> {
+    #[inline]
+    fn update(&mut self) {
+        self.update_with_count(1);
+    }
+
+    fn update_with_count(&mut self, count: usize) {
+        self.mapping.counter().fetch_add(count, Ordering::Relaxed);
+        self.sync();
+    }
+
+    fn update_with_time(&mut self, now: Instant) {
+        self.update_with_count_and_time(1, now);
+    }
+
+    fn update_with_count_and_time(&mut self, count: usize, now: Instant) {
+        let count = self.mapping.counter().fetch_add(count, Ordering::Relaxed) + count;
+        let delta = count.wrapping_sub(self.synced);
+        self.synced = count;
+        self.inner.update_with_count_and_time(delta, now);
+    }
+
+    #[inline]
+    fn update_from_counts(&mut self, counts: impl IntoIterator<Item = usize>) {
+        self.update_with_count(counts.into_iter().sum());
+    }
+
+    #[inline]
+    fn light_update(&mut self) {
+        self.update_with_count(1);
+    }
+}
+
+impl<P: ProgressLog> ProgressLog for SharedMemoryLogger<P> {
+    fn log(&mut self, now: Instant) {
+        self.sync();
+        self.inner.log(now);
+    }
+
+    fn log_if(&mut self) {
+        self.sync();
+        self.inner.log_if();
+    }
+
+    fn display_memory(&mut self, display_memory: bool) -> &mut Self {
+        self.inner.display_memory(display_memory);
+        self
+    }
+
+    fn display_memory_delta(&mut self, display_memory_delta: bool) -> &mut Self {
+        self.inner.display_memory_delta(display_memory_delta);
+        self
+    }
+
+    fn display_load_average(&mut self, display_load_average: bool) -> &mut Self {
+        self.inner.display_load_average(display_load_average);
+        self
+    }
+
+    fn monitor_free_space(&mut self, path: Option<&Path>, min_free_space: u64) -> &mut Self {
+        self.inner.monitor_free_space(path, min_free_space);
+        self
+    }
+
+    fn memory_warning_threshold(&mut self, memory_warning_threshold: Option<f64>) -> &mut Self {
+        self.inner
+            .memory_warning_threshold(memory_warning_threshold);
+        self
+    }
+
+    fn memory_refresh_interval(&mut self, memory_refresh_interval: Duration) -> &mut Self {
+        self.inner.memory_refresh_interval(memory_refresh_interval);
+        self
+    }
+
+    fn item_name(&mut self, item_name: impl AsRef<str>) -> &mut Self {
+        self.inner.item_name(item_name);
+        self
+    }
+
+    fn item_name_plural(&mut self, item_name_plural: impl AsRef<str>) -> &mut Self {
+        self.inner.item_name_plural(item_name_plural);
+        self
+    }
+
+    fn log_interval(&mut self, log_interval: Duration) -> &mut Self {
+        self.inner.log_interval(log_interval);
+        self
+    }
+
+    fn log_interval_jitter(&mut self, jitter: f64) -> &mut Self {
+        self.inner.log_interval_jitter(jitter);
+        self
+    }
+
+    fn expected_updates(&mut self, expected_updates: Option<usize>) -> &mut Self {
+        self.inner.expected_updates(expected_updates);
+        self
+    }
+
+    fn time_unit(&mut self, time_unit: Option<TimeUnit>) -> &mut Self {
+        self.inner.time_unit(time_unit);
+        self
+    }
+
+    fn local_speed(&mut self, local_speed: bool) -> &mut Self {
+        self.inner.local_speed(local_speed);
+        self
+    }
+
+    fn local_speed_window(&mut self, local_speed_window: usize) -> &mut Self {
+        self.inner.local_speed_window(local_speed_window);
+        self
+    }
+
+    fn min_speed(&mut self, min_speed: Option<f64>) -> &mut Self {
+        self.inner.min_speed(min_speed);
+        self
+    }
+
+    fn skip_idle(&mut self, skip_idle: bool) -> &mut Self {
+        self.inner.skip_idle(skip_idle);
+        self
+    }
+
+    fn max_logs(&mut self, max_logs: Option<usize>) -> &mut Self {
+        self.inner.max_logs(max_logs);
+        self
+    }
+
+    fn min_duration(&mut self, min_duration: Option<Duration>) -> &mut Self {
+        self.inner.min_duration(min_duration);
+        self
+    }
+
+    fn target_speed(&mut self, target_speed: Option<f64>) -> &mut Self {
+        self.inner.target_speed(target_speed);
+        self
+    }
+
+    fn failure_rate_thresholds(
+        &mut self,
+        warn_rate: Option<f64>,
+        error_rate: Option<f64>,
+    ) -> &mut Self {
+        self.inner.failure_rate_thresholds(warn_rate, error_rate);
+        self
+    }
+
+    fn eta_smoothing(&mut self, eta_smoothing: EtaSmoothing) -> &mut Self {
+        self.inner.eta_smoothing(eta_smoothing);
+        self
+    }
+
+    fn eta_range_threshold(&mut self, eta_range_threshold: Option<f64>) -> &mut Self {
+        self.inner.eta_range_threshold(eta_range_threshold);
+        self
+    }
+
+    fn speed_sparkline(&mut self, speed_sparkline: Option<usize>) -> &mut Self {
+        self.inner.speed_sparkline(speed_sparkline);
+        self
+    }
+
+    fn overshoot_policy(&mut self, overshoot_policy: OvershootPolicy) -> &mut Self {
+        self.inner.overshoot_policy(overshoot_policy);
+        self
+    }
+
+    fn expected_duration(&mut self, expected_duration: Option<Duration>) -> &mut Self {
+        self.inner.expected_duration(expected_duration);
+        self
+    }
+
+    fn time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.inner.time_budget(time_budget);
+        self
+    }
+
+    fn on_time_budget_exceeded(
+        &mut self,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.inner.on_time_budget_exceeded(callback);
+        self
+    }
+
+    fn cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) -> &mut Self {
+        self.inner.cancel_flag(cancel_flag);
+        self
+    }
+
+    fn deferred_logging(&mut self, deferred_logging: bool) -> &mut Self {
+        self.inner.deferred_logging(deferred_logging);
+        self
+    }
+
+    fn log_on_drop(&mut self, log_on_drop: bool) -> &mut Self {
+        self.inner.log_on_drop(log_on_drop);
+        self
+    }
+
+    fn log_span_tree(&mut self, log_span_tree: bool) -> &mut Self {
+        self.inner.log_span_tree(log_span_tree);
+        self
+    }
+
+    fn progress_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.progress_file(path);
+        self
+    }
+
+    fn report_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.report_file(path);
+        self
+    }
+
+    fn trace_file(&mut self, path: Option<&Path>) -> &mut Self {
+        self.inner.trace_file(path);
+        self
+    }
+
+    fn ci_format(&mut self, ci_format: Option<CiFormat>) -> &mut Self {
+        self.inner.ci_format(ci_format);
+        self
+    }
+
+    fn log_config(&mut self, log_config: bool) -> &mut Self {
+        self.inner.log_config(log_config);
+        self
+    }
+
+    fn log_target(&mut self, target: impl AsRef<str>) -> &mut Self {
+        self.inner.log_target(target);
+        self
+    }
+
+    fn elapsed_format(&mut self, elapsed_format: ElapsedFormat) -> &mut Self {
+        self.inner.elapsed_format(elapsed_format);
+        self
+    }
+
+    fn display_timestamp(&mut self, display_timestamp: bool) -> &mut Self {
+        self.inner.display_timestamp(display_timestamp);
+        self
+    }
+
+    fn display_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner.display_ratio(numerator, denominator);
+        self
+    }
+
+    fn fixed_width(&mut self, fixed_width: bool) -> &mut Self {
+        self.inner.fixed_width(fixed_width);
+        self
+    }
+
+    fn display_fields(&mut self, display_fields: DisplayFields) -> &mut Self {
+        self.inner.display_fields(display_fields);
+        self
+    }
+
+    fn timing_first(&mut self, timing_first: bool) -> &mut Self {
+        self.inner.timing_first(timing_first);
+        self
+    }
+
+    fn remove_ratio(
+        &mut self,
+        numerator: impl AsRef<str>,
+        denominator: impl AsRef<str>,
+    ) -> &mut Self {
+        self.inner.remove_ratio(numerator, denominator);
+        self
+    }
+
+    fn display_remaining(&mut self, display_remaining: bool) -> &mut Self {
+        self.inner.display_remaining(display_remaining);
+        self
+    }
+
+    fn display_fraction(&mut self, display_fraction: bool) -> &mut Self {
+        self.inner.display_fraction(display_fraction);
+        self
+    }
+
+    fn start(&mut self, msg: impl AsRef<str>) {
+        self.clear();
+        self.inner.start(msg);
+    }
+
+    fn start_with_expected(&mut self, msg: impl AsRef<str>, expected_updates: usize) {
+        self.clear();
+        self.inner.start_with_expected(msg, expected_updates);
+    }
+
+    fn start_resumed(
+        &mut self,
+        msg: impl AsRef<str>,
+        already_done: usize,
+        expected_updates: usize,
+    ) {
+        self.clear();
+        self.inner
+            .start_resumed(msg, already_done, expected_updates);
+    }
+
+    fn restart(&mut self) {
+        self.clear();
+        self.inner.restart();
+    }
+
+    fn update_failed(&mut self) {
+        self.inner.update_failed();
+    }
+
+    fn update_metric(&mut self, name: impl AsRef<str>, value: f64) {
+        self.inner.update_metric(name, value);
+    }
+
+    fn update_and_display(&mut self) {
+        self.sync();
+        self.inner.update_and_display();
+    }
+
+    fn stop(&mut self) {
+        self.sync();
+        self.inner.stop();
+    }
+
+    fn done(&mut self) {
+        self.sync();
+        self.inner.done();
+    }
+
+    fn done_with_count(&mut self, count: usize) {
+        self.sync();
+        self.inner.done_with_count(count);
+    }
+
+    fn time_budget_exceeded(&self) -> bool {
+        self.inner.time_budget_exceeded()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn is_started(&self) -> bool {
+        self.inner.is_started()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.inner.is_stopped()
+    }
+
+    fn items_per_second(&self) -> Option<f64> {
+        self.inner.items_per_second()
+    }
+
+    fn local_items_per_second(&self) -> Option<f64> {
+        self.inner.local_items_per_second()
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        self.inner.eta()
+    }
+
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        self.inner.elapsed()
+    }
+
+    fn get_log_interval(&self) -> Duration {
+        self.inner.get_log_interval()
+    }
+
+    fn get_item_name(&self) -> String {
+        self.inner.get_item_name()
+    }
+
+    fn get_expected_updates(&self) -> Option<usize> {
+        self.inner.get_expected_updates()
+    }
+
+    fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.inner.get_time_unit()
+    }
+
+    fn get_log_target(&self) -> String {
+        self.inner.get_log_target()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.inner.span()
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.inner.add_span(span);
+    }
+
+    fn refresh(&mut self) {
+        self.sync();
+        self.inner.refresh();
+    }
+
+    fn info(&self, args: Arguments<'_>) {
+        self.inner.info(args)
+    }
+}
+
+impl<P: ProgressLog + Display> Display for SharedMemoryLogger<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.inner.fmt(f)
+    }
+}
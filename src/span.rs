@@ -0,0 +1,162 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{Instant, TimeUnit};
+use std::fmt::{Display, Formatter, Result};
+use std::time::Duration;
+
+/// The span of an activity, with the spans of its nested activities.
+///
+/// Spans are returned by [`ProgressLog::span`](crate::ProgressLog::span).
+/// When a [`RollUp`](crate::RollUp) is done, the span of the child is added
+/// to the parent, so in a hierarchy of loggers the span of the outermost
+/// logger is a tree recording the elapsed time of every nested activity: a
+/// poor man's profiler for batch pipelines.
+///
+/// Spans of sibling activities with the same name (e.g., the phases of each
+/// iteration of a loop) are [merged](Span::merge) into a single span
+/// recording their number and total elapsed time, so the tree grows with the
+/// number of distinct activities, rather than with the number of activities.
+///
+/// The [`Display`] implementation shows the tree, one activity per line,
+/// with the elapsed time of each activity and its percentage of the elapsed
+/// time of the enclosing activity; the tree is logged by
+/// [`done`](crate::ProgressLog::done) if
+/// [requested](crate::ProgressLog::log_span_tree) and the span has children.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+///
+/// let mut parent = progress_logger![log_span_tree = true];
+/// parent.start("Building index...");
+/// for _ in 0..3 {
+///     for phase in ["Parsing", "Compressing"] {
+///         let mut pl = RollUp::new(progress_logger![], &mut parent);
+///         pl.start(format!("{}...", phase));
+///         pl.update_with_count(1000);
+///         pl.done();
+///     }
+/// }
+/// parent.done();
+///
+/// let span = parent.span().unwrap();
+/// assert_eq!(span.name, "Building index");
+/// let names: Vec<_> = span.children.iter().map(|child| child.name.as_str()).collect();
+/// assert_eq!(names, ["Parsing", "Compressing"]);
+/// assert_eq!(span.children[0].count, 3);
+/// assert!(span.children[0].elapsed() <= span.elapsed());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// The name of the activity, that is, the start message without trailing
+    /// dots.
+    pub name: String,
+    /// The start time of the activity, or of the first of the merged
+    /// activities.
+    pub start: Instant,
+    /// The stop time of the activity, or of the last of the merged
+    /// activities.
+    pub stop: Instant,
+    /// The number of merged activities.
+    pub count: usize,
+    /// The total elapsed time of the merged activities.
+    pub total: Duration,
+    /// The spans of the nested activities, in order of first completion.
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    /// Create the span of a single activity with no nested activities.
+    pub fn new(name: impl Into<String>, start: Instant, stop: Instant) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            stop,
+            count: 1,
+            total: stop.saturating_duration_since(start),
+            children: vec![],
+        }
+    }
+
+    /// Return the total elapsed time of the merged activities.
+    pub fn elapsed(&self) -> Duration {
+        self.total
+    }
+
+    /// Merge into this span the span of another activity with the same name.
+    ///
+    /// The counts and the total elapsed times are added, and the nested
+    /// activities are merged recursively by name.
+    pub fn merge(&mut self, other: Span) {
+        self.start = self.start.min(other.start);
+        self.stop = self.stop.max(other.stop);
+        self.count += other.count;
+        self.total += other.total;
+        for child in other.children {
+            self.add_child(child);
+        }
+    }
+
+    /// Add the span of a nested activity, merging it with the span of the
+    /// nested activity with the same name, if any.
+    pub fn add_child(&mut self, child: Span) {
+        add_span(&mut self.children, child);
+    }
+
+    /// Write the tree rooted at this span, indented by `depth` levels,
+    /// displaying the percentage of `parent_elapsed`, if given.
+    fn fmt_tree(
+        &self,
+        f: &mut Formatter<'_>,
+        depth: usize,
+        parent_elapsed: Option<Duration>,
+    ) -> Result {
+        let elapsed = self.elapsed();
+        write!(
+            f,
+            "{:indent$}{}",
+            "",
+            if self.name.is_empty() {
+                "Activity"
+            } else {
+                &self.name
+            },
+            indent = 2 * depth
+        )?;
+        if self.count > 1 {
+            write!(f, " ×{}", self.count)?;
+        }
+        write!(f, ": {}", TimeUnit::pretty_print(elapsed.as_millis()))?;
+        if let Some(parent_elapsed) = parent_elapsed.filter(|elapsed| !elapsed.is_zero()) {
+            write!(
+                f,
+                " ({:.2}%)",
+                100.0 * elapsed.as_secs_f64() / parent_elapsed.as_secs_f64()
+            )?;
+        }
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_tree(f, depth + 1, Some(elapsed))?;
+        }
+        Ok(())
+    }
+}
+
+/// Add `span` to `spans`, merging it with the span with the same name, if any.
+pub(crate) fn add_span(spans: &mut Vec<Span>, span: Span) {
+    match spans.iter_mut().find(|sibling| sibling.name == span.name) {
+        Some(sibling) => sibling.merge(span),
+        None => spans.push(span),
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.fmt_tree(f, 0, None)
+    }
+}
@@ -0,0 +1,265 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{clock, Instant, MemoryReadings, ProgressLogger, Readings, Rendered};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+#[cfg(test)]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+/// Deterministic values used by [`ProgressLogger::render`] in place of the
+/// current time and of system information.
+///
+/// Memory information, the free space, and the load average are displayed
+/// only if the logger is configured to display them. This structure is
+/// available only if the `test-utils` feature is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct RenderValues {
+    /// The time elapsed since the logger was started.
+    pub elapsed: Duration,
+    /// The time since the Unix epoch, used for
+    /// [timestamps](crate::ProgressLog::display_timestamp).
+    pub since_epoch: Duration,
+    /// The resident-set size of the process, in bytes.
+    pub res_mem: Option<u64>,
+    /// The virtual memory of the process, in bytes.
+    pub vir_mem: Option<u64>,
+    /// The available memory, in bytes.
+    pub available_mem: u64,
+    /// The free memory, in bytes.
+    pub free_mem: u64,
+    /// The total memory, in bytes.
+    pub total_mem: u64,
+    /// The used swap, in bytes.
+    pub used_swap: u64,
+    /// The total swap, in bytes.
+    pub total_swap: u64,
+    /// The available space on the monitored disk, in bytes.
+    pub free_space: u64,
+    /// The load average over 1, 5, and 15 minutes.
+    pub load_average: [f64; 3],
+}
+
+impl ProgressLogger {
+    /// Return the string that would be displayed by this logger if the given
+    /// values were the current time and system information.
+    ///
+    /// This method makes it possible to write snapshot tests of the output of
+    /// a logger. It is available only if the `test-utils` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dsi_progress_logger::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut pl = progress_logger![];
+    /// pl.start("Testing...");
+    /// pl.update_with_count(1000);
+    /// let values = RenderValues {
+    ///     elapsed: Duration::from_secs(10),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     pl.render(&values),
+    ///     "1,000 items, 10s, 100.00 items/s, 10.00 ms/item"
+    /// );
+    /// ```
+    pub fn render(&self, values: &RenderValues) -> String {
+        let readings = Readings {
+            now: self
+                .start_time
+                .map_or_else(clock::now, |start_time| start_time + values.elapsed),
+            elapsed: values.elapsed,
+            since_epoch: values.since_epoch,
+            memory: self.display_memory.then_some(MemoryReadings {
+                res: values.res_mem,
+                vir: values.vir_mem,
+                available: values.available_mem,
+                free: values.free_mem,
+                total: values.total_mem,
+                used_swap: values.used_swap,
+                total_swap: values.total_swap,
+            }),
+            free_space: self.free_space.as_ref().map(|_| values.free_space),
+            load_average: self.display_load_average.then_some(values.load_average),
+        };
+        Rendered(self, &readings).to_string()
+    }
+}
+
+/// The state of a [`MockClock`].
+#[derive(Debug)]
+struct MockState {
+    /// The real time at which the clock was installed.
+    start: Instant,
+    /// The virtual time elapsed since the clock was installed.
+    elapsed: Cell<Duration>,
+    /// The virtual times and counts of the logs emitted so far.
+    ticks: RefCell<Vec<(Duration, usize)>>,
+}
+
+thread_local! {
+    static MOCK_CLOCK: RefCell<Option<Rc<MockState>>> = const { RefCell::new(None) };
+}
+
+/// Return the time of the mock clock installed on the current thread, if any.
+pub(crate) fn mock_now() -> Option<Instant> {
+    MOCK_CLOCK.with_borrow(|state| {
+        state
+            .as_ref()
+            .map(|state| state.start + state.elapsed.get())
+    })
+}
+
+/// Record a log at `now` with the given count in the mock clock installed on
+/// the current thread, if any.
+pub(crate) fn record_tick(now: Instant, count: usize) {
+    MOCK_CLOCK.with_borrow(|state| {
+        if let Some(state) = state {
+            state.ticks.borrow_mut().push((now - state.start, count));
+        }
+    })
+}
+
+/// A virtual clock that tests can advance programmatically.
+///
+/// While a mock clock is installed on a thread, the loggers of this crate
+/// used on that thread read the time from the mock clock, which is advanced
+/// only by [`advance`](MockClock::advance), rather than from the system
+/// clock. Moreover, the mock clock records the progress logs emitted by
+/// [`ProgressLogger`] instances, which can be retrieved using
+/// [`ticks`](MockClock::ticks). In this way, logic depending on time (e.g.,
+/// log intervals or the estimated time to end) can be tested without sleeping.
+///
+/// The mock clock is uninstalled when dropped. Since it is thread-local,
+/// loggers used by other threads (e.g., through a [`ConcurrentWrapper`]) keep
+/// using the system clock. This structure is available only if the
+/// `test-utils` feature is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+/// use std::time::Duration;
+///
+/// let clock = MockClock::install();
+/// let mut pl = progress_logger![log_interval = Duration::from_secs(10)];
+/// pl.start("Testing...");
+/// for _ in 0..100 {
+///     clock.advance(Duration::from_secs(1));
+///     pl.update();
+/// }
+/// pl.done();
+/// let ticks = clock.ticks();
+/// assert_eq!(ticks.len(), 10);
+/// assert_eq!(ticks[0], (Duration::from_secs(10), 10));
+/// ```
+///
+/// [`ConcurrentWrapper`]: crate::ConcurrentWrapper
+#[derive(Debug)]
+pub struct MockClock(Rc<MockState>);
+
+impl MockClock {
+    /// Install a mock clock on the current thread.
+    ///
+    /// The virtual time starts at the current time.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if a mock clock is already installed on the current
+    /// thread.
+    pub fn install() -> Self {
+        let state = Rc::new(MockState {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::ZERO),
+            ticks: RefCell::new(vec![]),
+        });
+        MOCK_CLOCK.with_borrow_mut(|installed| {
+            assert!(
+                installed.is_none(),
+                "A mock clock is already installed on this thread"
+            );
+            *installed = Some(state.clone());
+        });
+        Self(state)
+    }
+
+    /// Advance the virtual time by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        self.0.elapsed.set(self.0.elapsed.get() + duration);
+    }
+
+    /// Return the virtual time elapsed since the clock was installed.
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed.get()
+    }
+
+    /// Return the progress logs emitted so far by [`ProgressLogger`]
+    /// instances, as pairs given by the virtual time elapsed since the clock
+    /// was installed and the count at the time of the log.
+    pub fn ticks(&self) -> Vec<(Duration, usize)> {
+        self.0.ticks.borrow().clone()
+    }
+
+    /// Forget the progress logs emitted so far.
+    pub fn clear_ticks(&self) {
+        self.0.ticks.borrow_mut().clear();
+    }
+}
+
+impl Drop for MockClock {
+    fn drop(&mut self) {
+        MOCK_CLOCK.with_borrow_mut(|installed| *installed = None);
+    }
+}
+
+/// A test fixture made of a mock clock installed on the current thread, a
+/// logger with a log interval of one second, and a flag set when the time
+/// budget of the logger is exceeded.
+#[cfg(test)]
+pub(crate) struct Fixture<P> {
+    /// The mock clock.
+    pub clock: MockClock,
+    /// The logger.
+    pub pl: P,
+    /// Whether the time budget of the logger has been exceeded.
+    exceeded: Arc<AtomicBool>,
+}
+
+#[cfg(test)]
+impl<P: crate::ProgressLog> Fixture<P> {
+    /// Install a mock clock and set up the given logger.
+    pub fn new(mut pl: P) -> Self {
+        let clock = MockClock::install();
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let flag = exceeded.clone();
+        pl.log_interval(Duration::from_secs(1))
+            .on_time_budget_exceeded(move || flag.store(true, Ordering::Relaxed));
+        Self {
+            clock,
+            pl,
+            exceeded,
+        }
+    }
+
+    /// Advance the clock by `step` and update the logger, `n` times.
+    pub fn run(&mut self, n: usize, step: Duration) {
+        for _ in 0..n {
+            self.clock.advance(step);
+            self.pl.update();
+        }
+    }
+
+    /// Return whether the time budget of the logger has been exceeded.
+    pub fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+}
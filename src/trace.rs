@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Chrome traces written at completion (see [`ProgressLog::trace_file`]).
+//!
+//! [`ProgressLog::trace_file`]: crate::ProgressLog::trace_file
+
+use crate::{clock, Instant, ProgressLogger, Span, SystemTime};
+use log::warn;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Return `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Write complete events for the spans of the nested activities, with
+/// timestamps relative to `start`, which corresponds to `start_time`.
+///
+/// Spans are written in the thread `tid` unless they overlap with a previous
+/// sibling, in which case they are written in a new thread, whose identifier
+/// is taken from `next_tid`, so that concurrent activities are displayed on
+/// separate tracks.
+fn write_spans(
+    trace: &mut String,
+    spans: &[Span],
+    start_time: Instant,
+    start: u128,
+    pid: u32,
+    tid: u32,
+    next_tid: &mut u32,
+) {
+    // The thread and the stop time of the last span of each track
+    let mut tracks: Vec<(u32, Instant)> = vec![];
+    for span in spans {
+        let span_tid = match tracks.iter_mut().find(|(_, stop)| *stop <= span.start) {
+            Some(track) => {
+                track.1 = span.stop;
+                track.0
+            }
+            None => {
+                let span_tid = if tracks.is_empty() {
+                    tid
+                } else {
+                    *next_tid += 1;
+                    *next_tid
+                };
+                tracks.push((span_tid, span.stop));
+                span_tid
+            }
+        };
+        let name = if span.count > 1 {
+            format!("{} ×{}", span.name, span.count)
+        } else {
+            span.name.clone()
+        };
+        let _ = write!(
+            trace,
+            ",\n{{\"name\":{},\"cat\":\"progress\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":{},\"tid\":{}}}",
+            json_string(&name),
+            start + span.start.saturating_duration_since(start_time).as_micros(),
+            span.stop.saturating_duration_since(span.start).as_micros(),
+            pid,
+            span_tid
+        );
+        write_spans(
+            trace,
+            &span.children,
+            start_time,
+            start,
+            pid,
+            span_tid,
+            next_tid,
+        );
+    }
+}
+
+impl ProgressLogger {
+    /// Write the Chrome trace of the activity, if a trace file is set.
+    pub(crate) fn write_trace(&self) {
+        let (Some(path), Some(start_time)) = (&self.trace_file, self.start_time) else {
+            return;
+        };
+        let now = clock::now();
+        // The start time in microseconds since the Unix epoch
+        let start = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(now - start_time)
+            .as_micros();
+        let duration = self.stop_time.unwrap_or(now) - start_time;
+        let (pid, tid) = (std::process::id(), 1);
+        let name = if self.start_msg.is_empty() {
+            "Activity"
+        } else {
+            &self.start_msg
+        };
+
+        let mut trace = String::from("{\"traceEvents\":[\n");
+        let _ = write!(
+            trace,
+            "{{\"name\":{},\"cat\":\"progress\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":{},\"tid\":{}}}",
+            json_string(name),
+            start,
+            duration.as_micros(),
+            pid,
+            tid
+        );
+        let mut next_tid = tid;
+        write_spans(
+            &mut trace,
+            &self.spans,
+            start_time,
+            start,
+            pid,
+            tid,
+            &mut next_tid,
+        );
+        let counter = json_string(&self.item_name_plural);
+        for &(elapsed, count) in [(Duration::ZERO, 0)]
+            .iter()
+            .chain(&self.history)
+            .chain([(duration, self.count)].iter())
+        {
+            let _ = write!(
+                trace,
+                ",\n{{\"name\":{},\"cat\":\"progress\",\"ph\":\"C\",\"ts\":{},\"pid\":{},\"tid\":{},\"args\":{{\"count\":{}}}}}",
+                counter,
+                start + elapsed.as_micros(),
+                pid,
+                tid,
+                count
+            );
+        }
+        trace.push_str("\n]}\n");
+
+        if let Err(e) = std::fs::write(path, trace) {
+            warn!(
+                target: &self.log_target,
+                "Cannot write trace file {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ProgressLog, ProgressUpdate, RollUp};
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_trace() {
+        let path = std::env::temp_dir().join("dsi_progress_logger_test_trace.json");
+        let mut pl = ProgressLogger::default();
+        pl.trace_file(Some(&path));
+        pl.start("Testing...");
+        pl.update_with_count(1000);
+        pl.done();
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(trace.starts_with("{\"traceEvents\":["));
+        assert!(trace.contains("\"name\":\"Testing...\",\"cat\":\"progress\",\"ph\":\"X\""));
+        assert!(trace.contains("\"args\":{\"count\":1000}"));
+    }
+
+    #[test]
+    fn test_trace_spans() {
+        let path = std::env::temp_dir().join("dsi_progress_logger_test_trace_spans.json");
+        let child_path = std::env::temp_dir().join("dsi_progress_logger_test_trace_child.json");
+        let mut pl = ProgressLogger::default();
+        pl.trace_file(Some(&path));
+        pl.start("Testing...");
+        let mut child = ProgressLogger::default();
+        child.trace_file(Some(&child_path));
+        let mut child = RollUp::new(child, &mut pl);
+        child.start("Child...");
+        child.done();
+        // Two overlapping activities
+        let now = clock::now();
+        pl.add_span(Span::new("First", now, now + Duration::from_secs(2)));
+        pl.add_span(Span::new(
+            "Second",
+            now + Duration::from_secs(1),
+            now + Duration::from_secs(3),
+        ));
+        pl.done();
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!child_path.exists());
+        assert!(trace.contains("\"name\":\"Child\""));
+        assert!(trace.contains("\"name\":\"First\""));
+        assert!(trace.contains("\"tid\":2"));
+    }
+}
@@ -0,0 +1,140 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::{humanize, Grouped, ProgressHandle, ProgressSnapshot, TimeUnit};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::{Gauge, Widget};
+
+/// A [`ratatui`] widget displaying the state of one or more loggers, one per
+/// line.
+///
+/// Each line contains a label, a progress bar with the percentage of
+/// completion (or the count, if the expected number of updates is not set),
+/// the average speed, and the estimated time to end. The state of the loggers
+/// is read from [snapshots](ProgressSnapshot) taken from their
+/// [handles](ProgressHandle), so the widget can be rendered by the thread
+/// driving the user interface without contending with the threads updating
+/// the loggers. This structure is available only if the `ratatui` feature is
+/// enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use dsi_progress_logger::prelude::*;
+/// use ratatui::buffer::Buffer;
+/// use ratatui::layout::Rect;
+/// use ratatui::widgets::Widget;
+///
+/// let mut pl = progress_logger![];
+/// let handle = pl.handle();
+/// pl.start_with_expected("Processing...", 1000);
+/// pl.update_with_count(500);
+///
+/// let area = Rect::new(0, 0, 60, 1);
+/// let mut buffer = Buffer::empty(area);
+/// ProgressWidget::new()
+///     .row("Processing", &handle)
+///     .render(area, &mut buffer);
+/// let line: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+/// assert!(line.starts_with("Processing "));
+/// assert!(line.contains("50.00%"));
+/// pl.done();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProgressWidget<'a> {
+    /// The labels and snapshots of the loggers to display.
+    rows: Vec<(&'a str, ProgressSnapshot)>,
+    /// The style of the progress bars.
+    gauge_style: Style,
+}
+
+impl<'a> ProgressWidget<'a> {
+    /// The width of the text following the progress bars.
+    const TEXT_WIDTH: u16 = 24;
+
+    /// Create an empty widget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line displaying a snapshot of the logger associated with the
+    /// given handle.
+    pub fn row(self, label: &'a str, handle: &ProgressHandle) -> Self {
+        self.snapshot_row(label, handle.snapshot())
+    }
+
+    /// Add a line displaying the given snapshot.
+    pub fn snapshot_row(mut self, label: &'a str, snapshot: ProgressSnapshot) -> Self {
+        self.rows.push((label, snapshot));
+        self
+    }
+
+    /// Set the style of the progress bars.
+    pub fn gauge_style(mut self, gauge_style: Style) -> Self {
+        self.gauge_style = gauge_style;
+        self
+    }
+}
+
+impl Widget for ProgressWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let label_width = self
+            .rows
+            .iter()
+            .map(|(label, _)| label.chars().count() as u16 + 1)
+            .max()
+            .unwrap_or(0)
+            .min(area.width);
+        for (i, (label, snapshot)) in self.rows.iter().enumerate().take(area.height as usize) {
+            let y = area.y + i as u16;
+            buf.set_stringn(area.x, y, label, label_width as usize, Style::default());
+
+            let text_width = Self::TEXT_WIDTH.min(area.width - label_width);
+            let bar = Rect::new(
+                area.x + label_width,
+                y,
+                area.width - label_width - text_width,
+                1,
+            );
+            match snapshot.fraction() {
+                Some(fraction) => Gauge::default()
+                    .ratio(fraction.clamp(0.0, 1.0))
+                    .label(format!("{:.2}%", 100.0 * fraction))
+                    .gauge_style(self.gauge_style)
+                    .render(bar, buf),
+                None => {
+                    buf.set_stringn(
+                        bar.x,
+                        y,
+                        Grouped(snapshot.count).to_string(),
+                        bar.width as usize,
+                        Style::default(),
+                    );
+                }
+            }
+
+            let mut text = String::new();
+            if let Some(items_per_second) = snapshot.items_per_second() {
+                text.push_str(&format!(" {}/s", humanize(items_per_second)));
+            }
+            if let Some(eta) = snapshot.eta().filter(|_| snapshot.running) {
+                text.push_str(&format!(
+                    ", {} to end",
+                    TimeUnit::pretty_print(eta.as_millis())
+                ));
+            }
+            buf.set_stringn(
+                bar.x + bar.width,
+                y,
+                text,
+                text_width as usize,
+                Style::default(),
+            );
+        }
+    }
+}
@@ -5,6 +5,54 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use num_format::{Buffer, Locale};
+use std::fmt::{self, Display, Formatter, Write};
+use std::time::Duration;
+
+/// A fixed-capacity string on the stack, used to format values without
+/// allocating.
+struct StackString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> StackString<N> {
+    fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // Only whole strings are written, so this cannot fail
+        std::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Write for StackString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Write using `write` into a buffer on the stack, and then pad the result
+/// using the width and alignment of `f`.
+fn pad_with(
+    f: &mut Formatter<'_>,
+    write: impl FnOnce(&mut StackString<64>) -> fmt::Result,
+) -> fmt::Result {
+    let mut s = StackString::new();
+    write(&mut s)?;
+    f.pad(s.as_str())
+}
+
 #[derive(Debug, Copy, Clone)]
 
 pub enum TimeUnit {
@@ -71,25 +119,171 @@ impl TimeUnit {
     }
 
     pub fn pretty_print(milliseconds: u128) -> String {
-        let mut result = String::new();
+        Pretty(milliseconds).to_string()
+    }
+}
 
-        if milliseconds < 1000 {
-            return format!("{}ms", milliseconds);
-        }
+/// Displays a number of milliseconds as [`TimeUnit::pretty_print`] would,
+/// without allocating.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pretty(pub u128);
+
+impl Display for Pretty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        pad_with(f, |result| {
+            let milliseconds = self.0;
+            if milliseconds < 1000 {
+                return write!(result, "{}ms", milliseconds);
+            }
 
-        let mut seconds = milliseconds / 1000;
+            let mut seconds = milliseconds / 1000;
 
-        for unit in [TimeUnit::Days, TimeUnit::Hours, TimeUnit::Minutes] {
-            let to_seconds = unit.as_seconds() as u128;
-            if seconds >= to_seconds {
-                result.push_str(&format!("{}{} ", seconds / to_seconds, unit.label(),));
-                seconds %= to_seconds;
+            for unit in [TimeUnit::Days, TimeUnit::Hours, TimeUnit::Minutes] {
+                let to_seconds = unit.as_seconds() as u128;
+                if seconds >= to_seconds {
+                    write!(result, "{}{} ", seconds / to_seconds, unit.label())?;
+                    seconds %= to_seconds;
+                }
             }
+
+            write!(result, "{}s", seconds)
+        })
+    }
+}
+
+/// Displays a number with thousands separators, without allocating.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Grouped(pub usize);
+
+impl Display for Grouped {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut buffer = Buffer::new();
+        buffer.write_formatted(&self.0, &Locale::en);
+        f.pad(buffer.as_str())
+    }
+}
+
+/// A format for elapsed times.
+///
+/// See [`ProgressLog::elapsed_format`](crate::ProgressLog::elapsed_format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElapsedFormat {
+    /// Days, hours, minutes, and seconds, or milliseconds for short times
+    /// (e.g., `1h 2m 3s`).
+    #[default]
+    Pretty,
+    /// Hours, minutes, and seconds (e.g., `01:02:03`).
+    Clock,
+    /// An ISO 8601 duration (e.g., `PT1H2M3.500S`).
+    Iso8601,
+    /// Seconds with millisecond precision (e.g., `3723.500`).
+    Seconds,
+}
+
+impl ElapsedFormat {
+    /// Format a duration.
+    pub fn format(&self, duration: Duration) -> String {
+        self.display(duration).to_string()
+    }
+
+    /// Return an object displaying a duration in this format, without
+    /// allocating.
+    pub(crate) fn display(self, duration: Duration) -> Elapsed {
+        Elapsed(self, duration)
+    }
+}
+
+/// Displays a duration in a given format, without allocating.
+///
+/// See [`ElapsedFormat::display`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Elapsed(ElapsedFormat, Duration);
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let millis = self.1.as_millis();
+        let seconds = millis / 1000;
+        match self.0 {
+            ElapsedFormat::Pretty => Pretty(millis).fmt(f),
+            ElapsedFormat::Clock => pad_with(f, |result| {
+                write!(
+                    result,
+                    "{:02}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }),
+            ElapsedFormat::Iso8601 => pad_with(f, |result| {
+                result.write_str("PT")?;
+                if seconds >= 3600 {
+                    write!(result, "{}H", seconds / 3600)?;
+                }
+                if seconds >= 60 {
+                    write!(result, "{}M", seconds / 60 % 60)?;
+                }
+                write!(result, "{}.{:03}S", seconds % 60, millis % 1000)
+            }),
+            ElapsedFormat::Seconds => pad_with(f, |result| {
+                write!(result, "{}.{:03}", seconds, millis % 1000)
+            }),
         }
+    }
+}
 
-        result.push_str(&format!("{}s", seconds));
+/// Format a time since the Unix epoch as an RFC 3339 UTC timestamp with
+/// millisecond precision (e.g., `2025-01-31T12:34:56.789Z`).
+pub(crate) fn rfc3339(since_epoch: Duration) -> String {
+    let seconds = since_epoch.as_secs();
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+    // Civil date from the number of days since the epoch, following Howard
+    // Hinnant's algorithm
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        since_epoch.subsec_millis()
+    )
+}
 
-        result
+/// Return the English plural form of a noun using simple rules, which work
+/// for most technical terms.
+///
+/// Nouns ending in `s`, but not in `ss` or `us`, are assumed to be already in
+/// plural form (e.g., `bytes`), except for nouns ending in `is`, whose
+/// plural form ends in `es` (e.g., `axis` → `axes`).
+pub(crate) fn plural(noun: &str) -> String {
+    let is_vowel = |c: char| "aeiou".contains(c.to_ascii_lowercase());
+    if let Some(stem) = noun.strip_suffix("is") {
+        format!("{}es", stem)
+    } else if ["ss", "us", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| noun.ends_with(suffix))
+    {
+        format!("{}es", noun)
+    } else if noun.is_empty() || noun.ends_with('s') {
+        noun.to_owned()
+    } else if let Some(stem) = noun
+        .strip_suffix('y')
+        .filter(|stem| stem.chars().last().is_some_and(|c| !is_vowel(c)))
+    {
+        format!("{}ies", stem)
+    } else {
+        format!("{}s", noun)
     }
 }
 
@@ -106,10 +300,279 @@ pub fn scale(mut val: f64) -> (f64, &'static str) {
 }
 
 pub fn humanize(val: f64) -> String {
-    let (val, unit) = scale(val);
-    format!("{:.2}{}", val, unit)
+    Humanized::new(val, false, "").to_string()
+}
+
+pub fn scale_binary(mut val: f64) -> (f64, &'static str) {
+    const UNITS: &[&str] = &["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"];
+    for unit in UNITS.iter() {
+        if val < 1024.0 {
+            return (val, unit);
+        }
+        val /= 1024.0;
+    }
+
+    (val, "Yi")
+}
+
+pub fn humanize_binary(val: f64) -> String {
+    Humanized::new(val, true, "").to_string()
+}
+
+/// Return a log target derived from the path of a source file, as returned by
+/// [`Location::file`](std::panic::Location::file), or `None` if the path is
+/// not inside a `src` directory of a crate.
+///
+/// The target is made of the name of the crate, that is, the name of the
+/// directory containing `src` without version suffix, followed by the modules
+/// corresponding to the path (e.g., `/path/mycrate-0.1.0/src/sort/mod.rs`
+/// becomes `mycrate::sort`), approximating [`module_path!`]. For git
+/// dependencies checked out by cargo (e.g.,
+/// `/path/checkouts/mycrate-<hash>/<revision>/src/lib.rs`), the name of the
+/// checked-out repository without hash is used instead. The name of the crate
+/// of a binary in `src/bin` is that of the binary. If the path starts with
+/// `src`, as it happens for the root package of a workspace, the crate name is
+/// not known, and the name of the executable returned by `executable_name`
+/// (without the hash appended by cargo to test executables) is used.
+pub(crate) fn caller_target(
+    file: &str,
+    executable_name: impl FnOnce() -> String,
+) -> Option<String> {
+    let components: Vec<&str> = file
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    let src = components
+        .iter()
+        .rposition(|&component| component == "src")?;
+    let mut modules: Vec<&str> = components[src + 1..].to_vec();
+    let last = modules.pop()?.strip_suffix(".rs")?;
+    if last != "mod" {
+        modules.push(last);
+    }
+    let crate_name = if modules.first() == Some(&"bin") && modules.len() > 1 {
+        modules.remove(0);
+        modules.remove(0).to_owned()
+    } else {
+        if matches!(modules.as_slice(), ["lib"] | ["main"]) {
+            modules.clear();
+        }
+        match src.checked_sub(1) {
+            Some(dir) => {
+                if dir >= 2 && components[dir - 2] == "checkouts" {
+                    // Strip the hash of repositories checked out by cargo
+                    strip_hash(components[dir - 1]).to_owned()
+                } else {
+                    // Strip the version, possibly a pre-release one, of
+                    // crates in the registry
+                    let dir = components[dir];
+                    dir.match_indices('-')
+                        .find(|&(pos, _)| dir[pos + 1..].starts_with(|c: char| c.is_ascii_digit()))
+                        .map_or(dir, |(pos, _)| &dir[..pos])
+                        .to_owned()
+                }
+            }
+            None => {
+                let name = executable_name();
+                let name = name.strip_suffix(".exe").unwrap_or(&name);
+                // Strip the hash of test executables built by cargo
+                strip_hash(name).to_owned()
+            }
+        }
+    };
+    Some(
+        std::iter::once(crate_name.replace('-', "_").as_str())
+            .chain(modules)
+            .collect::<Vec<_>>()
+            .join("::"),
+    )
+}
+
+/// Strip from `name` a suffix made of a dash followed by a 16-digit
+/// hexadecimal hash, as appended by cargo.
+fn strip_hash(name: &str) -> &str {
+    match name.rsplit_once('-') {
+        Some((name, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            name
+        }
+        _ => name,
+    }
 }
 
+/// Return the name of the current executable, or `main` if it is not
+/// available.
+pub(crate) fn executable_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .and_then(|s| s.to_owned().into_string().ok())
+        })
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// Return a sparkline of the given values, that is, a string of Unicode block
+/// elements whose heights are proportional to the values, scaled between the
+/// minimum and the maximum value.
+///
+/// If there are more than `width` values, they are divided into `width`
+/// consecutive groups and each group is represented by its maximum, so that
+/// spikes are not hidden.
+pub(crate) fn sparkline(values: &[f64], width: usize) -> String {
+    let width = width.min(values.len());
+    let groups = (0..width).map(|i| {
+        values[i * values.len() / width..(i + 1) * values.len() / width]
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max)
+    });
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    groups
+        .map(|value| sparkline_block(value, min, max))
+        .collect()
+}
+
+/// Write a sparkline of the given values, one block per value, as
+/// [`sparkline`] would, but without allocating.
+pub(crate) fn write_sparkline(
+    f: &mut impl std::fmt::Write,
+    values: impl Iterator<Item = f64> + Clone,
+) -> std::fmt::Result {
+    let (min, max) = values
+        .clone()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    for value in values {
+        f.write_char(sparkline_block(value, min, max))?;
+    }
+    Ok(())
+}
+
+/// Return the block representing `value` in a sparkline scaled between `min`
+/// and `max`.
+fn sparkline_block(value: f64, min: f64, max: f64) -> char {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    if max > min {
+        BLOCKS[(((value - min) / (max - min)) * 7.0).round() as usize]
+    } else {
+        BLOCKS[0]
+    }
+}
+
+/// A fixed number of buckets, each holding the maximum of a group of
+/// consecutive samples.
+///
+/// When all buckets are full, adjacent buckets are merged and the number of
+/// samples per bucket doubles, so the buckets always cover all the samples
+/// pushed, in constant space.
+#[derive(Debug, Clone)]
+pub(crate) struct Downsampled {
+    /// The maximum of each group of samples.
+    buckets: Vec<u64>,
+    /// The maximum number of buckets.
+    width: usize,
+    /// The number of samples per bucket.
+    stride: usize,
+    /// The number of samples in the last bucket.
+    pending: usize,
+    /// The minimum of the samples.
+    min: Option<u64>,
+}
+
+impl Downsampled {
+    /// Create an empty structure with at most `width` buckets.
+    pub(crate) fn new(width: usize) -> Self {
+        Self {
+            buckets: Vec::with_capacity(width),
+            width: width.max(1),
+            stride: 1,
+            pending: 0,
+            min: None,
+        }
+    }
+
+    /// Add a sample.
+    pub(crate) fn push(&mut self, value: u64) {
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        if self.pending == self.stride && self.buckets.len() == self.width {
+            for i in 0..self.width.div_ceil(2) {
+                self.buckets[i] =
+                    self.buckets[2 * i].max(*self.buckets.get(2 * i + 1).unwrap_or(&0));
+            }
+            self.buckets.truncate(self.width.div_ceil(2));
+            // The last bucket is a single old bucket if the width is odd
+            self.pending = self.stride * (2 - self.width % 2);
+            self.stride *= 2;
+        }
+        match self.buckets.last_mut() {
+            Some(last) if self.pending < self.stride => {
+                *last = (*last).max(value);
+                self.pending += 1;
+            }
+            _ => {
+                self.buckets.push(value);
+                self.pending = 1;
+            }
+        }
+    }
+
+    /// Remove all samples.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new(self.width);
+    }
+
+    /// Return the maximum of each group of samples.
+    pub(crate) fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    /// Return the minimum of the samples, if any.
+    pub(crate) fn min(&self) -> Option<u64> {
+        self.min
+    }
+
+    /// Return the maximum of the samples, if any.
+    pub(crate) fn max(&self) -> Option<u64> {
+        self.buckets.iter().copied().max()
+    }
+}
+
+/// Displays a value using SI or binary unit prefixes, followed by a suffix,
+/// as [`humanize`] or [`humanize_binary`] would, without allocating.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Humanized {
+    value: f64,
+    binary: bool,
+    suffix: &'static str,
+}
+
+impl Humanized {
+    pub(crate) fn new(value: f64, binary: bool, suffix: &'static str) -> Self {
+        Self {
+            value,
+            binary,
+            suffix,
+        }
+    }
+}
+
+impl Display for Humanized {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (val, unit) = if self.binary {
+            scale_binary(self.value)
+        } else {
+            scale(self.value)
+        };
+        pad_with(f, |result| {
+            write!(result, "{:.2}{}{}", val, unit, self.suffix)
+        })
+    }
+}
+
+// The tests separate attributes from items with blank lines
+#[allow(clippy::empty_line_after_outer_attr)]
 #[cfg(test)]
 
 mod test {
@@ -127,4 +590,126 @@ mod test {
         assert_eq!(humanize(12_345.0), "12.35k");
         assert_eq!(humanize(1_234_567_890.0), "1.23G");
     }
+    #[test]
+    fn test_elapsed_format() {
+        let duration = Duration::from_millis(3_723_500);
+        assert_eq!(ElapsedFormat::Pretty.format(duration), "1h 2m 3s");
+        assert_eq!(ElapsedFormat::Clock.format(duration), "01:02:03");
+        assert_eq!(ElapsedFormat::Iso8601.format(duration), "PT1H2M3.500S");
+        assert_eq!(ElapsedFormat::Seconds.format(duration), "3723.500");
+    }
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(Duration::ZERO), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            rfc3339(Duration::from_millis(1_738_326_896_789)),
+            "2025-01-31T12:34:56.789Z"
+        );
+    }
+    #[test]
+    fn test_padding() {
+        assert_eq!(format!("{:>8}", Pretty(61_000)), "   1m 1s");
+        assert_eq!(format!("{:>8}", Grouped(12_345)), "  12,345");
+        assert_eq!(
+            format!("{:>8}", Humanized::new(1536.0, true, "B")),
+            " 1.50KiB"
+        );
+    }
+    #[test]
+    fn test_plural() {
+        assert_eq!(plural("item"), "items");
+        assert_eq!(plural("node"), "nodes");
+        assert_eq!(plural("byte"), "bytes");
+        assert_eq!(plural("bytes"), "bytes");
+        assert_eq!(plural("entry"), "entries");
+        assert_eq!(plural("key"), "keys");
+        assert_eq!(plural("index"), "indexes");
+        assert_eq!(plural("batch"), "batches");
+        assert_eq!(plural("status"), "statuses");
+        assert_eq!(plural("axis"), "axes");
+        assert_eq!(plural(""), "");
+    }
+    #[test]
+    fn test_humanize_binary() {
+        assert_eq!(humanize_binary(1000.0), "1000.00");
+        assert_eq!(humanize_binary(1536.0), "1.50Ki");
+        assert_eq!(humanize_binary((1 << 30) as f64), "1.00Gi");
+    }
+    #[test]
+    fn test_caller_target() {
+        let exe = || "mycrate-0123456789abcdef".to_string();
+        assert_eq!(
+            caller_target(
+                "/home/u/.cargo/registry/src/index/my-crate-0.1.0/src/sort/mod.rs",
+                exe
+            ),
+            Some("my_crate::sort".to_string())
+        );
+        assert_eq!(
+            caller_target(
+                "/home/u/.cargo/registry/src/index/my-crate-1.0.0-rc.1/src/lib.rs",
+                exe
+            ),
+            Some("my_crate".to_string())
+        );
+        assert_eq!(
+            caller_target(
+                "/home/u/.cargo/git/checkouts/my-crate-0123456789abcdef/a1b2c3d/src/sort.rs",
+                exe
+            ),
+            Some("my_crate::sort".to_string())
+        );
+        assert_eq!(
+            caller_target("mycrate/src/sort/write.rs", exe),
+            Some("mycrate::sort::write".to_string())
+        );
+        assert_eq!(
+            caller_target("mycrate/src/lib.rs", exe),
+            Some("mycrate".to_string())
+        );
+        assert_eq!(
+            caller_target("mycrate/src/bin/tool.rs", exe),
+            Some("tool".to_string())
+        );
+        assert_eq!(
+            caller_target("src/sort.rs", exe),
+            Some("mycrate::sort".to_string())
+        );
+        assert_eq!(
+            caller_target("src/sort.rs", || "tool.exe".to_string()),
+            Some("tool::sort".to_string())
+        );
+        assert_eq!(caller_target("examples/demo.rs", exe), None);
+    }
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[1.0, 1.0], 10), "▁▁");
+        assert_eq!(sparkline(&[0.0, 7.0, 3.0, 5.0], 10), "▁█▄▆");
+        // Groups are represented by their maximum
+        assert_eq!(sparkline(&[0.0, 0.0, 0.0, 7.0, 1.0, 0.0], 3), "▁█▂");
+        let mut s = String::new();
+        write_sparkline(&mut s, [0.0, 7.0, 3.0, 5.0].into_iter()).unwrap();
+        assert_eq!(s, "▁█▄▆");
+    }
+    #[test]
+    fn test_downsampled() {
+        let mut downsampled = Downsampled::new(4);
+        for value in 0..4 {
+            downsampled.push(value);
+        }
+        assert_eq!(downsampled.buckets(), [0, 1, 2, 3]);
+        downsampled.push(4);
+        assert_eq!(downsampled.buckets(), [1, 3, 4]);
+        for value in 5..16 {
+            downsampled.push(value);
+        }
+        assert_eq!(downsampled.buckets(), [3, 7, 11, 15]);
+        assert_eq!((downsampled.min(), downsampled.max()), (Some(0), Some(15)));
+        let mut downsampled = Downsampled::new(3);
+        for value in 0..9 {
+            downsampled.push(value);
+        }
+        assert_eq!(downsampled.buckets(), [3, 7, 8]);
+    }
 }
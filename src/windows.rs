@@ -0,0 +1,75 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Memory information on Windows.
+//!
+//! [`sysinfo`] reports as virtual memory of a process either its private
+//! bytes or the size of its address space, depending on how processes are
+//! refreshed, and as swap the commit charge minus the physical memory. These
+//! functions read directly the quantities displayed by the Task Manager.
+
+use std::mem::{size_of, zeroed};
+use windows_sys::Win32::System::ProcessStatus::{
+    GetPerformanceInfo, GetProcessMemoryInfo, PERFORMANCE_INFORMATION, PROCESS_MEMORY_COUNTERS,
+    PROCESS_MEMORY_COUNTERS_EX,
+};
+use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+/// Return the working set and the private bytes of the current process.
+pub(crate) fn process_memory() -> Option<(u64, u64)> {
+    // SAFETY: the structure is plain data, and its size is passed to the call
+    unsafe {
+        let mut counters: PROCESS_MEMORY_COUNTERS_EX = zeroed();
+        counters.cb = size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32;
+        if GetProcessMemoryInfo(
+            GetCurrentProcess(),
+            &mut counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+            counters.cb,
+        ) == 0
+        {
+            return None;
+        }
+        Some((counters.WorkingSetSize as u64, counters.PrivateUsage as u64))
+    }
+}
+
+/// Return the commit charge and the commit limit of the system.
+pub(crate) fn commit() -> Option<(u64, u64)> {
+    // SAFETY: the structure is plain data, and its size is passed to the call
+    unsafe {
+        let mut info: PERFORMANCE_INFORMATION = zeroed();
+        info.cb = size_of::<PERFORMANCE_INFORMATION>() as u32;
+        if GetPerformanceInfo(&mut info, info.cb) == 0 {
+            return None;
+        }
+        Some((
+            info.CommitTotal.saturating_mul(info.PageSize) as u64,
+            info.CommitLimit.saturating_mul(info.PageSize) as u64,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_process_memory() {
+        let (working_set, private_bytes) = process_memory().unwrap();
+        assert!(working_set > 0);
+        assert!(private_bytes > 0);
+    }
+
+    #[test]
+    fn test_commit() {
+        let (commit_charge, commit_limit) = commit().unwrap();
+        assert!(commit_charge > 0);
+        assert!(commit_charge <= commit_limit);
+        // The commit charge includes the private bytes of this process
+        let (_, private_bytes) = process_memory().unwrap();
+        assert!(private_bytes <= commit_charge);
+    }
+}